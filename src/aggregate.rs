@@ -0,0 +1,54 @@
+use crate::file::account::{Account, Amount};
+use crate::file::transaction::Transaction;
+
+/// Running aggregates kept over the summary amounts and transactions of a
+/// file as it's read, such as totals per cost center taken from the text,
+/// so that a large file doesn't need to be held in memory or read twice. See
+/// `Bai2Reader::aggregate`.
+///
+/// ```
+/// use bai2::{Account, Aggregator, Bai2Reader, Transaction};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Default)]
+/// struct CostCenters(BTreeMap<String, i64>);
+///
+/// impl Aggregator for CostCenters {
+///     fn transaction(&mut self, _account: &Account, transaction: &Transaction) {
+///         let cost_center = transaction.text().split(' ').next().unwrap_or_default();
+///         let amount = transaction.signed_minor_units().map_or(0, |a| a.value());
+///         *self.0.entry(cost_center.to_string()).or_default() += amount;
+///     }
+/// }
+///
+/// # let content = "01,GSBI,ABC,200331,2300,1,,,2/
+/// # 02,ABC,GSBI,1,200331,,USD,2/
+/// # 03,123,USD,010,500,,/
+/// # 16,108,100,0,,,CC42 Deposit
+/// # 49,600,3/
+/// # 98,600,1,5/
+/// # 99,600,1,7/";
+/// let mut totals = CostCenters::default();
+/// Bai2Reader::new(content.as_bytes()).aggregate(&mut totals)?;
+/// assert_eq!(totals.0["CC42"], 100);
+/// # Ok::<(), bai2::Bai2Error>(())
+/// ```
+pub trait Aggregator {
+    /// Called for each summary amount in an account identifier, before the
+    /// account's transactions.
+    fn summary(&mut self, _account: &Account, _amount: &Amount) {}
+
+    /// Called for each transaction detail, in the order of the file.
+    fn transaction(&mut self, _account: &Account, _transaction: &Transaction) {}
+
+    /// Pass the account's summary amounts and then its transactions to the
+    /// aggregator, for accounts read some other way.
+    fn account(&mut self, account: &Account) {
+        for amount in account.amounts() {
+            self.summary(account, amount);
+        }
+        for transaction in account.transactions() {
+            self.transaction(account, transaction);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use arrow_array::types::Date32Type;
+use arrow_array::{
+    ArrayRef, Date32Array, Int64Array, RecordBatch, StringArray, UInt16Array, UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use crate::file::account::AmountType;
+use crate::Bai2File;
+
+/// Convert every transaction in the file to a row of a record batch, with the
+/// group and account it belongs to alongside it. Amounts are in minor units as
+/// they appear in the file, with the currency code to scale them by.
+pub(crate) fn transactions(file: &Bai2File) -> Result<RecordBatch, ArrowError> {
+    let mut group_sequence_number = Vec::new();
+    let mut account_sequence_number = Vec::new();
+    let mut sequence_number = Vec::new();
+    let mut as_of_date = Vec::new();
+    let mut value_date = Vec::new();
+    let mut account_number = Vec::new();
+    let mut currency_code = Vec::new();
+    let mut type_code = Vec::new();
+    let mut direction = Vec::new();
+    let mut amount = Vec::new();
+    let mut decimal_amount = Vec::new();
+    let mut bank_reference_number = Vec::new();
+    let mut customer_reference_number = Vec::new();
+    let mut text = Vec::new();
+
+    for group in &file.groups {
+        for account in &group.accounts {
+            for transaction in &account.transactions {
+                group_sequence_number.push(group.sequence_number as u64);
+                account_sequence_number.push(account.sequence_number as u64);
+                sequence_number.push(transaction.sequence_number as u64);
+                as_of_date.push(days(group.as_of_date));
+                value_date.push(days(transaction.value_date));
+                account_number.push(account.customer_account_number.clone());
+                currency_code.push(transaction.currency_code.to_string());
+                type_code.push(transaction.transaction_type.code());
+                direction.push(transaction.transaction_type.direction().name());
+                amount.push(transaction.amount);
+                decimal_amount.push(transaction.decimal_amount.clone());
+                bank_reference_number.push(transaction.bank_reference_number.clone());
+                customer_reference_number.push(transaction.customer_reference_number.clone());
+                text.push(transaction.text.clone());
+            }
+        }
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("group_sequence_number", DataType::UInt64, false),
+        Field::new("account_sequence_number", DataType::UInt64, false),
+        Field::new("sequence_number", DataType::UInt64, false),
+        Field::new("as_of_date", DataType::Date32, true),
+        Field::new("value_date", DataType::Date32, true),
+        Field::new("account_number", DataType::Utf8, false),
+        Field::new("currency_code", DataType::Utf8, false),
+        Field::new("type_code", DataType::UInt16, false),
+        Field::new("direction", DataType::Utf8, false),
+        Field::new("amount", DataType::UInt64, true),
+        Field::new("decimal_amount", DataType::Utf8, true),
+        Field::new("bank_reference_number", DataType::Utf8, false),
+        Field::new("customer_reference_number", DataType::Utf8, false),
+        Field::new("text", DataType::Utf8, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(group_sequence_number)),
+        Arc::new(UInt64Array::from(account_sequence_number)),
+        Arc::new(UInt64Array::from(sequence_number)),
+        Arc::new(Date32Array::from(as_of_date)),
+        Arc::new(Date32Array::from(value_date)),
+        Arc::new(StringArray::from(account_number)),
+        Arc::new(StringArray::from(currency_code)),
+        Arc::new(UInt16Array::from(type_code)),
+        Arc::new(StringArray::from(direction)),
+        Arc::new(UInt64Array::from(amount)),
+        Arc::new(StringArray::from(decimal_amount)),
+        Arc::new(StringArray::from(bank_reference_number)),
+        Arc::new(StringArray::from(customer_reference_number)),
+        Arc::new(StringArray::from(text)),
+    ];
+
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Convert every balance and summary in the account identifiers of the file to
+/// a row of a record batch, with the group and account they belong to.
+pub(crate) fn amounts(file: &Bai2File) -> Result<RecordBatch, ArrowError> {
+    let mut group_sequence_number = Vec::new();
+    let mut account_sequence_number = Vec::new();
+    let mut as_of_date = Vec::new();
+    let mut account_number = Vec::new();
+    let mut currency_code = Vec::new();
+    let mut type_code = Vec::new();
+    let mut amount_type = Vec::new();
+    let mut amount = Vec::new();
+    let mut decimal_amount = Vec::new();
+    let mut item_count = Vec::new();
+
+    for group in &file.groups {
+        for account in &group.accounts {
+            for summary in &account.amounts {
+                group_sequence_number.push(group.sequence_number as u64);
+                account_sequence_number.push(account.sequence_number as u64);
+                as_of_date.push(days(group.as_of_date));
+                account_number.push(account.customer_account_number.clone());
+                currency_code.push(account.currency_code.to_string());
+                type_code.push(summary.amount_type.code());
+                amount_type.push(match summary.amount_type {
+                    AmountType::Status(..) => "status",
+                    AmountType::CreditSummary(..) => "credit_summary",
+                    AmountType::DebitSummary(..) => "debit_summary",
+                    AmountType::Unknown(..) => "unknown",
+                });
+                amount.push(summary.amount);
+                decimal_amount.push(summary.decimal_amount.clone());
+                item_count.push(summary.item_count);
+            }
+        }
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("group_sequence_number", DataType::UInt64, false),
+        Field::new("account_sequence_number", DataType::UInt64, false),
+        Field::new("as_of_date", DataType::Date32, true),
+        Field::new("account_number", DataType::Utf8, false),
+        Field::new("currency_code", DataType::Utf8, false),
+        Field::new("type_code", DataType::UInt16, false),
+        Field::new("amount_type", DataType::Utf8, false),
+        Field::new("amount", DataType::Int64, true),
+        Field::new("decimal_amount", DataType::Utf8, true),
+        Field::new("item_count", DataType::UInt16, true),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(group_sequence_number)),
+        Arc::new(UInt64Array::from(account_sequence_number)),
+        Arc::new(Date32Array::from(as_of_date)),
+        Arc::new(StringArray::from(account_number)),
+        Arc::new(StringArray::from(currency_code)),
+        Arc::new(UInt16Array::from(type_code)),
+        Arc::new(StringArray::from(amount_type)),
+        Arc::new(Int64Array::from(amount)),
+        Arc::new(StringArray::from(decimal_amount)),
+        Arc::new(UInt16Array::from(item_count)),
+    ];
+
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Write a record batch to a Parquet file.
+#[cfg(feature = "parquet")]
+pub(crate) fn write_parquet<W: std::io::Write + Send>(
+    batch: &RecordBatch,
+    writer: W,
+) -> Result<(), parquet::errors::ParquetError> {
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+fn days(date: Option<NaiveDate>) -> Option<i32> {
+    date.map(Date32Type::from_naive_date)
+}
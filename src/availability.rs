@@ -0,0 +1,120 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use std::collections::{BTreeSet, HashSet};
+
+/// Dates that banks are closed on other than weekends, such as public
+/// holidays. Implemented for sets and slices of dates.
+pub trait HolidayCalendar {
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+}
+
+impl HolidayCalendar for BTreeSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for HashSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for [NaiveDate] {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for Vec<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+/// The days a bank is open. The default is every day other than Saturday and
+/// Sunday, which is what `Weekends` uses.
+pub trait Calendar {
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// The date the given number of business days after the date. Zero days is
+    /// the date itself, even when it isn't a business day.
+    fn add_business_days(&self, date: NaiveDate, days: u16) -> Option<NaiveDate> {
+        let mut result = date;
+        for _ in 0..days {
+            result = result.succ_opt()?;
+            while !self.is_business_day(result) {
+                result = result.succ_opt()?;
+            }
+        }
+
+        Some(result)
+    }
+}
+
+/// A calendar where banks are open every weekday.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Weekends;
+
+impl Calendar for Weekends {}
+
+/// Works out the date funds become available from a date and the number of
+/// days of availability reported for them.
+///
+/// By default days are calendar days. Skipping weekends or giving a holiday
+/// calendar counts only the days the bank is open instead, so funds available
+/// in one day from a Friday become available on Monday.
+#[derive(Clone, Copy, Default)]
+pub struct AvailabilityDate<'a> {
+    holidays: Option<&'a dyn HolidayCalendar>,
+    skip_weekends: bool,
+}
+
+impl<'a> AvailabilityDate<'a> {
+    pub fn new() -> AvailabilityDate<'a> {
+        AvailabilityDate::default()
+    }
+
+    /// Don't count Saturdays and Sundays.
+    pub fn skip_weekends(mut self) -> AvailabilityDate<'a> {
+        self.skip_weekends = true;
+        self
+    }
+
+    /// Don't count the holidays in the calendar.
+    pub fn holidays(mut self, holidays: &'a dyn HolidayCalendar) -> AvailabilityDate<'a> {
+        self.holidays = Some(holidays);
+        self
+    }
+
+    /// The date funds become available when they're available after the given
+    /// number of days from the date. Same-day funds (0 days) are available on
+    /// the date itself.
+    pub fn resolve(&self, date: NaiveDate, days: u16) -> Option<NaiveDate> {
+        if self.holidays.is_none() && !self.skip_weekends {
+            return date.checked_add_days(Days::new(days as u64));
+        }
+
+        self.add_business_days(date, days)
+    }
+}
+
+impl Calendar for AvailabilityDate<'_> {
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        if self.skip_weekends && !Weekends.is_business_day(date) {
+            return false;
+        }
+
+        !self.holidays.is_some_and(|h| h.is_holiday(date))
+    }
+}
+
+impl std::fmt::Debug for AvailabilityDate<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AvailabilityDate")
+            .field("holidays", &self.holidays.is_some())
+            .field("skip_weekends", &self.skip_weekends)
+            .finish()
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::NaiveDate;
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::error::Bai2Error;
+use crate::file::account::{AmountFields, AmountType};
+use crate::file::funds_type::FundsType;
+use crate::file::group::Group;
+use crate::file::time::Time;
+use crate::file::transaction::TransactionFields;
+use crate::file::transaction_type::TransactionType;
+use crate::file::util::{parse_amount, parse_date, parse_int, parse_str, parse_text, parse_time};
+use crate::file::version;
+use crate::options::ParseOptions;
+use crate::scanner::node::{Node, Tree};
+
+/// A view of a parsed file that borrows its text from the parser instead of
+/// copying every field into a string of its own, for pipelines that parse,
+/// filter, and discard files without keeping them. Fields are only copied
+/// when there's a slash in them to remove, and the text of a transaction
+/// isn't put together until it's asked for, and is only copied then when
+/// it's continued. See `Bai2Parser::parse_borrowed`.
+///
+/// Only the structure of the file and the fields it holds are checked. The
+/// control totals and counts in the trailers aren't, and neither sorting nor
+/// amounts in decimal form are available; use `Bai2File` for those.
+#[derive(Debug)]
+pub struct Bai2FileRef<'a> {
+    pub creation_date: Option<NaiveDate>,
+    pub creation_time: Option<Time>,
+    pub file_id: Cow<'a, str>,
+    pub groups: Vec<GroupRef<'a>>,
+    pub receiver: Cow<'a, str>,
+    pub sender: Cow<'a, str>,
+}
+
+#[derive(Debug)]
+pub struct GroupRef<'a> {
+    pub accounts: Vec<AccountRef<'a>>,
+    pub as_of_date: Option<NaiveDate>,
+    pub as_of_time: Option<Time>,
+    pub originator: Cow<'a, str>,
+    pub ultimate_receiver: Cow<'a, str>,
+}
+
+#[derive(Debug)]
+pub struct AccountRef<'a> {
+    pub amounts: Vec<AmountRef>,
+    /// The currency of the account, or of its group when it doesn't have one,
+    /// or the default currency when neither does.
+    pub currency_code: Cow<'a, str>,
+    pub customer_account_number: Cow<'a, str>,
+    pub transactions: Vec<TransactionRef<'a>>,
+}
+
+#[derive(Debug)]
+pub struct AmountRef {
+    pub amount: Option<i64>,
+    pub amount_type: AmountType,
+    pub funds_type: FundsType,
+    pub item_count: Option<u16>,
+}
+
+#[derive(Debug)]
+pub struct TransactionRef<'a> {
+    pub amount: Option<u64>,
+    pub bank_reference_number: Cow<'a, str>,
+    pub customer_reference_number: Cow<'a, str>,
+    pub funds_type: FundsType,
+    pub text: TextRef<'a>,
+    pub transaction_type: TransactionType,
+    pub value_date: Option<NaiveDate>,
+    pub value_time: Option<Time>,
+}
+
+impl<'a> Bai2FileRef<'a> {
+    pub(crate) fn from_tree(
+        tree: &'a Tree,
+        options: &ParseOptions,
+    ) -> Result<Bai2FileRef<'a>, Bai2Error> {
+        let root = tree.root();
+        let options = &*version::with_version(options, &root);
+        let header_fields = root
+            .required_fields(9, options.allow_short_records)
+            .map_err(|(line, field)| Bai2Error::InvalidFileHeader { line, field })?;
+        check_trailer(&root, options, |line| Bai2Error::FileTrailerMissing {
+            line,
+        })?;
+
+        let groups = root
+            .children()
+            .map(|n| GroupRef::from_node(&n, options))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Bai2FileRef {
+            creation_date: parse_date(header_fields[3], options),
+            creation_time: parse_time(header_fields[4], options),
+            file_id: parse_str(header_fields[5]),
+            groups,
+            receiver: parse_str(header_fields[2]),
+            sender: parse_str(header_fields[1]),
+        })
+    }
+}
+
+impl<'a> GroupRef<'a> {
+    fn from_node(node: &Node<'a>, options: &ParseOptions) -> Result<GroupRef<'a>, Bai2Error> {
+        let header_fields = Group::header_fields(node, options)?;
+        check_trailer(node, options, |line| Bai2Error::GroupTrailerMissing {
+            line,
+        })?;
+
+        let group_currency = parse_str(header_fields[6]);
+        let accounts = node
+            .children()
+            .map(|n| AccountRef::from_node(&n, &group_currency, options))
+            .collect::<Result<_, _>>()?;
+
+        Ok(GroupRef {
+            accounts,
+            as_of_date: parse_date(header_fields[4], options),
+            as_of_time: parse_time(header_fields[5], options),
+            originator: parse_str(header_fields[2]),
+            ultimate_receiver: parse_str(header_fields[1]),
+        })
+    }
+}
+
+impl<'a> AccountRef<'a> {
+    fn from_node(
+        node: &Node<'a>,
+        group_currency: &Cow<'a, str>,
+        options: &ParseOptions,
+    ) -> Result<AccountRef<'a>, Bai2Error> {
+        let header_fields = node
+            .required_fields(7, options.allow_short_records)
+            .map_err(|(line, field)| Bai2Error::InvalidAccountHeader { line, field })?;
+        check_trailer(node, options, |line| Bai2Error::AccountTrailerMissing {
+            line,
+        })?;
+
+        let currency_code = match parse_str(header_fields[2]) {
+            currency if !currency.is_empty() => currency,
+            _ if !group_currency.is_empty() => group_currency.clone(),
+            _ => Cow::Owned(options.default_currency.clone()),
+        };
+
+        let amounts = AmountFields::split(&header_fields[3..], options)
+            .map_err(|(index, detail)| {
+                let (line, field) = node.field_position(index + 3);
+                Bai2Error::InvalidAccountSummary {
+                    line,
+                    field,
+                    detail,
+                }
+            })?
+            .into_iter()
+            .map(|fields| AmountRef {
+                amount: parse_amount(fields.amount),
+                amount_type: fields.amount_type,
+                funds_type: fields.funds.funds_type,
+                item_count: parse_int(fields.item_count),
+            })
+            .collect();
+
+        let transactions = node
+            .children()
+            .map(|n| {
+                let fields = TransactionFields::from_node(&n, options)?;
+                Ok(TransactionRef {
+                    amount: parse_amount(fields.amount),
+                    bank_reference_number: parse_str(fields.bank_reference_number),
+                    customer_reference_number: parse_str(fields.customer_reference_number),
+                    funds_type: fields.funds.funds_type,
+                    text: TextRef {
+                        join_continued: options.join_continued_text,
+                        node: n,
+                        start: fields.text_start,
+                    },
+                    transaction_type: fields.transaction_type,
+                    value_date: fields.funds.value_date,
+                    value_time: fields.funds.value_time,
+                })
+            })
+            .collect::<Result<_, Bai2Error>>()?;
+
+        Ok(AccountRef {
+            amounts,
+            currency_code,
+            customer_account_number: parse_str(header_fields[1]),
+            transactions,
+        })
+    }
+}
+
+/// The text of a transaction in a `Bai2FileRef`, which is only put together
+/// from its record when it's asked for, since it has to be copied when it's
+/// continued onto more lines and most pipelines never look at it.
+///
+/// ```
+/// use bai2::{Bai2Parser, ParseOptions};
+/// use std::borrow::Cow;
+///
+/// let content = "01,GSBI,ABC,200331,2300,1,,,2/
+/// 02,ABC,GSBI,1,200331,,USD,2/
+/// 03,123,USD,010,500,,/
+/// 16,108,100,0,,,INVOICE 03/31
+/// 16,108,200,0,,,PAYROLL 03/31, ACME INC
+/// 88,MORE TEXT/
+/// 49,800,5/
+/// 98,800,1,7/
+/// 99,800,1,9/";
+/// let mut parser = Bai2Parser::new(ParseOptions::default());
+/// let file = parser.parse_borrowed(content)?;
+/// let transactions = &file.groups[0].accounts[0].transactions;
+///
+/// let text = transactions[0].text.get();
+/// assert!(matches!(text, Cow::Borrowed("INVOICE 03/31")));
+///
+/// let text = transactions[1].text.get();
+/// assert!(matches!(text, Cow::Owned(_)));
+/// assert_eq!(text, "PAYROLL 03/31, ACME INC,MORE TEXT");
+/// # Ok::<(), bai2::Bai2Error>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct TextRef<'a> {
+    join_continued: bool,
+    node: Node<'a>,
+    start: usize,
+}
+
+impl<'a> TextRef<'a> {
+    /// The text, borrowed from the parser unless it's continued.
+    pub fn get(&self) -> Cow<'a, str> {
+        parse_text(self.node.text_from(self.start, self.join_continued))
+    }
+}
+
+impl fmt::Debug for TextRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
+
+impl fmt::Display for TextRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.get())
+    }
+}
+
+/// Make sure the record was closed by its trailer, unless missing trailers are
+/// allowed.
+fn check_trailer(
+    node: &Node,
+    options: &ParseOptions,
+    missing: impl FnOnce(usize) -> Bai2Error,
+) -> Result<(), Bai2Error> {
+    if node.sibling().is_none() && !options.allow_missing_trailers {
+        return Err(missing(node.line_number()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use chrono::{NaiveDate, NaiveTime};
+use std::collections::BTreeMap;
+
+use crate::file::account::{Account, Amount, AmountType, CurrencySource};
+use crate::file::category::Category;
+use crate::file::currency::{Currency, CurrencyExponents};
+use crate::file::funds_type::FundsType;
+use crate::file::group::{AsOfDateModifier, Group, GroupStatus};
+use crate::file::time::Time;
+use crate::file::transaction::Transaction;
+use crate::file::transaction_type::TransactionType;
+use crate::options::ParseOptions;
+use crate::stats::FileStats;
+use crate::{Bai2File, SCHEMA_VERSION};
+
+/// Builds a file from scratch, e.g. to simulate a bank's feed when testing.
+/// Write it out with `Bai2File::to_bai2`.
+#[derive(Debug)]
+pub struct FileBuilder {
+    creation_date: Option<NaiveDate>,
+    creation_time: Option<NaiveTime>,
+    currency_exponents: CurrencyExponents,
+    file_id: String,
+    groups: Vec<GroupBuilder>,
+    receiver: String,
+    sender: String,
+}
+
+impl FileBuilder {
+    pub fn new(sender: &str, receiver: &str, file_id: &str) -> FileBuilder {
+        FileBuilder {
+            creation_date: None,
+            creation_time: None,
+            currency_exponents: CurrencyExponents::default(),
+            file_id: file_id.to_string(),
+            groups: Vec::new(),
+            receiver: receiver.to_string(),
+            sender: sender.to_string(),
+        }
+    }
+
+    pub fn created_at(mut self, date: NaiveDate, time: NaiveTime) -> FileBuilder {
+        self.creation_date = Some(date);
+        self.creation_time = Some(time);
+        self
+    }
+
+    /// The exponents used for the decimal amounts of the accounts and
+    /// transactions, as with `ParseOptions::currency_exponents`.
+    pub fn currency_exponents(mut self, exponents: CurrencyExponents) -> FileBuilder {
+        self.currency_exponents = exponents;
+        self
+    }
+
+    pub fn group(mut self, group: GroupBuilder) -> FileBuilder {
+        self.groups.push(group);
+        self
+    }
+
+    pub fn build(self) -> Bai2File {
+        let mut accounts_before = 0;
+        let groups = self
+            .groups
+            .into_iter()
+            .enumerate()
+            .map(|(i, g)| {
+                let group = g.build(i + 1, accounts_before, &self.currency_exponents);
+                accounts_before += group.accounts.len();
+                group
+            })
+            .collect();
+
+        Bai2File {
+            block_size: None,
+            control_total: None,
+            creation_date: self.creation_date,
+            creation_time: self.creation_time.map(Time::At),
+            file_id: self.file_id,
+            groups,
+            original: None,
+            other_records: Vec::new(),
+            physical_record_length: None,
+            provenance: None,
+            raw: None,
+            receiver: self.receiver,
+            schema_version: SCHEMA_VERSION,
+            sender: self.sender,
+            skipped_groups: Vec::new(),
+            stats: FileStats::default(),
+            validation_failures: Vec::new(),
+            version_number: Some(2),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GroupBuilder {
+    accounts: Vec<AccountBuilder>,
+    as_of_date: NaiveDate,
+    as_of_date_modifier: Option<AsOfDateModifier>,
+    as_of_time: Option<NaiveTime>,
+    currency_code: Option<String>,
+    originator: String,
+    status: GroupStatus,
+    ultimate_receiver: String,
+}
+
+impl GroupBuilder {
+    pub fn new(originator: &str, ultimate_receiver: &str, as_of_date: NaiveDate) -> GroupBuilder {
+        GroupBuilder {
+            accounts: Vec::new(),
+            as_of_date,
+            as_of_date_modifier: None,
+            as_of_time: None,
+            currency_code: None,
+            originator: originator.to_string(),
+            status: GroupStatus::parse("1"),
+            ultimate_receiver: ultimate_receiver.to_string(),
+        }
+    }
+
+    /// A group for an interim report on the as-of date, like the ones banks
+    /// send throughout the day. Its accounts usually report current balances,
+    /// such as current ledger (030) and current available (060), along with
+    /// the transactions so far.
+    pub fn intraday(
+        originator: &str,
+        ultimate_receiver: &str,
+        as_of_date: NaiveDate,
+        as_of_time: NaiveTime,
+    ) -> GroupBuilder {
+        GroupBuilder::new(originator, ultimate_receiver, as_of_date)
+            .as_of_time(as_of_time)
+            .as_of_date_modifier(AsOfDateModifier::InterimSameDayData)
+    }
+
+    pub fn account(mut self, account: AccountBuilder) -> GroupBuilder {
+        self.accounts.push(account);
+        self
+    }
+
+    pub fn as_of_date_modifier(mut self, modifier: AsOfDateModifier) -> GroupBuilder {
+        self.as_of_date_modifier = Some(modifier);
+        self
+    }
+
+    pub fn as_of_time(mut self, time: NaiveTime) -> GroupBuilder {
+        self.as_of_time = Some(time);
+        self
+    }
+
+    pub fn currency(mut self, currency_code: &str) -> GroupBuilder {
+        self.currency_code = Some(currency_code.to_string());
+        self
+    }
+
+    pub fn status(mut self, status: GroupStatus) -> GroupBuilder {
+        self.status = status;
+        self
+    }
+
+    fn build(
+        self,
+        sequence_number: usize,
+        accounts_before: usize,
+        exponents: &CurrencyExponents,
+    ) -> Group {
+        let group_currency = self.currency_code.as_deref();
+        let accounts = self
+            .accounts
+            .into_iter()
+            .enumerate()
+            .map(|(i, a)| {
+                a.build(
+                    accounts_before + i + 1,
+                    group_currency,
+                    self.as_of_date,
+                    exponents,
+                )
+            })
+            .collect();
+
+        Group {
+            accounts,
+            as_of_date: Some(self.as_of_date),
+            as_of_date_modifier: self.as_of_date_modifier,
+            as_of_time: self.as_of_time.map(Time::At),
+            control_total: None,
+            currency_code: Currency::parse(
+                &self
+                    .currency_code
+                    .unwrap_or_else(|| ParseOptions::default().default_currency),
+            ),
+            originator: self.originator,
+            original: None,
+            other_records: Vec::new(),
+            raw: None,
+            sequence_number,
+            skipped_accounts: Vec::new(),
+            status: self.status,
+            ultimate_receiver: self.ultimate_receiver,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AccountBuilder {
+    amounts: Vec<(u16, i64, Option<u16>)>,
+    currency_code: Option<String>,
+    customer_account_number: String,
+    transactions: Vec<(u16, u64, String, String, String)>,
+}
+
+impl AccountBuilder {
+    pub fn new(customer_account_number: &str) -> AccountBuilder {
+        AccountBuilder {
+            amounts: Vec::new(),
+            currency_code: None,
+            customer_account_number: customer_account_number.to_string(),
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Report a balance, such as current ledger (030), or a summary amount
+    /// without an item count.
+    pub fn amount(mut self, type_code: u16, amount: i64) -> AccountBuilder {
+        self.amounts.push((type_code, amount, None));
+        self
+    }
+
+    pub fn currency(mut self, currency_code: &str) -> AccountBuilder {
+        self.currency_code = Some(currency_code.to_string());
+        self
+    }
+
+    /// Report a summary amount along with the number of items it totals.
+    pub fn summary(mut self, type_code: u16, amount: i64, item_count: u16) -> AccountBuilder {
+        self.amounts.push((type_code, amount, Some(item_count)));
+        self
+    }
+
+    pub fn transaction(
+        mut self,
+        type_code: u16,
+        amount: u64,
+        bank_reference_number: &str,
+        customer_reference_number: &str,
+        text: &str,
+    ) -> AccountBuilder {
+        self.transactions.push((
+            type_code,
+            amount,
+            bank_reference_number.to_string(),
+            customer_reference_number.to_string(),
+            text.to_string(),
+        ));
+        self
+    }
+
+    fn build(
+        self,
+        sequence_number: usize,
+        group_currency: Option<&str>,
+        as_of_date: NaiveDate,
+        exponents: &CurrencyExponents,
+    ) -> Account {
+        let (currency_code, currency_source) = match (self.currency_code, group_currency) {
+            (Some(currency), _) => (Currency::parse(&currency), CurrencySource::Account),
+            (None, Some(currency)) => (Currency::parse(currency), CurrencySource::Group),
+            (None, None) => (
+                Currency::parse(&ParseOptions::default().default_currency),
+                CurrencySource::Default,
+            ),
+        };
+
+        let amounts = self
+            .amounts
+            .into_iter()
+            .map(|(type_code, amount, item_count)| Amount {
+                amount_type: AmountType::from_code(type_code),
+                amount: Some(amount),
+                availability: BTreeMap::new(),
+                category: Category::from_code(&format!("{:03}", type_code)),
+                decimal_amount: Some(exponents.to_decimal(amount, currency_code.code())),
+                funds_type: FundsType::Unknown,
+                item_count,
+                value_date: None,
+                value_time: None,
+            })
+            .collect();
+
+        let transactions = self
+            .transactions
+            .into_iter()
+            .enumerate()
+            .map(
+                |(i, (type_code, amount, bank_reference, customer_reference, text))| Transaction {
+                    amount: Some(amount),
+                    as_of_date: Some(as_of_date),
+                    availability: BTreeMap::new(),
+                    bank_reference_number: bank_reference,
+                    category: Category::from_code(&format!("{:03}", type_code)),
+                    currency_code: currency_code.clone(),
+                    customer_reference_number: customer_reference,
+                    custom_category: None,
+                    decimal_amount: i64::try_from(amount)
+                        .ok()
+                        .map(|amount| exponents.to_decimal(amount, currency_code.code())),
+                    funds_type: FundsType::Unknown,
+                    original: None,
+                    raw: None,
+                    sequence_number: i + 1,
+                    text: text.trim().to_string(),
+                    transaction_type: TransactionType::from_code(type_code),
+                    value_date: None,
+                    value_time: None,
+                },
+            )
+            .collect();
+
+        Account {
+            amounts,
+            control_total: None,
+            currency_code,
+            currency_source,
+            customer_account_number: self.customer_account_number,
+            original: None,
+            other_records: Vec::new(),
+            raw: None,
+            sequence_number,
+            transactions,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use serde_json::Value;
+
+/// Compare the parsed output against an expected snapshot, returning a line for
+/// each difference found. Provenance is ignored since it changes on every run.
+pub fn diff(expected: &Value, actual: &Value) -> Vec<String> {
+    let mut differences = Vec::new();
+    diff_values("$", expected, actual, &mut differences);
+    differences
+}
+
+fn diff_values(path: &str, expected: &Value, actual: &Value, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected {
+                if key == "provenance" {
+                    continue;
+                }
+
+                let key_path = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        diff_values(&key_path, expected_value, actual_value, differences)
+                    }
+                    None => differences.push(format!("{}: missing from output", key_path)),
+                }
+            }
+
+            for key in actual.keys() {
+                if key != "provenance" && !expected.contains_key(key) {
+                    differences.push(format!("{}.{}: not in snapshot", path, key));
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.len() != actual.len() {
+                differences.push(format!(
+                    "{}: expected {} items, found {}",
+                    path,
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+
+            for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+                diff_values(&format!("{}[{}]", path, i), e, a, differences);
+            }
+        }
+        (expected, actual) if expected != actual => {
+            differences.push(format!("{}: expected {}, found {}", path, expected, actual));
+        }
+        _ => (),
+    }
+}
@@ -0,0 +1,51 @@
+use bai2::{CodeLevel, CodeTable, Direction};
+
+const HEADERS: [&str; 4] = ["CODE", "LEVEL", "DIRECTION", "DESCRIPTION"];
+
+/// Lay out every type code as a row of columns, with a header row first and
+/// the ranges of custom codes last. Balances have no direction, so it's left
+/// blank for them.
+pub fn table(codes: &CodeTable) -> String {
+    let direction = |level, direction: Direction| match level {
+        CodeLevel::Status => String::new(),
+        _ => direction.to_string(),
+    };
+
+    let mut rows = vec![HEADERS.map(str::to_string)];
+    rows.extend(codes.codes.iter().map(|code| {
+        [
+            format!("{:03}", code.code),
+            code.level.to_string(),
+            direction(code.level, code.direction),
+            code.description.clone(),
+        ]
+    }));
+    rows.extend(codes.custom_ranges.iter().map(|range| {
+        [
+            format!("{:03}-{:03}", range.start, range.end),
+            range.level.to_string(),
+            direction(range.level, range.direction),
+            "Custom".to_string(),
+        ]
+    }));
+
+    let mut widths = [0; HEADERS.len()];
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for row in rows {
+        let columns: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(column, width)| format!("{:<width$}", column))
+            .collect();
+        output.push_str(columns.join("  ").trim_end());
+        output.push('\n');
+    }
+
+    output
+}
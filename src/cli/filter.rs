@@ -0,0 +1,141 @@
+use bai2::{Account, Bai2File, Direction, Transaction};
+use chrono::NaiveDate;
+use clap::{Args, ValueEnum};
+use std::cmp::Ordering;
+
+/// Flags that narrow the transactions down to the ones that match all of them.
+#[derive(Debug, Args)]
+pub struct TransactionFilter {
+    /// only include transactions in this account (repeatable)
+    #[arg(long = "account", value_name = "NUMBER")]
+    accounts: Vec<String>,
+
+    /// only include transactions with this type code (repeatable)
+    #[arg(long = "type-code", value_name = "CODE")]
+    type_codes: Vec<u16>,
+
+    /// only include credits or debits
+    #[arg(long)]
+    direction: Option<DirectionArg>,
+
+    /// only include transactions of at least this amount, e.g. 100.00
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_decimal)]
+    min_amount: Option<Decimal>,
+
+    /// only include transactions of at most this amount, e.g. 100.00
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_decimal)]
+    max_amount: Option<Decimal>,
+
+    /// only include transactions that take effect on this date (YYYY-MM-DD)
+    #[arg(long)]
+    date: Option<NaiveDate>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum DirectionArg {
+    Credit,
+    Debit,
+}
+
+impl From<DirectionArg> for Direction {
+    fn from(direction: DirectionArg) -> Direction {
+        match direction {
+            DirectionArg::Credit => Direction::Credit,
+            DirectionArg::Debit => Direction::Debit,
+        }
+    }
+}
+
+/// A decimal amount as its whole and fractional digits, without leading or
+/// trailing zeros, so that amounts of any length and in different currencies
+/// can be compared exactly.
+#[derive(Clone, Debug)]
+struct Decimal {
+    fraction: String,
+    whole: String,
+}
+
+impl Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        self.whole
+            .len()
+            .cmp(&other.whole.len())
+            .then_with(|| self.whole.cmp(&other.whole))
+            .then_with(|| self.fraction.cmp(&other.fraction))
+    }
+}
+
+impl TransactionFilter {
+    /// Remove the transactions that don't match from the file.
+    pub fn apply(&self, file: &mut Bai2File) {
+        file.retain_transactions(|account, transaction| self.matches(account, transaction));
+    }
+
+    fn matches(&self, account: &Account, transaction: &Transaction) -> bool {
+        if !self.accounts.is_empty()
+            && !self
+                .accounts
+                .iter()
+                .any(|a| a == account.customer_account_number())
+        {
+            return false;
+        }
+
+        let transaction_type = transaction.transaction_type();
+        if !self.type_codes.is_empty() && !self.type_codes.contains(&transaction_type.code()) {
+            return false;
+        }
+
+        if self
+            .direction
+            .is_some_and(|d| Direction::from(d) != transaction_type.direction())
+        {
+            return false;
+        }
+
+        if self.min_amount.is_some() || self.max_amount.is_some() {
+            let Some(amount) = transaction
+                .decimal_amount()
+                .and_then(|a| parse_decimal(a).ok())
+            else {
+                return false;
+            };
+            if self
+                .min_amount
+                .as_ref()
+                .is_some_and(|min| amount.cmp(min).is_lt())
+            {
+                return false;
+            }
+            if self
+                .max_amount
+                .as_ref()
+                .is_some_and(|max| amount.cmp(max).is_gt())
+            {
+                return false;
+            }
+        }
+
+        if self.date.is_some() && self.date != transaction.effective_date() {
+            return false;
+        }
+
+        true
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, String> {
+    let error = || format!("`{}` isn't an amount like 100.00", value);
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error());
+    }
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error());
+    }
+
+    Ok(Decimal {
+        fraction: fraction.trim_end_matches('0').to_string(),
+        whole: whole.trim_start_matches('0').to_string(),
+    })
+}
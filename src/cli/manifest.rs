@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A record of the files converted by a batch run, with enough about each
+/// input to tell whether it needs converting again on the next run.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Manifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    /// Why the file couldn't be parsed, when it couldn't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The SHA-256 hash of the raw bytes of the input, when it could be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    pub input: PathBuf,
+    /// Where the JSON for the file was written, when it could be parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    pub status: Status,
+    /// The number of validation failures reported as warnings.
+    pub warnings: usize,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Failed,
+    Parsed,
+}
+
+impl Manifest {
+    /// Read the manifest of an earlier run, or an empty one if there wasn't
+    /// one.
+    pub fn load(path: &Path) -> Result<Manifest, String> {
+        if !path.exists() {
+            return Ok(Manifest::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|_| format!("could not read manifest `{}`", path.display()))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("could not parse manifest `{}`: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json + "\n")
+            .map_err(|_| format!("could not write manifest `{}`", path.display()))
+    }
+
+    /// Add the entries of an earlier run for inputs that weren't part of this
+    /// one, so that the manifest covers everything converted so far.
+    pub fn keep_others(&mut self, previous: Manifest) {
+        let others: Vec<ManifestEntry> = previous
+            .files
+            .into_iter()
+            .filter(|entry| !self.files.iter().any(|e| e.input == entry.input))
+            .collect();
+        self.files.extend(others);
+    }
+
+    /// The entry for an input from an earlier run that doesn't need to be
+    /// converted again: it was parsed from the same bytes and its output is
+    /// still there.
+    pub fn unchanged(&self, input: &Path, hash: &str) -> Option<&ManifestEntry> {
+        self.files.iter().find(|entry| {
+            entry.input == input
+                && entry.hash.as_deref() == Some(hash)
+                && entry.status == Status::Parsed
+                && entry.output.as_deref().is_some_and(Path::exists)
+        })
+    }
+
+    /// Whether this run has already written JSON to `output`.
+    pub fn has_output(&self, output: &Path) -> bool {
+        self.files
+            .iter()
+            .any(|entry| entry.output.as_deref() == Some(output))
+    }
+
+    /// Whether an earlier run wrote JSON to `output` for an input other than
+    /// `input`, whose entry will be kept.
+    pub fn has_other_output(&self, output: &Path, input: &Path) -> bool {
+        self.files
+            .iter()
+            .any(|entry| entry.input != input && entry.output.as_deref() == Some(output))
+    }
+}
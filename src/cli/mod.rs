@@ -0,0 +1,8 @@
+pub mod check;
+pub mod codes;
+pub mod filter;
+pub mod manifest;
+pub mod output;
+pub mod report;
+pub mod source;
+pub mod transactions;
@@ -0,0 +1,89 @@
+/// Print the output, compressed with gzip when `gzip` is set, which needs the
+/// `gzip` feature.
+pub fn print(output: &str, gzip: bool) -> Result<(), String> {
+    if !gzip {
+        println!("{}", output);
+        return Ok(());
+    }
+
+    print_gzip(output)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn print_gzip(_output: &str) -> Result<(), String> {
+    Err("--gzip requires the `gzip` feature".to_string())
+}
+
+#[cfg(feature = "gzip")]
+fn print_gzip(output: &str) -> Result<(), String> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::{self, Write};
+
+    let mut encoder = GzEncoder::new(io::stdout().lock(), Compression::default());
+    encoder
+        .write_all(output.as_bytes())
+        .and_then(|_| encoder.write_all(b"\n"))
+        .and_then(|_| encoder.finish()?.flush())
+        .map_err(|e| format!("could not write output: {}", e))
+}
+
+/// POST the output as JSON to `url`, retrying up to `retries` times with
+/// backoff when the request fails or the server returns a 5xx status, which
+/// needs the `webhook` feature. When `BAI2_WEBHOOK_SECRET` is set, the body is
+/// signed with it in an `X-Bai2-Signature` header of the form
+/// `sha256=<hex HMAC-SHA256 of the body>`.
+#[cfg(not(feature = "webhook"))]
+pub fn post(_output: &str, _url: &str, _retries: u32) -> Result<(), String> {
+    Err("--post-to requires the `webhook` feature".to_string())
+}
+
+#[cfg(feature = "webhook")]
+pub fn post(output: &str, url: &str, retries: u32) -> Result<(), String> {
+    use hmac::{Hmac, KeyInit, Mac};
+    use sha2::Sha256;
+    use std::{env, thread, time::Duration};
+
+    let signature = env::var("BAI2_WEBHOOK_SECRET").ok().map(|secret| {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+        mac.update(output.as_bytes());
+        let digest: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("sha256={}", digest)
+    });
+
+    let mut last_error = String::new();
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            thread::sleep(Duration::from_secs(1 << (attempt - 1).min(6)));
+        }
+
+        let mut request = ureq::post(url).header("Content-Type", "application/json");
+        if let Some(signature) = &signature {
+            request = request.header("X-Bai2-Signature", signature);
+        }
+
+        match request.send(output) {
+            Ok(_) => return Ok(()),
+            Err(ureq::Error::StatusCode(status)) if status < 500 => {
+                return Err(format!(
+                    "`{}` rejected the output with status {}",
+                    url, status
+                ));
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+
+    Err(format!(
+        "could not post to `{}` after {} attempts: {}",
+        url,
+        retries + 1,
+        last_error
+    ))
+}
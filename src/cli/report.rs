@@ -0,0 +1,124 @@
+use bai2::{Account, Bai2File, CurrencyExponents, Direction, Group, MinorUnits};
+
+const HEADERS: [&str; 13] = [
+    "DATE",
+    "ACCOUNT",
+    "CURRENCY",
+    "OPENING LEDGER",
+    "CLOSING LEDGER",
+    "OPENING AVAILABLE",
+    "CLOSING AVAILABLE",
+    "CREDITS",
+    "TOTAL CREDITS",
+    "DEBITS",
+    "TOTAL DEBITS",
+    "RECORDS",
+    "LINES",
+];
+
+/// The columns that hold numbers, which line up on the right.
+const NUMBER_COLUMNS: std::ops::Range<usize> = 3..13;
+
+const OPENING_LEDGER: u16 = 10;
+const CLOSING_LEDGER: u16 = 15;
+const OPENING_AVAILABLE: u16 = 40;
+const CLOSING_AVAILABLE: u16 = 45;
+
+/// Lay out a summary of every account as a row of columns, with a header row
+/// first. Balances are the ones reported in the account identifier and are
+/// left blank when the bank didn't send them, while the credit and debit
+/// counts and totals are worked out from the transactions. Each account's
+/// logical records and the lines they take up, counting continuations, come
+/// last, since it's the lines that the bank counts in the account trailer.
+/// Totals are scaled with the same exponents as the file's amounts.
+pub fn table(file: &Bai2File, exponents: &CurrencyExponents) -> String {
+    let mut rows = vec![HEADERS.map(str::to_string)];
+    for group in &file.groups {
+        rows.extend(
+            group
+                .accounts()
+                .iter()
+                .map(|account| row(file, group, account, exponents)),
+        );
+    }
+
+    let mut widths = [0; HEADERS.len()];
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for row in rows {
+        let columns: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (column, width))| {
+                if NUMBER_COLUMNS.contains(&i) {
+                    format!("{:>width$}", column)
+                } else {
+                    format!("{:<width$}", column)
+                }
+            })
+            .collect();
+        output.push_str(columns.join("  ").trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+fn row(
+    file: &Bai2File,
+    group: &Group,
+    account: &Account,
+    exponents: &CurrencyExponents,
+) -> [String; 13] {
+    let balance = |code: u16| {
+        account
+            .summary(code)
+            .and_then(|amount| amount.decimal_amount())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let total = |direction: Direction| {
+        let amounts = account
+            .transactions()
+            .iter()
+            .filter(|t| t.transaction_type().direction() == direction)
+            .map(|t| t.minor_units().unwrap_or_default());
+        match MinorUnits::checked_sum(amounts) {
+            Some(total) => total.to_decimal_with(account.currency(), exponents),
+            None => "overflow".to_string(),
+        }
+    };
+    let count = |direction: Direction| {
+        account
+            .transactions()
+            .iter()
+            .filter(|t| t.transaction_type().direction() == direction)
+            .count()
+    };
+    let stats = file.stats.accounts.get(account.sequence_number() - 1);
+
+    [
+        group
+            .as_of_date()
+            .map(|d| d.to_string())
+            .unwrap_or_default(),
+        account.customer_account_number().to_string(),
+        account.currency_code().to_string(),
+        balance(OPENING_LEDGER),
+        balance(CLOSING_LEDGER),
+        balance(OPENING_AVAILABLE),
+        balance(CLOSING_AVAILABLE),
+        count(Direction::Credit).to_string(),
+        total(Direction::Credit),
+        count(Direction::Debit).to_string(),
+        total(Direction::Debit),
+        stats.map(|s| s.records.to_string()).unwrap_or_default(),
+        stats.map(|s| s.lines.to_string()).unwrap_or_default(),
+    ]
+}
@@ -0,0 +1,209 @@
+use std::{fs, path::Path};
+
+/// Read the raw bytes of a BAI2 file from a local path, or from `s3://` and
+/// `sftp://` URLs when the matching features are enabled.
+pub fn read(location: &Path) -> Result<Vec<u8>, String> {
+    let location_str = location.to_string_lossy();
+
+    if let Some(rest) = location_str.strip_prefix("s3://") {
+        return read_s3(rest);
+    }
+
+    if let Some(rest) = location_str.strip_prefix("sftp://") {
+        return read_sftp(rest);
+    }
+
+    fs::read(location).map_err(|_| format!("could not read file `{}`", location_str))
+}
+
+/// The location with the password of an `sftp://` URL left out, so that it
+/// can be shown in messages and output.
+pub fn redacted(location: &Path) -> String {
+    let location = location.to_string_lossy();
+    match location.strip_prefix("sftp://") {
+        Some(rest) => format!("sftp://{}", redact_sftp(rest)),
+        None => location.into_owned(),
+    }
+}
+
+/// `user:password@host/path` as `user@host/path`.
+fn redact_sftp(location: &str) -> String {
+    let (authority, path) = location.split_at(location.find('/').unwrap_or(location.len()));
+    match authority.rsplit_once('@') {
+        Some((credentials, address)) => {
+            let user = credentials
+                .split_once(':')
+                .map_or(credentials, |(user, _)| user);
+            format!("{}@{}{}", user, address, path)
+        }
+        None => location.to_string(),
+    }
+}
+
+#[cfg(not(feature = "s3"))]
+fn read_s3(_location: &str) -> Result<Vec<u8>, String> {
+    Err("reading from s3:// requires the `s3` feature".to_string())
+}
+
+#[cfg(not(feature = "sftp"))]
+fn read_sftp(_location: &str) -> Result<Vec<u8>, String> {
+    Err("reading from sftp:// requires the `sftp` feature".to_string())
+}
+
+/// Fetch an object using credentials from the standard AWS environment
+/// variables (`AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, and optionally
+/// `AWS_SESSION_TOKEN` and `AWS_REGION`).
+#[cfg(feature = "s3")]
+fn read_s3(location: &str) -> Result<Vec<u8>, String> {
+    use chrono::Utc;
+    use std::env;
+
+    let (bucket, key) = location
+        .split_once('/')
+        .ok_or(format!("invalid s3 location `s3://{}`", location))?;
+
+    let access_key =
+        env::var("AWS_ACCESS_KEY_ID").map_err(|_| "AWS_ACCESS_KEY_ID is not set".to_string())?;
+    let secret_key = env::var("AWS_SECRET_ACCESS_KEY")
+        .map_err(|_| "AWS_SECRET_ACCESS_KEY is not set".to_string())?;
+    let session_token = env::var("AWS_SESSION_TOKEN").ok();
+    let region = env::var("AWS_REGION")
+        .or_else(|_| env::var("AWS_DEFAULT_REGION"))
+        .unwrap_or_else(|_| "us-east-1".to_string());
+
+    let host = format!("{}.s3.{}.amazonaws.com", bucket, region);
+    let path = format!("/{}", s3::uri_encode(key));
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = "UNSIGNED-PAYLOAD";
+
+    let mut headers = vec![
+        ("host", host.clone()),
+        ("x-amz-content-sha256", payload_hash.to_string()),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(token) = &session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<&str>>()
+        .join(";");
+    let canonical_request = format!(
+        "GET\n{}\n\n{}\n{}\n{}",
+        path, canonical_headers, signed_headers, payload_hash
+    );
+
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        bai2::raw_hash(canonical_request.as_bytes())
+    );
+
+    let signing_key = [region.as_str(), "s3", "aws4_request"].iter().fold(
+        s3::hmac(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes()),
+        |key, part| s3::hmac(&key, part.as_bytes()),
+    );
+    let signature: String = s3::hmac(&signing_key, string_to_sign.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, scope, signed_headers, signature
+    );
+
+    let mut request =
+        ureq::get(format!("https://{}{}", host, path)).header("Authorization", authorization);
+    for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+        request = request.header(*name, value);
+    }
+
+    request
+        .call()
+        .map_err(|e| format!("could not read `s3://{}`: {}", location, e))?
+        .body_mut()
+        .read_to_vec()
+        .map_err(|e| format!("could not read `s3://{}`: {}", location, e))
+}
+
+#[cfg(feature = "s3")]
+mod s3 {
+    use hmac::{Hmac, KeyInit, Mac};
+    use sha2::Sha256;
+
+    pub fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    pub fn uri_encode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+}
+
+/// Fetch a file over SFTP from a location like `user[:password]@host[:port]/path`,
+/// authenticating with the password when given and the SSH agent otherwise.
+#[cfg(feature = "sftp")]
+fn read_sftp(location: &str) -> Result<Vec<u8>, String> {
+    use ssh2::Session;
+    use std::io::Read;
+    use std::net::TcpStream;
+
+    // The location can hold a password, which mustn't end up in messages
+    let shown = redact_sftp(location);
+    let error = |e: &dyn std::fmt::Display| format!("could not read `sftp://{}`: {}", shown, e);
+
+    let (authority, path) = location
+        .split_once('/')
+        .ok_or(format!("invalid sftp location `sftp://{}`", shown))?;
+    let (credentials, address) = authority
+        .rsplit_once('@')
+        .ok_or(format!("missing user in `sftp://{}`", shown))?;
+    let (user, password) = match credentials.split_once(':') {
+        Some((user, password)) => (user, Some(password)),
+        None => (credentials, None),
+    };
+    let address = match address.contains(':') {
+        true => address.to_string(),
+        false => format!("{}:22", address),
+    };
+
+    let tcp = TcpStream::connect(&address).map_err(|e| error(&e))?;
+    let mut session = Session::new().map_err(|e| error(&e))?;
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(|e| error(&e))?;
+
+    match password {
+        Some(password) => session.userauth_password(user, password),
+        None => session.userauth_agent(user),
+    }
+    .map_err(|e| error(&e))?;
+
+    let sftp = session.sftp().map_err(|e| error(&e))?;
+    let mut file = sftp
+        .open(Path::new(&format!("/{}", path)))
+        .map_err(|e| error(&e))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content).map_err(|e| error(&e))?;
+
+    Ok(content)
+}
@@ -0,0 +1,82 @@
+use bai2::{Account, Bai2File, Direction, Transaction};
+
+const HEADERS: [&str; 7] = [
+    "DATE",
+    "ACCOUNT",
+    "CODE",
+    "DESCRIPTION",
+    "AMOUNT",
+    "REFERENCES",
+    "TEXT",
+];
+
+/// Lay out every transaction in the file as a row of columns, with a header row
+/// first. Debits are shown as negative amounts.
+pub fn table(file: &Bai2File) -> String {
+    let mut rows = vec![HEADERS.map(str::to_string)];
+    rows.extend(transactions(file).map(|(account, transaction)| row(account, transaction)));
+
+    let mut widths = [0; HEADERS.len()];
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for row in rows {
+        let columns: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (column, width))| match i {
+                // amounts line up on the right, and the text doesn't need padding
+                4 => format!("{:>width$}", column),
+                6 => column.to_string(),
+                _ => format!("{:<width$}", column),
+            })
+            .collect();
+        output.push_str(columns.join("  ").trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+fn transactions(file: &Bai2File) -> impl Iterator<Item = (&Account, &Transaction)> {
+    file.groups
+        .iter()
+        .flat_map(|group| group.accounts())
+        .flat_map(|account| account.transactions().iter().map(move |t| (account, t)))
+}
+
+fn row(account: &Account, transaction: &Transaction) -> [String; 7] {
+    let transaction_type = transaction.transaction_type();
+    let amount = transaction
+        .decimal_amount()
+        .map(|amount| match transaction_type.direction() {
+            Direction::Debit => format!("-{}", amount),
+            _ => amount.to_string(),
+        });
+    let references = [
+        transaction.bank_reference_number(),
+        transaction.customer_reference_number(),
+    ]
+    .into_iter()
+    .filter(|r| !r.is_empty())
+    .collect::<Vec<_>>()
+    .join(" / ");
+
+    [
+        transaction
+            .effective_date()
+            .map(|date| date.to_string())
+            .unwrap_or_default(),
+        account.customer_account_number().to_string(),
+        format!("{:03}", transaction_type.code()),
+        transaction_type.description(),
+        amount.unwrap_or_default(),
+        references,
+        transaction.text().to_string(),
+    ]
+}
@@ -0,0 +1,198 @@
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+use crate::file::account::{AmountSubtype, AmountType};
+use crate::file::direction::Direction;
+use crate::file::transaction_type::{TransactionSubType, TransactionType};
+
+/// Words in the names of codes that are abbreviations, which are written in
+/// capitals in their descriptions.
+const ABBREVIATIONS: [&str; 16] = [
+    "ach", "apr", "arp", "atm", "chf", "crs", "db", "dtc", "edi", "edibanx", "ff", "frb", "mtd",
+    "pac", "ytd", "zba",
+];
+
+/// Every type code the parser knows, along with the ranges of codes that are
+/// left for banks to define, for keeping another system's reference data in
+/// step with the crate. See `code_table`.
+#[derive(Clone, Debug, Serialize)]
+pub struct CodeTable {
+    pub codes: Vec<CodeInfo>,
+    pub custom_ranges: Vec<CustomCodeRange>,
+}
+
+/// A type code at one level. A code used both in account summaries and in
+/// transaction details has an entry for each.
+#[derive(Clone, Debug, Serialize)]
+pub struct CodeInfo {
+    #[serde(serialize_with = "serialize_code")]
+    pub code: u16,
+    /// A description made from the name of the code's subtype, e.g. "ACH
+    /// credit received".
+    pub description: String,
+    /// Whether the code is a credit or a debit, which balances aren't.
+    pub direction: Direction,
+    pub level: CodeLevel,
+}
+
+/// A range of type codes that banks define for themselves, inclusive of both
+/// ends.
+#[derive(Clone, Debug, Serialize)]
+pub struct CustomCodeRange {
+    pub direction: Direction,
+    #[serde(serialize_with = "serialize_code")]
+    pub end: u16,
+    pub level: CodeLevel,
+    #[serde(serialize_with = "serialize_code")]
+    pub start: u16,
+}
+
+/// Where a type code is used: for a balance in an account identifier, for a
+/// credit or debit summary in an account identifier, or for a transaction
+/// detail.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeLevel {
+    Status,
+    Summary,
+    Detail,
+}
+
+impl CodeLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodeLevel::Status => "status",
+            CodeLevel::Summary => "summary",
+            CodeLevel::Detail => "detail",
+        }
+    }
+}
+
+impl fmt::Display for CodeLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The table of every type code the parser knows, as read by `AmountType`
+/// and `TransactionType`, in order of code.
+pub fn code_table() -> CodeTable {
+    let mut codes = Vec::new();
+    let mut custom_ranges: Vec<CustomCodeRange> = Vec::new();
+
+    for code in 1..=999 {
+        let levels = [
+            summary_level(&AmountType::from_code(code)),
+            detail_level(&TransactionType::from_code(code)),
+        ];
+
+        for (level, direction, name) in levels.into_iter().flatten() {
+            let Some(name) = name else {
+                let range = custom_ranges.iter_mut().find(|range| {
+                    range.level == level && range.direction == direction && range.end + 1 == code
+                });
+                match range {
+                    Some(range) => range.end = code,
+                    None => custom_ranges.push(CustomCodeRange {
+                        direction,
+                        end: code,
+                        level,
+                        start: code,
+                    }),
+                }
+                continue;
+            };
+
+            codes.push(CodeInfo {
+                code,
+                description: describe(&name),
+                direction,
+                level,
+            });
+        }
+    }
+
+    custom_ranges.sort_by_key(|range| (range.level, range.start));
+    CodeTable {
+        codes,
+        custom_ranges,
+    }
+}
+
+/// The level, direction, and subtype name of a summary code, with no name for
+/// a custom code.
+fn summary_level(amount_type: &AmountType) -> Option<(CodeLevel, Direction, Option<String>)> {
+    let (level, subtype) = match amount_type {
+        AmountType::Status(_, subtype) => (CodeLevel::Status, subtype),
+        AmountType::CreditSummary(_, subtype) | AmountType::DebitSummary(_, subtype) => {
+            (CodeLevel::Summary, subtype)
+        }
+        AmountType::Unknown(..) => return None,
+    };
+
+    let name = match subtype {
+        AmountSubtype::CustomStatus
+        | AmountSubtype::CustomCreditSummary
+        | AmountSubtype::CustomDebitSummary => None,
+        subtype => Some(format!("{:?}", subtype)),
+    };
+    Some((level, amount_type.direction(), name))
+}
+
+/// The level, direction, and subtype name of a detail code, with no name for
+/// a custom code.
+fn detail_level(
+    transaction_type: &TransactionType,
+) -> Option<(CodeLevel, Direction, Option<String>)> {
+    let name = match transaction_type {
+        TransactionType::Unknown(..) => return None,
+        TransactionType::Credit(_, TransactionSubType::Custom)
+        | TransactionType::Debit(_, TransactionSubType::Custom) => None,
+        transaction_type => Some(format!("{:?}", transaction_type.subtype())),
+    };
+    Some((CodeLevel::Detail, transaction_type.direction(), name))
+}
+
+/// Turn the name of a subtype like `AchCreditReceived` into a description
+/// like "ACH credit received".
+pub(crate) fn describe(name: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        let starts_word = previous.is_none_or(|p| {
+            (c.is_uppercase() && !p.is_uppercase()) || (c.is_ascii_digit() != p.is_ascii_digit())
+        });
+        match words.last_mut() {
+            Some(word) if !starts_word => word.push(c),
+            _ => words.push(c.to_string()),
+        }
+        previous = Some(c);
+    }
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let word = word.to_lowercase();
+            if ABBREVIATIONS.contains(&word.as_str()) {
+                word.to_uppercase()
+            } else if i == 0 {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn serialize_code<S>(code: &u16, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(&format_args!("{:03}", code))
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
+use crate::{Bai2File, SCHEMA_VERSION};
+
+/// How the parser handles a directory of sample files, for telling whether
+/// upgrading the crate changes the output for a bank's files. Save one for
+/// the version in use, then compare it to one built after the upgrade:
+///
+/// ```no_run
+/// use bai2::{Corpus, ParseOptions};
+///
+/// let expected: Corpus = serde_json::from_str(&std::fs::read_to_string("corpus.json")?)?;
+/// let actual = Corpus::from_dir("samples", &ParseOptions::default())?;
+/// for path in actual.changed_files(&expected) {
+///     println!("{} is parsed differently", path);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Corpus {
+    pub crate_version: String,
+    /// Every file in the directory and the ones within it, in order of path.
+    pub files: Vec<CorpusFile>,
+    pub schema_version: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CorpusFile {
+    /// Why the file couldn't be parsed, when it couldn't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The `content_hash` of the parsed file, when it could be parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// The path of the file within the directory, with `/` between its parts
+    /// whatever the platform.
+    pub path: String,
+}
+
+impl Corpus {
+    /// Parse every file in the directory, and in the directories within it,
+    /// with the options. A file that can't be parsed is listed with its error
+    /// rather than failing the whole corpus, since how a file fails matters
+    /// too.
+    pub fn from_dir(dir: impl AsRef<Path>, options: &ParseOptions) -> Result<Corpus, Bai2Error> {
+        let mut paths = Vec::new();
+        collect_paths(dir.as_ref(), "", &mut paths)?;
+        paths.sort();
+
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let (hash, error) =
+                    match Bai2File::from_path_with_options(dir.as_ref().join(&path), options) {
+                        Ok(file) => (Some(file.content_hash()), None),
+                        Err(e) => (None, Some(e.to_string())),
+                    };
+                CorpusFile { error, hash, path }
+            })
+            .collect();
+
+        Ok(Corpus {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            files,
+            schema_version: SCHEMA_VERSION,
+        })
+    }
+
+    /// The paths of the files whose result differs from the one in the other
+    /// corpus, along with files that are only in one of them, in order of
+    /// path.
+    pub fn changed_files<'a>(&'a self, other: &'a Corpus) -> Vec<&'a str> {
+        let mut paths: Vec<&str> = self
+            .files
+            .iter()
+            .filter(|file| !other.files.contains(file))
+            .chain(
+                other
+                    .files
+                    .iter()
+                    .filter(|file| !self.files.iter().any(|f| f.path == file.path)),
+            )
+            .map(|file| file.path.as_str())
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        paths
+    }
+}
+
+fn collect_paths(dir: &Path, prefix: &str, paths: &mut Vec<String>) -> Result<(), Bai2Error> {
+    let open_failed = |e: std::io::Error| Bai2Error::OpenFailed {
+        path: dir.display().to_string(),
+        message: e.to_string(),
+    };
+
+    for entry in fs::read_dir(dir).map_err(open_failed)? {
+        let entry = entry.map_err(open_failed)?;
+        let path = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type().map_err(open_failed)?.is_dir() {
+            collect_paths(&entry.path(), &format!("{}/", path), paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
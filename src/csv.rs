@@ -0,0 +1,80 @@
+use crate::file::direction::Direction;
+use crate::file::util::format_int;
+use crate::Bai2File;
+
+const HEADERS: [&str; 11] = [
+    "as_of_date",
+    "value_date",
+    "account_number",
+    "currency_code",
+    "type_code",
+    "direction",
+    "amount",
+    "decimal_amount",
+    "bank_reference_number",
+    "customer_reference_number",
+    "text",
+];
+
+/// Write every transaction in the file as a row of CSV, with the as-of date of
+/// its group and the number and currency of its account alongside it.
+///
+/// `amount` is in minor units as it appears in the file, and `decimal_amount`
+/// is scaled by the currency and negative for debits, so totals can be taken
+/// from a spreadsheet without looking at the direction. The text is kept as it
+/// was in the file, commas included.
+pub(crate) fn write(file: &Bai2File) -> String {
+    let mut output = String::new();
+    push_row(&mut output, HEADERS.map(str::to_string));
+
+    for group in &file.groups {
+        for account in &group.accounts {
+            for transaction in &account.transactions {
+                let direction = transaction.transaction_type.direction();
+                let decimal_amount = match (&transaction.decimal_amount, direction) {
+                    (Some(amount), Direction::Debit) => format!("-{}", amount),
+                    (amount, _) => amount.clone().unwrap_or_default(),
+                };
+
+                push_row(
+                    &mut output,
+                    [
+                        format_date(group.as_of_date),
+                        format_date(transaction.value_date),
+                        account.customer_account_number.clone(),
+                        transaction.currency_code.to_string(),
+                        format!("{:03}", transaction.transaction_type.code()),
+                        direction.to_string(),
+                        format_int(transaction.amount),
+                        decimal_amount,
+                        transaction.bank_reference_number.clone(),
+                        transaction.customer_reference_number.clone(),
+                        transaction.text.clone(),
+                    ],
+                );
+            }
+        }
+    }
+
+    output
+}
+
+fn push_row<const N: usize>(output: &mut String, fields: [String; N]) {
+    let fields: Vec<String> = fields.iter().map(|f| quote(f)).collect();
+    output.push_str(&fields.join(","));
+    output.push('\n');
+}
+
+/// Quote a field when it contains anything that would break the row up, as in
+/// RFC 4180.
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn format_date(date: Option<chrono::NaiveDate>) -> String {
+    date.map(|d| d.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,104 @@
+use log::debug;
+use serde::Serialize;
+
+use crate::file::group::{Group, GroupStatus};
+use crate::Bai2File;
+
+/// A running picture of the current day, built by applying successive files
+/// (typically interim same-day reports) in the order they were received.
+///
+/// Groups are matched on originator, ultimate receiver, and as-of date, and
+/// accounts within a group on account number and currency. Each group status
+/// is applied as the BAI2 spec describes it:
+///
+/// - Update: accounts in the group replace the matching accounts, and any new
+///   accounts are added.
+/// - Deletion: accounts in the group are removed. A deletion without any
+///   accounts removes the whole group.
+///
+/// After an update or deletion, the group's control total is the sum of its
+/// accounts' totals, and its raw records are cleared.
+/// - Correction: the group replaces the previously received group entirely.
+/// - Test only and unknown statuses are ignored.
+#[derive(Debug, Default, Serialize)]
+pub struct DayView {
+    groups: Vec<Group>,
+}
+
+impl DayView {
+    pub fn new() -> DayView {
+        DayView::default()
+    }
+
+    pub fn apply(&mut self, file: Bai2File) {
+        for group in file.groups {
+            self.apply_group(group);
+        }
+    }
+
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    // Private
+
+    fn apply_group(&mut self, group: Group) {
+        let existing = self.groups.iter().position(|g| g.same_group(&group));
+
+        match (group.status(), existing) {
+            (GroupStatus::Update, Some(index)) => self.groups[index].apply_update(group),
+            (GroupStatus::Update, None) => self.groups.push(group),
+            (GroupStatus::Correction, Some(index)) => self.groups[index] = group,
+            (GroupStatus::Correction, None) => self.groups.push(group),
+            (GroupStatus::Deletion, Some(index)) => {
+                if self.groups[index].apply_deletion(&group) {
+                    self.groups.remove(index);
+                }
+            }
+            (GroupStatus::Deletion, None) => debug!("skipping deletion of unknown group"),
+            (status, _) => debug!("skipping group with status {:?}", status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseOptions;
+
+    fn parse(content: &str) -> Bai2File {
+        let options = ParseOptions {
+            keep_raw_records: true,
+            preserve_formatting: true,
+            ..ParseOptions::default()
+        };
+        Bai2File::new_with_options(content.to_string(), &options).unwrap()
+    }
+
+    #[test]
+    fn updates_total_the_merged_accounts_again() {
+        let mut day = DayView::new();
+        day.apply(parse(
+            "01,A,B,200331,0900,1,,,2/
+02,,B,1,200331,0900,,3/
+03,1,USD,010,100,,/
+49,100,2/
+98,100,1,4/
+99,100,1,6/",
+        ));
+        day.apply(parse(
+            "01,A,B,200331,1200,2,,,2/
+02,,B,1,200331,1200,,3/
+03,2,USD,010,50,,/
+49,50,2/
+98,50,1,4/
+99,50,1,6/",
+        ));
+
+        let group = &day.groups()[0];
+        assert_eq!(group.accounts().len(), 2);
+        assert_eq!(group.control_total(), Some(150));
+        assert_eq!(group.raw(), None);
+        assert!(group.original.is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Bai2Error;
+
+/// The character encoding of a file's raw bytes.
+///
+/// Most banks send ASCII, which every encoding other than EBCDIC reads the
+/// same, but text fields can carry accented characters in Latin-1 or Windows
+/// code pages, and mainframes send EBCDIC.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Encoding {
+    /// UTF-8, failing on bytes that aren't valid UTF-8
+    #[default]
+    Utf8,
+    /// ISO 8859-1, where every byte is the character with the same code point
+    Latin1,
+    /// Windows code page 1252, which is Latin-1 with printable characters in
+    /// place of most of the control characters from 0x80 to 0x9F
+    Cp1252,
+    /// EBCDIC code page 037, as used by US and Canadian mainframes
+    Ebcdic,
+    /// UTF-8 when the bytes are valid UTF-8, EBCDIC when the file header is in
+    /// EBCDIC, and Windows code page 1252 otherwise
+    Auto,
+}
+
+impl Encoding {
+    /// Decode the raw bytes of a file. Only UTF-8 can fail, with the line of
+    /// the first byte that isn't valid. A UTF-8 byte order mark is dropped.
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, Bai2Error> {
+        match self {
+            Encoding::Utf8 => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+                String::from_utf8(bytes.to_vec()).map_err(|e| {
+                    let offset = e.utf8_error().valid_up_to();
+                    Bai2Error::ReadFailed {
+                        line: 1 + bytes[..offset].iter().filter(|&&b| b == b'\n').count(),
+                        message: "stream did not contain valid UTF-8".to_string(),
+                    }
+                })
+            }
+            Encoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+            Encoding::Cp1252 => Ok(bytes
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9F => CP1252[(b - 0x80) as usize],
+                    _ => b as char,
+                })
+                .collect()),
+            Encoding::Ebcdic => Ok(bytes.iter().map(|&b| CP037[b as usize]).collect()),
+            Encoding::Auto => Encoding::detect(bytes).decode(bytes),
+        }
+    }
+
+    /// The encoding `Auto` decodes the bytes with.
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        if std::str::from_utf8(bytes).is_ok() {
+            return Encoding::Utf8;
+        }
+
+        // The file header starts with its record code, which is "01" in EBCDIC
+        // and doesn't appear at the start of a file in any other encoding. Any
+        // blank lines before it are skipped, as they are when scanning.
+        let start = bytes
+            .iter()
+            .position(|b| !EBCDIC_LINE_BREAKS.contains(b))
+            .unwrap_or(bytes.len());
+        if bytes[start..].starts_with(&[0xF0, 0xF1]) {
+            Encoding::Ebcdic
+        } else {
+            Encoding::Cp1252
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf8",
+            Encoding::Latin1 => "latin1",
+            Encoding::Cp1252 => "cp1252",
+            Encoding::Ebcdic => "ebcdic",
+            Encoding::Auto => "auto",
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Encoding, String> {
+        match name {
+            "utf8" | "utf-8" => Ok(Encoding::Utf8),
+            "latin1" | "iso-8859-1" => Ok(Encoding::Latin1),
+            "cp1252" | "windows-1252" => Ok(Encoding::Cp1252),
+            "ebcdic" | "cp037" => Ok(Encoding::Ebcdic),
+            "auto" => Ok(Encoding::Auto),
+            _ => Err(format!(
+                "unknown encoding `{}`, expected utf8, latin1, cp1252, ebcdic, or auto",
+                name
+            )),
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Carriage return, newline, and line feed in EBCDIC.
+const EBCDIC_LINE_BREAKS: [u8; 3] = [0x0D, 0x15, 0x25];
+
+/// The characters of bytes 0x80 to 0x9F in Windows code page 1252. The five
+/// bytes it leaves undefined are kept as the control characters Latin-1 has
+/// for them.
+const CP1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// The characters of every byte in EBCDIC code page 037. The EBCDIC newline
+/// (0x15) is read as a line feed so that records still split into lines.
+const CP037: [char; 256] = [
+    '\u{0000}', '\u{0001}', '\u{0002}', '\u{0003}', '\u{009C}', '\u{0009}', '\u{0086}', '\u{007F}',
+    '\u{0097}', '\u{008D}', '\u{008E}', '\u{000B}', '\u{000C}', '\u{000D}', '\u{000E}', '\u{000F}',
+    '\u{0010}', '\u{0011}', '\u{0012}', '\u{0013}', '\u{009D}', '\u{000A}', '\u{0008}', '\u{0087}',
+    '\u{0018}', '\u{0019}', '\u{0092}', '\u{008F}', '\u{001C}', '\u{001D}', '\u{001E}', '\u{001F}',
+    '\u{0080}', '\u{0081}', '\u{0082}', '\u{0083}', '\u{0084}', '\u{000A}', '\u{0017}', '\u{001B}',
+    '\u{0088}', '\u{0089}', '\u{008A}', '\u{008B}', '\u{008C}', '\u{0005}', '\u{0006}', '\u{0007}',
+    '\u{0090}', '\u{0091}', '\u{0016}', '\u{0093}', '\u{0094}', '\u{0095}', '\u{0096}', '\u{0004}',
+    '\u{0098}', '\u{0099}', '\u{009A}', '\u{009B}', '\u{0014}', '\u{0015}', '\u{009E}', '\u{001A}',
+    '\u{0020}', '\u{00A0}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E1}', '\u{00E3}', '\u{00E5}',
+    '\u{00E7}', '\u{00F1}', '\u{00A2}', '\u{002E}', '\u{003C}', '\u{0028}', '\u{002B}', '\u{007C}',
+    '\u{0026}', '\u{00E9}', '\u{00EA}', '\u{00EB}', '\u{00E8}', '\u{00ED}', '\u{00EE}', '\u{00EF}',
+    '\u{00EC}', '\u{00DF}', '\u{0021}', '\u{0024}', '\u{002A}', '\u{0029}', '\u{003B}', '\u{00AC}',
+    '\u{002D}', '\u{002F}', '\u{00C2}', '\u{00C4}', '\u{00C0}', '\u{00C1}', '\u{00C3}', '\u{00C5}',
+    '\u{00C7}', '\u{00D1}', '\u{00A6}', '\u{002C}', '\u{0025}', '\u{005F}', '\u{003E}', '\u{003F}',
+    '\u{00F8}', '\u{00C9}', '\u{00CA}', '\u{00CB}', '\u{00C8}', '\u{00CD}', '\u{00CE}', '\u{00CF}',
+    '\u{00CC}', '\u{0060}', '\u{003A}', '\u{0023}', '\u{0040}', '\u{0027}', '\u{003D}', '\u{0022}',
+    '\u{00D8}', '\u{0061}', '\u{0062}', '\u{0063}', '\u{0064}', '\u{0065}', '\u{0066}', '\u{0067}',
+    '\u{0068}', '\u{0069}', '\u{00AB}', '\u{00BB}', '\u{00F0}', '\u{00FD}', '\u{00FE}', '\u{00B1}',
+    '\u{00B0}', '\u{006A}', '\u{006B}', '\u{006C}', '\u{006D}', '\u{006E}', '\u{006F}', '\u{0070}',
+    '\u{0071}', '\u{0072}', '\u{00AA}', '\u{00BA}', '\u{00E6}', '\u{00B8}', '\u{00C6}', '\u{00A4}',
+    '\u{00B5}', '\u{007E}', '\u{0073}', '\u{0074}', '\u{0075}', '\u{0076}', '\u{0077}', '\u{0078}',
+    '\u{0079}', '\u{007A}', '\u{00A1}', '\u{00BF}', '\u{00D0}', '\u{00DD}', '\u{00DE}', '\u{00AE}',
+    '\u{005E}', '\u{00A3}', '\u{00A5}', '\u{00B7}', '\u{00A9}', '\u{00A7}', '\u{00B6}', '\u{00BC}',
+    '\u{00BD}', '\u{00BE}', '\u{005B}', '\u{005D}', '\u{00AF}', '\u{00A8}', '\u{00B4}', '\u{00D7}',
+    '\u{007B}', '\u{0041}', '\u{0042}', '\u{0043}', '\u{0044}', '\u{0045}', '\u{0046}', '\u{0047}',
+    '\u{0048}', '\u{0049}', '\u{00AD}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00F3}', '\u{00F5}',
+    '\u{007D}', '\u{004A}', '\u{004B}', '\u{004C}', '\u{004D}', '\u{004E}', '\u{004F}', '\u{0050}',
+    '\u{0051}', '\u{0052}', '\u{00B9}', '\u{00FB}', '\u{00FC}', '\u{00F9}', '\u{00FA}', '\u{00FF}',
+    '\u{005C}', '\u{00F7}', '\u{0053}', '\u{0054}', '\u{0055}', '\u{0056}', '\u{0057}', '\u{0058}',
+    '\u{0059}', '\u{005A}', '\u{00B2}', '\u{00D4}', '\u{00D6}', '\u{00D2}', '\u{00D3}', '\u{00D5}',
+    '\u{0030}', '\u{0031}', '\u{0032}', '\u{0033}', '\u{0034}', '\u{0035}', '\u{0036}', '\u{0037}',
+    '\u{0038}', '\u{0039}', '\u{00B3}', '\u{00DB}', '\u{00DC}', '\u{00D9}', '\u{00DA}', '\u{009F}',
+];
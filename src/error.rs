@@ -0,0 +1,335 @@
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::fmt;
+
+use crate::validation::ValidationFailure;
+
+/// An error encountered while scanning or parsing a file. Each variant has a
+/// stable code so that failures can be logged and aggregated across banks.
+///
+/// Line numbers start at 1. Field numbers count the record code as field 1 and
+/// run across continuations, so they always refer to the logical record while
+/// the line points at the physical line the field is on. When a record is too
+/// short, the field is the first one that's missing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Bai2Error {
+    ReadFailed {
+        line: usize,
+        message: String,
+    },
+    OpenFailed {
+        path: String,
+        message: String,
+    },
+    EmptyFile,
+    FileHeaderMissing {
+        line: usize,
+    },
+    FileTrailerMissing {
+        line: usize,
+    },
+    GroupTrailerMissing {
+        line: usize,
+    },
+    AccountTrailerMissing {
+        line: usize,
+    },
+    GroupHeaderWithoutFileHeader {
+        line: usize,
+    },
+    AccountWithoutGroupHeader {
+        line: usize,
+    },
+    TransactionWithoutAccount {
+        line: usize,
+    },
+    AccountTrailerWithoutAccount {
+        line: usize,
+    },
+    GroupTrailerWithoutGroupHeader {
+        line: usize,
+    },
+    FileTrailerWithoutFileHeader {
+        line: usize,
+    },
+    UnknownRecordType {
+        line: usize,
+        code: String,
+    },
+    InvalidFileHeader {
+        line: usize,
+        field: usize,
+    },
+    InvalidFileTrailer {
+        line: usize,
+        field: usize,
+    },
+    InvalidGroupHeader {
+        line: usize,
+        field: usize,
+    },
+    InvalidGroupTrailer {
+        line: usize,
+        field: usize,
+    },
+    InvalidAccountHeader {
+        line: usize,
+        field: usize,
+    },
+    InvalidAccountTrailer {
+        line: usize,
+        field: usize,
+    },
+    InvalidAccountSummary {
+        line: usize,
+        field: usize,
+        detail: &'static str,
+    },
+    InvalidTransactionDetail {
+        line: usize,
+        field: usize,
+        detail: &'static str,
+    },
+    ValidationFailed {
+        failure: ValidationFailure,
+    },
+    TooManyErrors {
+        line: usize,
+        max: usize,
+    },
+}
+
+impl Bai2Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Bai2Error::ReadFailed { .. } => "E0001",
+            Bai2Error::OpenFailed { .. } => "E0002",
+            Bai2Error::EmptyFile => "E0100",
+            Bai2Error::FileHeaderMissing { .. } => "E0101",
+            Bai2Error::FileTrailerMissing { .. } => "E0102",
+            Bai2Error::GroupTrailerMissing { .. } => "E0103",
+            Bai2Error::AccountTrailerMissing { .. } => "E0104",
+            Bai2Error::GroupHeaderWithoutFileHeader { .. } => "E0110",
+            Bai2Error::AccountWithoutGroupHeader { .. } => "E0111",
+            Bai2Error::TransactionWithoutAccount { .. } => "E0112",
+            Bai2Error::AccountTrailerWithoutAccount { .. } => "E0113",
+            Bai2Error::GroupTrailerWithoutGroupHeader { .. } => "E0114",
+            Bai2Error::FileTrailerWithoutFileHeader { .. } => "E0115",
+            Bai2Error::UnknownRecordType { .. } => "E0116",
+            Bai2Error::InvalidFileHeader { .. } => "E0200",
+            Bai2Error::InvalidFileTrailer { .. } => "E0201",
+            Bai2Error::InvalidGroupHeader { .. } => "E0202",
+            Bai2Error::InvalidGroupTrailer { .. } => "E0203",
+            Bai2Error::InvalidAccountHeader { .. } => "E0204",
+            Bai2Error::InvalidAccountTrailer { .. } => "E0205",
+            Bai2Error::InvalidAccountSummary { .. } => "E0206",
+            Bai2Error::InvalidTransactionDetail { .. } => "E0207",
+            Bai2Error::ValidationFailed { .. } => "E0300",
+            Bai2Error::TooManyErrors { .. } => "E0301",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bai2Error::ReadFailed { .. } => "ReadFailed",
+            Bai2Error::OpenFailed { .. } => "OpenFailed",
+            Bai2Error::EmptyFile => "EmptyFile",
+            Bai2Error::FileHeaderMissing { .. } => "FileHeaderMissing",
+            Bai2Error::FileTrailerMissing { .. } => "FileTrailerMissing",
+            Bai2Error::GroupTrailerMissing { .. } => "GroupTrailerMissing",
+            Bai2Error::AccountTrailerMissing { .. } => "AccountTrailerMissing",
+            Bai2Error::GroupHeaderWithoutFileHeader { .. } => "GroupHeaderWithoutFileHeader",
+            Bai2Error::AccountWithoutGroupHeader { .. } => "AccountWithoutGroupHeader",
+            Bai2Error::TransactionWithoutAccount { .. } => "TransactionWithoutAccount",
+            Bai2Error::AccountTrailerWithoutAccount { .. } => "AccountTrailerWithoutAccount",
+            Bai2Error::GroupTrailerWithoutGroupHeader { .. } => "GroupTrailerWithoutGroupHeader",
+            Bai2Error::FileTrailerWithoutFileHeader { .. } => "FileTrailerWithoutFileHeader",
+            Bai2Error::UnknownRecordType { .. } => "UnknownRecordType",
+            Bai2Error::InvalidFileHeader { .. } => "InvalidFileHeader",
+            Bai2Error::InvalidFileTrailer { .. } => "InvalidFileTrailer",
+            Bai2Error::InvalidGroupHeader { .. } => "InvalidGroupHeader",
+            Bai2Error::InvalidGroupTrailer { .. } => "InvalidGroupTrailer",
+            Bai2Error::InvalidAccountHeader { .. } => "InvalidAccountHeader",
+            Bai2Error::InvalidAccountTrailer { .. } => "InvalidAccountTrailer",
+            Bai2Error::InvalidAccountSummary { .. } => "InvalidAccountSummary",
+            Bai2Error::InvalidTransactionDetail { .. } => "InvalidTransactionDetail",
+            Bai2Error::ValidationFailed { .. } => "ValidationFailed",
+            Bai2Error::TooManyErrors { .. } => "TooManyErrors",
+        }
+    }
+
+    /// The record code of the record the error was found in, or the record
+    /// that was expected but not found.
+    pub fn record_type(&self) -> Option<&str> {
+        match self {
+            Bai2Error::ReadFailed { .. }
+            | Bai2Error::OpenFailed { .. }
+            | Bai2Error::EmptyFile
+            | Bai2Error::TooManyErrors { .. } => None,
+            Bai2Error::UnknownRecordType { code, .. } => Some(code),
+            Bai2Error::FileHeaderMissing { .. } | Bai2Error::InvalidFileHeader { .. } => Some("01"),
+            Bai2Error::GroupHeaderWithoutFileHeader { .. }
+            | Bai2Error::InvalidGroupHeader { .. } => Some("02"),
+            Bai2Error::AccountWithoutGroupHeader { .. }
+            | Bai2Error::InvalidAccountHeader { .. }
+            | Bai2Error::InvalidAccountSummary { .. } => Some("03"),
+            Bai2Error::TransactionWithoutAccount { .. }
+            | Bai2Error::InvalidTransactionDetail { .. } => Some("16"),
+            Bai2Error::AccountTrailerMissing { .. }
+            | Bai2Error::AccountTrailerWithoutAccount { .. }
+            | Bai2Error::InvalidAccountTrailer { .. } => Some("49"),
+            Bai2Error::GroupTrailerMissing { .. }
+            | Bai2Error::GroupTrailerWithoutGroupHeader { .. }
+            | Bai2Error::InvalidGroupTrailer { .. } => Some("98"),
+            Bai2Error::FileTrailerMissing { .. }
+            | Bai2Error::FileTrailerWithoutFileHeader { .. }
+            | Bai2Error::InvalidFileTrailer { .. } => Some("99"),
+            Bai2Error::ValidationFailed { failure } => Some(failure.check.record_type()),
+        }
+    }
+
+    /// The line the error was found on. For a missing trailer this is the line
+    /// of the header that was never closed.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Bai2Error::EmptyFile | Bai2Error::OpenFailed { .. } => None,
+            Bai2Error::ReadFailed { line, .. }
+            | Bai2Error::FileHeaderMissing { line }
+            | Bai2Error::FileTrailerMissing { line }
+            | Bai2Error::GroupTrailerMissing { line }
+            | Bai2Error::AccountTrailerMissing { line }
+            | Bai2Error::GroupHeaderWithoutFileHeader { line }
+            | Bai2Error::AccountWithoutGroupHeader { line }
+            | Bai2Error::TransactionWithoutAccount { line }
+            | Bai2Error::AccountTrailerWithoutAccount { line }
+            | Bai2Error::GroupTrailerWithoutGroupHeader { line }
+            | Bai2Error::FileTrailerWithoutFileHeader { line }
+            | Bai2Error::UnknownRecordType { line, .. }
+            | Bai2Error::TooManyErrors { line, .. }
+            | Bai2Error::InvalidFileHeader { line, .. }
+            | Bai2Error::InvalidFileTrailer { line, .. }
+            | Bai2Error::InvalidGroupHeader { line, .. }
+            | Bai2Error::InvalidGroupTrailer { line, .. }
+            | Bai2Error::InvalidAccountHeader { line, .. }
+            | Bai2Error::InvalidAccountTrailer { line, .. }
+            | Bai2Error::InvalidAccountSummary { line, .. }
+            | Bai2Error::InvalidTransactionDetail { line, .. } => Some(*line),
+            Bai2Error::ValidationFailed { failure } => Some(failure.line),
+        }
+    }
+
+    /// The field within the record that the error was found in, if the error
+    /// is about the contents of a record rather than where it appears.
+    pub fn field(&self) -> Option<usize> {
+        match self {
+            Bai2Error::InvalidFileHeader { field, .. }
+            | Bai2Error::InvalidFileTrailer { field, .. }
+            | Bai2Error::InvalidGroupHeader { field, .. }
+            | Bai2Error::InvalidGroupTrailer { field, .. }
+            | Bai2Error::InvalidAccountHeader { field, .. }
+            | Bai2Error::InvalidAccountTrailer { field, .. }
+            | Bai2Error::InvalidAccountSummary { field, .. }
+            | Bai2Error::InvalidTransactionDetail { field, .. } => Some(*field),
+            Bai2Error::ValidationFailed { failure } => Some(failure.check.field()),
+            _ => None,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Bai2Error::ReadFailed { message, .. } => format!("could not read line: {}", message),
+            Bai2Error::OpenFailed { path, message } => {
+                format!("could not open `{}`: {}", path, message)
+            }
+            Bai2Error::EmptyFile => "no lines found in file".to_string(),
+            Bai2Error::FileHeaderMissing { .. } => "file header not found".to_string(),
+            Bai2Error::FileTrailerMissing { .. } => "file trailer not found".to_string(),
+            Bai2Error::GroupTrailerMissing { .. } => "group trailer not found".to_string(),
+            Bai2Error::AccountTrailerMissing { .. } => "account trailer not found".to_string(),
+            Bai2Error::GroupHeaderWithoutFileHeader { .. } => {
+                "group header found without file header".to_string()
+            }
+            Bai2Error::AccountWithoutGroupHeader { .. } => {
+                "account identifier found without group header".to_string()
+            }
+            Bai2Error::TransactionWithoutAccount { .. } => {
+                "transaction detail found without account identifier".to_string()
+            }
+            Bai2Error::AccountTrailerWithoutAccount { .. } => {
+                "account trailer found without account identifier".to_string()
+            }
+            Bai2Error::GroupTrailerWithoutGroupHeader { .. } => {
+                "group trailer found without group header".to_string()
+            }
+            Bai2Error::FileTrailerWithoutFileHeader { .. } => {
+                "file trailer found without file header".to_string()
+            }
+            Bai2Error::UnknownRecordType { code, .. } => {
+                format!("unrecognized record type `{}`", code)
+            }
+            Bai2Error::InvalidFileHeader { .. } => {
+                "Invalid file header. Expected 9 fields, but found less.".to_string()
+            }
+            Bai2Error::InvalidFileTrailer { .. } => {
+                "Invalid file trailer. Expected 4 fields, but found less.".to_string()
+            }
+            Bai2Error::InvalidGroupHeader { .. } => {
+                "Invalid group header. Expected 7 fields, but found less.".to_string()
+            }
+            Bai2Error::InvalidGroupTrailer { .. } => {
+                "Invalid group trailer. Expected 4 fields, but found less.".to_string()
+            }
+            Bai2Error::InvalidAccountHeader { .. } => {
+                "Invalid account header. Expected 7 fields, but found less.".to_string()
+            }
+            Bai2Error::InvalidAccountTrailer { .. } => {
+                "Invalid account trailer. Expected 3 fields, but found less.".to_string()
+            }
+            Bai2Error::InvalidAccountSummary { detail, .. } => {
+                format!("Invalid account summary. {}", detail)
+            }
+            Bai2Error::InvalidTransactionDetail { detail, .. } => {
+                format!("Invalid transaction detail. {}", detail)
+            }
+            Bai2Error::ValidationFailed { failure } => format!(
+                "{} is {} in the trailer, but {} was found",
+                failure.check.description(),
+                failure.expected,
+                failure.found
+            ),
+            Bai2Error::TooManyErrors { max, .. } => {
+                format!("more than {} errors found, giving up", max)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Bai2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line(), self.field()) {
+            (Some(line), Some(field)) => {
+                write!(f, "line {}, field {}: {}", line, field, self.message())
+            }
+            (Some(line), None) => write!(f, "line {}: {}", line, self.message()),
+            _ => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for Bai2Error {}
+
+impl Serialize for Bai2Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Bai2Error", 6)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("field", &self.field())?;
+        state.serialize_field("line", &self.line())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("name", self.name())?;
+        state.serialize_field("record_type", &self.record_type())?;
+        state.end()
+    }
+}
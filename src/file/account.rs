@@ -1,140 +1,461 @@
 use chrono::NaiveDate;
 use serde::ser::{SerializeStruct, Serializer};
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
+use crate::reconcile::{self, Discrepancy};
 use crate::scanner::node::Node;
+use crate::writer;
 
-use super::funds_type::{FundsSubType, FundsType};
+use super::category::Category;
+use super::currency::Currency;
+use super::custom_codes::CustomCodes;
+use super::direction::Direction;
+use super::funds_type::{deserialize_availability, serialize_availability, Funds, FundsType};
+use super::minor_units::MinorUnits;
+use super::opaque_record::OpaqueRecord;
+use super::original_format::OriginalFormat;
+use super::time::Time;
 use super::transaction::Transaction;
-use super::util::{parse_currency, parse_date, parse_int, parse_string, parse_time};
+use super::transaction_type::{parse_type_code, InvalidTypeCode, UnknownTypeCode};
+use super::util::{parse_amount, parse_int, parse_optional_string, parse_str, parse_string};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Account {
-    amounts: Vec<Amount>,
-    currency_code: String,
-    customer_account_number: String,
-    transactions: Vec<Transaction>,
-    value_date: Option<NaiveDate>,
-    value_time: Option<String>,
+    pub(crate) amounts: Vec<Amount>,
+    pub(crate) control_total: Option<i64>,
+    pub(crate) currency_code: Currency,
+    pub(crate) currency_source: CurrencySource,
+    pub(crate) customer_account_number: String,
+    #[serde(skip)]
+    pub(crate) original: Option<OriginalFormat>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) other_records: Vec<OpaqueRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) raw: Option<String>,
+    pub(crate) sequence_number: usize,
+    pub(crate) transactions: Vec<Transaction>,
 }
 
 impl Account {
-    pub fn from_node(node: &Node, default_currency: &str) -> Result<Account, &'static str> {
-        let header_fields = node.fields();
-        if header_fields.len() < 7 {
-            return Err("Invalid account header. Expected 7 fields, but found less.");
-        }
+    pub fn from_node(
+        node: &Node,
+        sequence_number: usize,
+        group_currency: Option<&str>,
+        as_of_date: Option<NaiveDate>,
+        options: &ParseOptions,
+    ) -> Result<Account, Bai2Error> {
+        let header_fields = node
+            .required_fields(7, options.allow_short_records)
+            .map_err(|(line, field)| Bai2Error::InvalidAccountHeader { line, field })?;
 
-        let trailer_fields = node.sibling_fields();
-        if trailer_fields.len() < 3 {
-            return Err("Invalid account trailer. Expected 3 fields, but found less.");
-        }
+        let control_total = match node.sibling() {
+            Some(trailer) => {
+                let trailer_fields = trailer
+                    .required_fields(3, options.allow_short_records)
+                    .map_err(|(line, field)| Bai2Error::InvalidAccountTrailer { line, field })?;
+                parse_amount(trailer_fields[1])
+            }
+            None if options.allow_missing_trailers => None,
+            None => {
+                return Err(Bai2Error::AccountTrailerMissing {
+                    line: node.line_number(),
+                })
+            }
+        };
+
+        let (currency_code, currency_source) =
+            match (parse_optional_string(header_fields[2]), group_currency) {
+                (Some(currency), _) => (Currency::parse(&currency), CurrencySource::Account),
+                (None, Some(currency)) => (Currency::parse(currency), CurrencySource::Group),
+                (None, None) => (
+                    Currency::parse(&options.default_currency),
+                    CurrencySource::Default,
+                ),
+            };
+        let amounts = Amount::parse(&header_fields[3..], currency_code.code(), options).map_err(
+            |(index, detail)| {
+                let (line, field) = node.field_position(index + 3);
+                Bai2Error::InvalidAccountSummary {
+                    line,
+                    field,
+                    detail,
+                }
+            },
+        )?;
 
         let txns_result = node
-            .children
-            .iter()
-            .map(Transaction::from_node)
-            .collect::<Result<Vec<Transaction>, &'static str>>();
-
-        match txns_result {
-            Err(e) => Err(e),
-            Ok(transactions) => Ok(Account {
-                amounts: Amount::parse(header_fields[3..].to_vec()),
-                currency_code: parse_currency(header_fields[2], default_currency),
+            .children()
+            .enumerate()
+            .map(|(i, n)| Transaction::from_node(&n, i + 1, &currency_code, as_of_date, options))
+            .collect::<Result<Vec<Transaction>, Bai2Error>>();
+
+        let mut account = match txns_result {
+            Err(e) => return Err(e),
+            Ok(transactions) => Account {
+                amounts,
+                control_total,
+                currency_code,
+                currency_source,
                 customer_account_number: parse_string(header_fields[1]),
+                original: None,
+                other_records: OpaqueRecord::from_parent(node),
+                raw: options.keep_raw_records.then(|| node.raw_with_trailer()),
+                sequence_number,
                 transactions,
-                value_date: None,
-                value_time: None,
-            }),
+            },
+        };
+
+        if options.preserve_formatting {
+            let fields = writer::account_header(
+                &account,
+                account.currency_code.to_string(),
+                options.version,
+            );
+            let contents = writer::account_contents(&account, options.version);
+            account.original = Some(OriginalFormat::new(&fields, node, contents));
+        }
+
+        if options.sort_transactions {
+            account.sort_transactions();
         }
+
+        Ok(account)
+    }
+
+    /// Sort transactions chronologically by their effective date. The sort is
+    /// stable, so transactions on the same date keep their original order, and
+    /// transactions without a date are placed last.
+    pub fn sort_transactions(&mut self) {
+        self.transactions.sort_by_key(|t| {
+            let date = t.effective_date();
+            (date.is_none(), date)
+        });
+    }
+
+    /// Check the credit and debit summaries against the detail transactions.
+    /// See `Bai2File::reconcile`.
+    pub fn reconcile(&self) -> Vec<Discrepancy> {
+        reconcile::account(self)
+    }
+
+    /// The balances and summary amounts from the account identifier record.
+    pub fn amounts(&self) -> &[Amount] {
+        &self.amounts
+    }
+
+    /// The balance or summary with the type code, e.g. 100 for total credits,
+    /// along with its item count and funds type.
+    pub fn summary(&self, code: u16) -> Option<&Amount> {
+        self.amounts.iter().find(|a| a.amount_type.code() == code)
+    }
+
+    /// The signed total of the account from its trailer, which is the sum of
+    /// the amounts in the account identifier and transaction records.
+    pub fn control_total(&self) -> Option<i64> {
+        self.control_total
+    }
+
+    /// The records with codes that aren't part of the format that were found
+    /// in the account. See `OpaqueRecord`.
+    pub fn other_records(&self) -> &[OpaqueRecord] {
+        &self.other_records
+    }
+
+    /// The account identifier and account trailer records and their
+    /// continuations exactly as they appeared in the file, one line each,
+    /// when they were kept with `ParseOptions::keep_raw_records`. The
+    /// transactions have their own.
+    pub fn raw(&self) -> Option<&str> {
+        self.raw.as_deref()
+    }
+
+    /// The control total as `MinorUnits`.
+    pub fn control_total_minor_units(&self) -> Option<MinorUnits> {
+        self.control_total.map(MinorUnits)
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency_code
+    }
+
+    pub fn currency_code(&self) -> &str {
+        self.currency_code.code()
+    }
+
+    pub fn currency_source(&self) -> CurrencySource {
+        self.currency_source
+    }
+
+    pub fn customer_account_number(&self) -> &str {
+        &self.customer_account_number
+    }
+
+    /// The position of the account in the file, counting the accounts in every
+    /// group and starting from 1.
+    pub fn sequence_number(&self) -> usize {
+        self.sequence_number
+    }
+
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
     }
+
+    pub(crate) fn transactions_mut(&mut self) -> &mut [Transaction] {
+        &mut self.transactions
+    }
+
+    pub(crate) fn same_account(&self, other: &Account) -> bool {
+        self.customer_account_number == other.customer_account_number
+            && self.currency_code == other.currency_code
+    }
+}
+
+/// An account that was left out of its group because it couldn't be parsed,
+/// when `ParseOptions::skip_invalid_accounts` is set.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SkippedAccount {
+    pub error: Bai2Error,
+    /// The line of the account identifier.
+    pub line: usize,
+    /// The number the account would have had among the accounts of the file.
+    pub sequence_number: usize,
 }
 
-#[derive(Debug, Serialize)]
+/// Which level of the file supplied an account's currency. The file header
+/// doesn't carry a currency in BAI2, so an account without its own currency
+/// falls back to its group's, and then to the default in the parse options.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrencySource {
+    Account,
+    Group,
+    Default,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Amount {
-    amount_type: AmountType,
-    amount: Option<i64>,
-    availability: HashMap<u16, i64>,
-    funds_type: FundsType,
-    item_count: Option<u16>,
-    value_date: Option<NaiveDate>,
-    value_time: Option<String>,
+    pub(crate) amount_type: AmountType,
+    pub(crate) amount: Option<i64>,
+    #[serde(
+        serialize_with = "serialize_availability",
+        deserialize_with = "deserialize_availability"
+    )]
+    pub(crate) availability: BTreeMap<u16, i64>,
+    pub(crate) category: Option<Category>,
+    pub(crate) decimal_amount: Option<String>,
+    pub(crate) funds_type: FundsType,
+    pub(crate) item_count: Option<u16>,
+    pub(crate) value_date: Option<NaiveDate>,
+    pub(crate) value_time: Option<Time>,
 }
 
 impl Amount {
-    fn parse(fields: Vec<&str>) -> Vec<Amount> {
+    pub fn amount(&self) -> Option<i64> {
+        self.amount
+    }
+
+    /// The amount as `MinorUnits`.
+    pub fn minor_units(&self) -> Option<MinorUnits> {
+        self.amount.map(MinorUnits)
+    }
+
+    pub fn amount_type(&self) -> &AmountType {
+        &self.amount_type
+    }
+
+    /// The amount becoming available after each number of days, for
+    /// distributed availability.
+    pub fn availability(&self) -> &BTreeMap<u16, i64> {
+        &self.availability
+    }
+
+    pub fn category(&self) -> Option<Category> {
+        self.category
+    }
+
+    /// The amount as a decimal in the account's currency, e.g. "123.45" for an
+    /// amount of 12345 in USD.
+    pub fn decimal_amount(&self) -> Option<&str> {
+        self.decimal_amount.as_deref()
+    }
+
+    pub fn funds_type(&self) -> &FundsType {
+        &self.funds_type
+    }
+
+    pub fn item_count(&self) -> Option<u16> {
+        self.item_count
+    }
+
+    /// The date the amount is available on, for a value-dated (V) amount.
+    /// Value dating is given for each amount in an account identifier rather
+    /// than for the account as a whole.
+    pub fn value_date(&self) -> Option<NaiveDate> {
+        self.value_date
+    }
+
+    /// The time of day the amount is available, for a value-dated (V) amount
+    /// that gives one.
+    pub fn value_time(&self) -> Option<Time> {
+        self.value_time
+    }
+
+    /// The date a value-dated (V) amount settles on, which is its value date.
+    /// Amounts with any other funds type don't have one.
+    pub fn settlement_date(&self) -> Option<NaiveDate> {
+        match self.funds_type {
+            FundsType::ValueDated => self.value_date,
+            _ => None,
+        }
+    }
+
+    /// Whether the amount settles after the given as-of date, e.g. a balance
+    /// the bank has reported ahead of time.
+    pub fn is_future_dated(&self, as_of: NaiveDate) -> bool {
+        self.settlement_date().is_some_and(|date| date > as_of)
+    }
+
+    /// Parse the summaries in the fields of an account identifier that follow
+    /// the currency code. Errors hold the index of the offending field in
+    /// `fields` along with a description of the problem.
+    fn parse(
+        fields: &[&str],
+        currency_code: &str,
+        options: &ParseOptions,
+    ) -> Result<Vec<Amount>, (usize, &'static str)> {
+        let amounts = AmountFields::split(fields, options)?
+            .into_iter()
+            .map(|fields| {
+                let amount: Option<i64> = parse_amount(fields.amount);
+                Amount {
+                    amount,
+                    amount_type: fields.amount_type,
+                    availability: fields.funds.availability,
+                    category: Category::from_code(fields.type_code),
+                    decimal_amount: amount
+                        .map(|a| options.currency_exponents.to_decimal(a, currency_code)),
+                    funds_type: fields.funds.funds_type,
+                    item_count: parse_int(fields.item_count),
+                    value_date: fields.funds.value_date,
+                    value_time: fields.funds.value_time,
+                }
+            })
+            .collect();
+
+        Ok(amounts)
+    }
+}
+
+/// The fields of one balance or summary in an account identifier, before
+/// they're parsed.
+pub(crate) struct AmountFields<'a> {
+    pub(crate) amount: &'a str,
+    pub(crate) amount_type: AmountType,
+    pub(crate) funds: Funds,
+    pub(crate) item_count: &'a str,
+    pub(crate) type_code: &'a str,
+}
+
+impl<'a> AmountFields<'a> {
+    /// Split the fields of an account identifier that follow the currency code
+    /// into summaries. Errors hold the index of the offending field in
+    /// `fields` along with a description of the problem.
+    pub(crate) fn split(
+        fields: &[&'a str],
+        options: &ParseOptions,
+    ) -> Result<Vec<AmountFields<'a>>, (usize, &'static str)> {
         let mut amounts = Vec::new();
         let mut next_start_index = 0;
 
-        while fields.len() > next_start_index + 1 {
-            let mut amount = Amount {
-                amount: parse_int(fields[next_start_index + 1]),
-                amount_type: AmountType::parse(fields[next_start_index]),
-                availability: HashMap::new(),
-                funds_type: FundsType::parse(fields[next_start_index + 3]),
-                item_count: parse_int(fields[next_start_index + 2]),
-                value_date: None,
-                value_time: None,
-            };
+        while next_start_index < fields.len() {
+            let remaining = &fields[next_start_index..];
+            if remaining.iter().all(|f| parse_str(f).is_empty()) {
+                break;
+            }
 
-            match amount.funds_type {
-                FundsType::ValueDated => {
-                    amount.value_date = parse_date(fields[next_start_index + 4]);
-                    amount.value_time = parse_time(fields[next_start_index + 5]);
-                    next_start_index = next_start_index + 6;
-                }
-                FundsType::DistributedAvailability(FundsSubType::S) => {
-                    amount
-                        .availability
-                        .insert(0, parse_int(fields[next_start_index + 4]).unwrap());
-                    amount
-                        .availability
-                        .insert(1, parse_int(fields[next_start_index + 5]).unwrap());
-                    amount
-                        .availability
-                        .insert(2, parse_int(fields[next_start_index + 6]).unwrap());
-                    next_start_index = next_start_index + 7;
-                }
-                FundsType::DistributedAvailability(FundsSubType::D) => {
-                    let num_distributions = parse_int(fields[next_start_index + 4]).unwrap_or(0);
-                    next_start_index = next_start_index + 5;
-
-                    for _ in 0..num_distributions {
-                        match (
-                            parse_int(fields[next_start_index]),
-                            parse_int(fields[next_start_index + 1]),
-                        ) {
-                            (Some(days), Some(amt)) => {
-                                amount.availability.insert(days, amt);
-                            }
-                            _ => {}
-                        }
-
-                        next_start_index = next_start_index + 2;
-                    }
-                }
-                _ => {
-                    next_start_index = next_start_index + 4;
-                }
+            // Trailing item count and funds type fields may be left off when
+            // the record is terminated early.
+            let optional_field = |i: usize| remaining.get(i).copied().unwrap_or("");
+
+            let type_code = remaining[0];
+            if parse_str(type_code).is_empty() {
+                return Err((next_start_index, "Expected a type code, but found none."));
+            }
+            let amount_type = AmountType::parse_with_custom_codes(type_code, &options.custom_codes)
+                .map_err(|_| {
+                    (
+                        next_start_index,
+                        "Expected a type code, but found a non-number.",
+                    )
+                })?;
+
+            if let (AmountType::Status(..), Some(len)) = (&amount_type, status_len(remaining)) {
+                amounts.push(AmountFields {
+                    amount: optional_field(1),
+                    amount_type,
+                    funds: Funds::parse(&[], options)?,
+                    item_count: "",
+                    type_code,
+                });
+                next_start_index += len;
+                continue;
             }
 
-            amounts.push(amount);
+            let funds = Funds::parse(remaining.get(3..).unwrap_or(&[]), options)
+                .map_err(|(index, detail)| (next_start_index + 3 + index, detail))?;
+            next_start_index += 3 + funds.len;
+
+            amounts.push(AmountFields {
+                amount: optional_field(1),
+                amount_type,
+                funds,
+                item_count: optional_field(2),
+                type_code,
+            });
         }
 
-        return amounts;
+        Ok(amounts)
     }
 }
 
+/// The number of fields taken up by a status amount whose item count and
+/// funds type are empty, as they are for a status. Banks differ in whether
+/// they write these as empty fields or leave them out, and some add more empty
+/// fields before the next type code. `None` when either has a value, so that
+/// the amount is read like any other.
+fn status_len(fields: &[&str]) -> Option<usize> {
+    let mut len = 2;
+    for _ in 0..2 {
+        match fields.get(len) {
+            Some(field) if parse_str(field).is_empty() => len += 1,
+            Some(field) if !is_type_code(field) => return None,
+            _ => return Some(len),
+        }
+    }
+
+    while fields.get(len).is_some_and(|f| parse_str(f).is_empty()) {
+        len += 1;
+    }
+    Some(len)
+}
+
+fn is_type_code(field: &str) -> bool {
+    let field = parse_str(field);
+    field.len() == 3 && field.bytes().all(|b| b.is_ascii_digit())
+}
+
 #[derive(Debug)]
 pub enum AmountType {
-    Status(String, AmountSubtype),
-    CreditSummary(String, AmountSubtype),
-    DebitSummary(String, AmountSubtype),
-    Unknown(String, AmountSubtype),
+    Status(u16, AmountSubtype),
+    CreditSummary(u16, AmountSubtype),
+    DebitSummary(u16, AmountSubtype),
+    Unknown(u16, AmountSubtype),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AmountSubtype {
     AchNetPosition,
@@ -347,284 +668,330 @@ pub enum AmountSubtype {
     TwoOrMoreDaysFloat,
     Unknown,
     ZeroDayFloat,
+    /// A custom code with the label given to it in `ParseOptions::custom_codes`,
+    /// serialized as the label alone.
+    #[serde(untagged)]
+    Labeled(String),
+}
+
+impl AmountSubtype {
+    /// Every summary and balance type code with a subtype, in order, including
+    /// the codes set aside for custom balances and summaries.
+    pub fn all_codes() -> impl Iterator<Item = u16> {
+        (0..=999).filter(|&code| AmountSubtype::try_from(code).is_ok())
+    }
+
+    /// Every type code with this subtype, in order.
+    pub fn codes(&self) -> Vec<u16> {
+        AmountSubtype::all_codes()
+            .filter(|&code| AmountSubtype::try_from(code).as_ref() == Ok(self))
+            .collect()
+    }
+}
+
+impl TryFrom<u16> for AmountSubtype {
+    type Error = UnknownTypeCode;
+
+    fn try_from(code: u16) -> Result<AmountSubtype, UnknownTypeCode> {
+        let (AmountType::Status(_, subtype)
+        | AmountType::CreditSummary(_, subtype)
+        | AmountType::DebitSummary(_, subtype)
+        | AmountType::Unknown(_, subtype)) = AmountType::from_code(code);
+
+        match subtype {
+            AmountSubtype::Unknown => Err(UnknownTypeCode(code)),
+            subtype => Ok(subtype),
+        }
+    }
 }
 
 impl AmountType {
-    fn parse(type_code: &str) -> AmountType {
-        let code = parse_string(type_code);
-
-        match type_code {
-            "010" => AmountType::Status(code, AmountSubtype::OpeningLedger),
-            "011" => AmountType::Status(code, AmountSubtype::AverageOpeningLedgerMtd),
-            "012" => AmountType::Status(code, AmountSubtype::AverageOpeningLedgerYtd),
-            "015" => AmountType::Status(code, AmountSubtype::ClosingLedger),
-            "020" => AmountType::Status(code, AmountSubtype::AverageClosingLedgerMtd),
-            "021" => AmountType::Status(code, AmountSubtype::AverageClosingLedgerPreviousMonth),
-            "022" => AmountType::Status(code, AmountSubtype::AggregateBalanceAdjustments),
-            "024" => AmountType::Status(code, AmountSubtype::AverageClosingLedgerYtdPreviousMonth),
-            "025" => AmountType::Status(code, AmountSubtype::AverageClosingLedgerYtd),
-            "030" => AmountType::Status(code, AmountSubtype::CurrentLedger),
-            "037" => AmountType::Status(code, AmountSubtype::AchNetPosition),
-            "039" => AmountType::Status(
+    pub fn code(&self) -> u16 {
+        match *self {
+            AmountType::Status(c, _)
+            | AmountType::CreditSummary(c, _)
+            | AmountType::DebitSummary(c, _)
+            | AmountType::Unknown(c, _) => c,
+        }
+    }
+
+    /// The direction of a credit or debit summary. Balances don't have one.
+    pub fn direction(&self) -> Direction {
+        match self {
+            AmountType::CreditSummary(..) => Direction::Credit,
+            AmountType::DebitSummary(..) => Direction::Debit,
+            AmountType::Status(..) | AmountType::Unknown(..) => Direction::Unknown,
+        }
+    }
+
+    /// Parse the type code, reading the custom codes that have been given a
+    /// label and direction with those.
+    pub(crate) fn parse_with_custom_codes(
+        type_code: &str,
+        custom_codes: &CustomCodes,
+    ) -> Result<AmountType, InvalidTypeCode> {
+        let code = parse_type_code(type_code)?;
+        let Some((label, direction)) = custom_codes.get(code) else {
+            return Ok(AmountType::from_code(code));
+        };
+
+        let subtype = AmountSubtype::Labeled(label.to_string());
+        Ok(match direction {
+            Direction::Credit => AmountType::CreditSummary(code, subtype),
+            Direction::Debit => AmountType::DebitSummary(code, subtype),
+            Direction::Unknown => AmountType::Status(code, subtype),
+        })
+    }
+
+    pub(crate) fn from_code(code: u16) -> AmountType {
+        match code {
+            10 => AmountType::Status(code, AmountSubtype::OpeningLedger),
+            11 => AmountType::Status(code, AmountSubtype::AverageOpeningLedgerMtd),
+            12 => AmountType::Status(code, AmountSubtype::AverageOpeningLedgerYtd),
+            15 => AmountType::Status(code, AmountSubtype::ClosingLedger),
+            20 => AmountType::Status(code, AmountSubtype::AverageClosingLedgerMtd),
+            21 => AmountType::Status(code, AmountSubtype::AverageClosingLedgerPreviousMonth),
+            22 => AmountType::Status(code, AmountSubtype::AggregateBalanceAdjustments),
+            24 => AmountType::Status(code, AmountSubtype::AverageClosingLedgerYtdPreviousMonth),
+            25 => AmountType::Status(code, AmountSubtype::AverageClosingLedgerYtd),
+            30 => AmountType::Status(code, AmountSubtype::CurrentLedger),
+            37 => AmountType::Status(code, AmountSubtype::AchNetPosition),
+            39 => AmountType::Status(
                 code,
                 AmountSubtype::OpeningAvailableAndTotalSameDayAchDtcDeposit,
             ),
-            "040" => AmountType::Status(code, AmountSubtype::OpeningAvailable),
-            "041" => AmountType::Status(code, AmountSubtype::AverageOpeningAvailableMtd),
-            "042" => AmountType::Status(code, AmountSubtype::AverageOpeningAvailableYtd),
-            "043" => AmountType::Status(code, AmountSubtype::AverageAvailablePreviousMonth),
-            "044" => AmountType::Status(code, AmountSubtype::DisbursingOpeningAvailableBalance),
-            "045" => AmountType::Status(code, AmountSubtype::ClosingAvailable),
-            "050" => AmountType::Status(code, AmountSubtype::AverageClosingAvailableMtd),
-            "051" => AmountType::Status(code, AmountSubtype::AverageClosingAvailableLastMonth),
-            "054" => AmountType::Status(code, AmountSubtype::AverageClosingAvailableYtdLastMonth),
-            "055" => AmountType::Status(code, AmountSubtype::AverageClosingAvailableYtd),
-            "056" => AmountType::Status(code, AmountSubtype::LoanBalance),
-            "057" => AmountType::Status(code, AmountSubtype::TotalInvestmentPosition),
-            "059" => AmountType::Status(code, AmountSubtype::CurrentAvailableCrsSupressed),
-            "060" => AmountType::Status(code, AmountSubtype::CurrentAvailable),
-            "061" => AmountType::Status(code, AmountSubtype::AverageCurrentAvailableMtd),
-            "062" => AmountType::Status(code, AmountSubtype::AverageCurrentAvailableYtd),
-            "063" => AmountType::Status(code, AmountSubtype::TotalFloat),
-            "065" => AmountType::Status(code, AmountSubtype::TargetBalance),
-            "066" => AmountType::Status(code, AmountSubtype::AdjustedBalance),
-            "067" => AmountType::Status(code, AmountSubtype::AdjustedBalanceMtd),
-            "068" => AmountType::Status(code, AmountSubtype::AdjustedBalanceYtd),
-            "070" => AmountType::Status(code, AmountSubtype::ZeroDayFloat),
-            "072" => AmountType::Status(code, AmountSubtype::OneDayFloat),
-            "073" => AmountType::Status(code, AmountSubtype::FloatAdjustment),
-            "074" => AmountType::Status(code, AmountSubtype::TwoOrMoreDaysFloat),
-            "075" => AmountType::Status(code, AmountSubtype::ThreeOrMoreDaysFloat),
-            "076" => AmountType::Status(code, AmountSubtype::AdjustmentToBalances),
-            "077" => AmountType::Status(code, AmountSubtype::AverageAdjustmentToBalancesMtd),
-            "078" => AmountType::Status(code, AmountSubtype::AverageAdjustmentToBalancesYtd),
-            "079" => AmountType::Status(code, AmountSubtype::FourDayFloat),
-            "080" => AmountType::Status(code, AmountSubtype::FiveDayFloat),
-            "081" => AmountType::Status(code, AmountSubtype::SixDayFloat),
-            "082" => AmountType::Status(code, AmountSubtype::Average1DayFloatMtd),
-            "083" => AmountType::Status(code, AmountSubtype::Average1DayFloatYtd),
-            "084" => AmountType::Status(code, AmountSubtype::Average2DayFloatMtd),
-            "085" => AmountType::Status(code, AmountSubtype::Average2DayFloatYtd),
-            "086" => AmountType::Status(code, AmountSubtype::TransferCalculation),
-            "100" => AmountType::CreditSummary(code, AmountSubtype::TotalCredits),
-            "101" => AmountType::CreditSummary(code, AmountSubtype::TotalCreditAmountMtd),
-            "105" => AmountType::CreditSummary(code, AmountSubtype::CreditsNotDetailed),
-            "106" => AmountType::CreditSummary(code, AmountSubtype::DepositsSubjectToFloat),
-            "107" => AmountType::CreditSummary(code, AmountSubtype::TotalAdjustmentCreditsYtd),
-            "109" => AmountType::CreditSummary(code, AmountSubtype::CurrentDayTotalLockboxDeposits),
-            "110" => AmountType::CreditSummary(code, AmountSubtype::TotalLockboxDeposits),
-            "120" => AmountType::CreditSummary(code, AmountSubtype::EdiTransactionCredit),
-            "130" => AmountType::CreditSummary(code, AmountSubtype::TotalConcentrationCredits),
-            "131" => AmountType::CreditSummary(code, AmountSubtype::TotalDtcCredits),
-            "140" => AmountType::CreditSummary(code, AmountSubtype::TotalAchCredits),
-            "146" => AmountType::CreditSummary(code, AmountSubtype::TotalBankCardDeposits),
-            "150" => {
-                AmountType::CreditSummary(code, AmountSubtype::TotalPreauthorizedPaymentCredits)
-            }
-            "160" => {
-                AmountType::CreditSummary(code, AmountSubtype::TotalAchDisbursingFundingCredits)
-            }
-            "162" => {
-                AmountType::CreditSummary(code, AmountSubtype::CorporateTradePaymentSettlement)
-            }
-            "163" => AmountType::CreditSummary(code, AmountSubtype::CorporateTradePaymentCredits),
-            "167" => AmountType::CreditSummary(code, AmountSubtype::AchSettlementCredits),
-            "170" => AmountType::CreditSummary(code, AmountSubtype::TotalOtherCheckDeposits),
-            "178" => AmountType::CreditSummary(code, AmountSubtype::ListPostCredits),
-            "180" => AmountType::CreditSummary(code, AmountSubtype::TotalLoanProceeds),
-            "182" => AmountType::CreditSummary(code, AmountSubtype::TotalBankPreparedDeposits),
-            "185" => AmountType::CreditSummary(code, AmountSubtype::TotalMiscellaneousDeposits),
-            "186" => AmountType::CreditSummary(code, AmountSubtype::TotalCashLetterCredits),
-            "188" => AmountType::CreditSummary(code, AmountSubtype::TotalCashLetterAdjustments),
-            "190" => AmountType::CreditSummary(code, AmountSubtype::TotalIncomingMoneyTransfers),
-            "200" => AmountType::CreditSummary(code, AmountSubtype::TotalAutomaticTransferCredits),
-            "205" => AmountType::CreditSummary(code, AmountSubtype::TotalBookTransferCredits),
-            "207" => AmountType::CreditSummary(
+            40 => AmountType::Status(code, AmountSubtype::OpeningAvailable),
+            41 => AmountType::Status(code, AmountSubtype::AverageOpeningAvailableMtd),
+            42 => AmountType::Status(code, AmountSubtype::AverageOpeningAvailableYtd),
+            43 => AmountType::Status(code, AmountSubtype::AverageAvailablePreviousMonth),
+            44 => AmountType::Status(code, AmountSubtype::DisbursingOpeningAvailableBalance),
+            45 => AmountType::Status(code, AmountSubtype::ClosingAvailable),
+            50 => AmountType::Status(code, AmountSubtype::AverageClosingAvailableMtd),
+            51 => AmountType::Status(code, AmountSubtype::AverageClosingAvailableLastMonth),
+            54 => AmountType::Status(code, AmountSubtype::AverageClosingAvailableYtdLastMonth),
+            55 => AmountType::Status(code, AmountSubtype::AverageClosingAvailableYtd),
+            56 => AmountType::Status(code, AmountSubtype::LoanBalance),
+            57 => AmountType::Status(code, AmountSubtype::TotalInvestmentPosition),
+            59 => AmountType::Status(code, AmountSubtype::CurrentAvailableCrsSupressed),
+            60 => AmountType::Status(code, AmountSubtype::CurrentAvailable),
+            61 => AmountType::Status(code, AmountSubtype::AverageCurrentAvailableMtd),
+            62 => AmountType::Status(code, AmountSubtype::AverageCurrentAvailableYtd),
+            63 => AmountType::Status(code, AmountSubtype::TotalFloat),
+            65 => AmountType::Status(code, AmountSubtype::TargetBalance),
+            66 => AmountType::Status(code, AmountSubtype::AdjustedBalance),
+            67 => AmountType::Status(code, AmountSubtype::AdjustedBalanceMtd),
+            68 => AmountType::Status(code, AmountSubtype::AdjustedBalanceYtd),
+            70 => AmountType::Status(code, AmountSubtype::ZeroDayFloat),
+            72 => AmountType::Status(code, AmountSubtype::OneDayFloat),
+            73 => AmountType::Status(code, AmountSubtype::FloatAdjustment),
+            74 => AmountType::Status(code, AmountSubtype::TwoOrMoreDaysFloat),
+            75 => AmountType::Status(code, AmountSubtype::ThreeOrMoreDaysFloat),
+            76 => AmountType::Status(code, AmountSubtype::AdjustmentToBalances),
+            77 => AmountType::Status(code, AmountSubtype::AverageAdjustmentToBalancesMtd),
+            78 => AmountType::Status(code, AmountSubtype::AverageAdjustmentToBalancesYtd),
+            79 => AmountType::Status(code, AmountSubtype::FourDayFloat),
+            80 => AmountType::Status(code, AmountSubtype::FiveDayFloat),
+            81 => AmountType::Status(code, AmountSubtype::SixDayFloat),
+            82 => AmountType::Status(code, AmountSubtype::Average1DayFloatMtd),
+            83 => AmountType::Status(code, AmountSubtype::Average1DayFloatYtd),
+            84 => AmountType::Status(code, AmountSubtype::Average2DayFloatMtd),
+            85 => AmountType::Status(code, AmountSubtype::Average2DayFloatYtd),
+            86 => AmountType::Status(code, AmountSubtype::TransferCalculation),
+            100 => AmountType::CreditSummary(code, AmountSubtype::TotalCredits),
+            101 => AmountType::CreditSummary(code, AmountSubtype::TotalCreditAmountMtd),
+            105 => AmountType::CreditSummary(code, AmountSubtype::CreditsNotDetailed),
+            106 => AmountType::CreditSummary(code, AmountSubtype::DepositsSubjectToFloat),
+            107 => AmountType::CreditSummary(code, AmountSubtype::TotalAdjustmentCreditsYtd),
+            109 => AmountType::CreditSummary(code, AmountSubtype::CurrentDayTotalLockboxDeposits),
+            110 => AmountType::CreditSummary(code, AmountSubtype::TotalLockboxDeposits),
+            120 => AmountType::CreditSummary(code, AmountSubtype::EdiTransactionCredit),
+            130 => AmountType::CreditSummary(code, AmountSubtype::TotalConcentrationCredits),
+            131 => AmountType::CreditSummary(code, AmountSubtype::TotalDtcCredits),
+            140 => AmountType::CreditSummary(code, AmountSubtype::TotalAchCredits),
+            146 => AmountType::CreditSummary(code, AmountSubtype::TotalBankCardDeposits),
+            150 => AmountType::CreditSummary(code, AmountSubtype::TotalPreauthorizedPaymentCredits),
+            160 => AmountType::CreditSummary(code, AmountSubtype::TotalAchDisbursingFundingCredits),
+            162 => AmountType::CreditSummary(code, AmountSubtype::CorporateTradePaymentSettlement),
+            163 => AmountType::CreditSummary(code, AmountSubtype::CorporateTradePaymentCredits),
+            167 => AmountType::CreditSummary(code, AmountSubtype::AchSettlementCredits),
+            170 => AmountType::CreditSummary(code, AmountSubtype::TotalOtherCheckDeposits),
+            178 => AmountType::CreditSummary(code, AmountSubtype::ListPostCredits),
+            180 => AmountType::CreditSummary(code, AmountSubtype::TotalLoanProceeds),
+            182 => AmountType::CreditSummary(code, AmountSubtype::TotalBankPreparedDeposits),
+            185 => AmountType::CreditSummary(code, AmountSubtype::TotalMiscellaneousDeposits),
+            186 => AmountType::CreditSummary(code, AmountSubtype::TotalCashLetterCredits),
+            188 => AmountType::CreditSummary(code, AmountSubtype::TotalCashLetterAdjustments),
+            190 => AmountType::CreditSummary(code, AmountSubtype::TotalIncomingMoneyTransfers),
+            200 => AmountType::CreditSummary(code, AmountSubtype::TotalAutomaticTransferCredits),
+            205 => AmountType::CreditSummary(code, AmountSubtype::TotalBookTransferCredits),
+            207 => AmountType::CreditSummary(
                 code,
                 AmountSubtype::TotalInternationalMoneyTransferCredits,
             ),
-            "210" => AmountType::CreditSummary(code, AmountSubtype::TotalInternationalCredits),
-            "215" => AmountType::CreditSummary(code, AmountSubtype::TotalLettersOfCredit),
-            "230" => AmountType::CreditSummary(code, AmountSubtype::TotalSecurityCredits),
-            "231" => AmountType::CreditSummary(code, AmountSubtype::TotalCollectionCredits),
-            "239" => AmountType::CreditSummary(code, AmountSubtype::TotalBankersAcceptanceCredits),
-            "245" => AmountType::CreditSummary(code, AmountSubtype::MonthlyDividends),
-            "250" => AmountType::CreditSummary(code, AmountSubtype::TotalChecksPostedAndReturned),
-            "251" => AmountType::CreditSummary(code, AmountSubtype::TotalDebitReversals),
-            "256" => AmountType::CreditSummary(code, AmountSubtype::TotalAchReturnItems),
-            "260" => AmountType::CreditSummary(code, AmountSubtype::TotalRejectedCredits),
-            "270" => AmountType::CreditSummary(code, AmountSubtype::TotalZbaCredits),
-            "271" => AmountType::CreditSummary(code, AmountSubtype::NetZeroBalanceAmount),
-            "280" => {
-                AmountType::CreditSummary(code, AmountSubtype::TotalControlledDisbursingCredits)
-            }
-            "285" => AmountType::CreditSummary(code, AmountSubtype::TotalDtcDisbursingCredits),
-            "294" => AmountType::CreditSummary(code, AmountSubtype::TotalAtmCredits),
-            "302" => AmountType::CreditSummary(code, AmountSubtype::CorrespondentBankDeposit),
-            "303" => AmountType::CreditSummary(code, AmountSubtype::TotalWireTransfersInFF),
-            "304" => AmountType::CreditSummary(code, AmountSubtype::TotalWireTransfersInCHF),
-            "305" => AmountType::CreditSummary(code, AmountSubtype::TotalFedFundsSold),
-            "307" => AmountType::CreditSummary(code, AmountSubtype::TotalTrustCredits),
-            "309" => AmountType::CreditSummary(code, AmountSubtype::TotalValueDatedFunds),
-            "310" => AmountType::CreditSummary(code, AmountSubtype::TotalCommercialDeposits),
-            "315" => AmountType::CreditSummary(code, AmountSubtype::TotalInternationalCreditsFf),
-            "316" => AmountType::CreditSummary(code, AmountSubtype::TotalInternationalCreditsChf),
-            "318" => AmountType::CreditSummary(code, AmountSubtype::TotalForeignCheckPurchased),
-            "319" => AmountType::CreditSummary(code, AmountSubtype::LateDeposit),
-            "320" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesSoldFf),
-            "321" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesSoldChf),
-            "324" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesMaturedFf),
-            "325" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesMaturedChf),
-            "326" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesInterest),
-            "327" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesMatured),
-            "328" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesInterestFf),
-            "329" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesInterestChf),
-            "330" => AmountType::CreditSummary(code, AmountSubtype::TotalEscrowCredits),
-            "332" => AmountType::CreditSummary(
+            210 => AmountType::CreditSummary(code, AmountSubtype::TotalInternationalCredits),
+            215 => AmountType::CreditSummary(code, AmountSubtype::TotalLettersOfCredit),
+            230 => AmountType::CreditSummary(code, AmountSubtype::TotalSecurityCredits),
+            231 => AmountType::CreditSummary(code, AmountSubtype::TotalCollectionCredits),
+            239 => AmountType::CreditSummary(code, AmountSubtype::TotalBankersAcceptanceCredits),
+            245 => AmountType::CreditSummary(code, AmountSubtype::MonthlyDividends),
+            250 => AmountType::CreditSummary(code, AmountSubtype::TotalChecksPostedAndReturned),
+            251 => AmountType::CreditSummary(code, AmountSubtype::TotalDebitReversals),
+            256 => AmountType::CreditSummary(code, AmountSubtype::TotalAchReturnItems),
+            260 => AmountType::CreditSummary(code, AmountSubtype::TotalRejectedCredits),
+            270 => AmountType::CreditSummary(code, AmountSubtype::TotalZbaCredits),
+            271 => AmountType::CreditSummary(code, AmountSubtype::NetZeroBalanceAmount),
+            280 => AmountType::CreditSummary(code, AmountSubtype::TotalControlledDisbursingCredits),
+            285 => AmountType::CreditSummary(code, AmountSubtype::TotalDtcDisbursingCredits),
+            294 => AmountType::CreditSummary(code, AmountSubtype::TotalAtmCredits),
+            302 => AmountType::CreditSummary(code, AmountSubtype::CorrespondentBankDeposit),
+            303 => AmountType::CreditSummary(code, AmountSubtype::TotalWireTransfersInFF),
+            304 => AmountType::CreditSummary(code, AmountSubtype::TotalWireTransfersInCHF),
+            305 => AmountType::CreditSummary(code, AmountSubtype::TotalFedFundsSold),
+            307 => AmountType::CreditSummary(code, AmountSubtype::TotalTrustCredits),
+            309 => AmountType::CreditSummary(code, AmountSubtype::TotalValueDatedFunds),
+            310 => AmountType::CreditSummary(code, AmountSubtype::TotalCommercialDeposits),
+            315 => AmountType::CreditSummary(code, AmountSubtype::TotalInternationalCreditsFf),
+            316 => AmountType::CreditSummary(code, AmountSubtype::TotalInternationalCreditsChf),
+            318 => AmountType::CreditSummary(code, AmountSubtype::TotalForeignCheckPurchased),
+            319 => AmountType::CreditSummary(code, AmountSubtype::LateDeposit),
+            320 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesSoldFf),
+            321 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesSoldChf),
+            324 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesMaturedFf),
+            325 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesMaturedChf),
+            326 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesInterest),
+            327 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesMatured),
+            328 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesInterestFf),
+            329 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesInterestChf),
+            330 => AmountType::CreditSummary(code, AmountSubtype::TotalEscrowCredits),
+            332 => AmountType::CreditSummary(
                 code,
                 AmountSubtype::TotalMiscellaneousSecuritiesCreditsFf,
             ),
-            "336" => AmountType::CreditSummary(
+            336 => AmountType::CreditSummary(
                 code,
                 AmountSubtype::TotalMiscellaneousSecuritiesCreditsChf,
             ),
-            "338" => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesSold),
-            "340" => AmountType::CreditSummary(code, AmountSubtype::TotalBrokerDeposits),
-            "341" => AmountType::CreditSummary(code, AmountSubtype::TotalBrokerDepositsFf),
-            "343" => AmountType::CreditSummary(code, AmountSubtype::TotalBrokerDepositsChf),
-            "350" => AmountType::CreditSummary(code, AmountSubtype::InvestmentSold),
-            "352" => AmountType::CreditSummary(code, AmountSubtype::TotalCashCenterCredits),
-            "355" => AmountType::CreditSummary(code, AmountSubtype::InvestmentInterest),
-            "356" => AmountType::CreditSummary(code, AmountSubtype::TotalCreditAdjustment),
-            "360" => AmountType::CreditSummary(
+            338 => AmountType::CreditSummary(code, AmountSubtype::TotalSecuritiesSold),
+            340 => AmountType::CreditSummary(code, AmountSubtype::TotalBrokerDeposits),
+            341 => AmountType::CreditSummary(code, AmountSubtype::TotalBrokerDepositsFf),
+            343 => AmountType::CreditSummary(code, AmountSubtype::TotalBrokerDepositsChf),
+            350 => AmountType::CreditSummary(code, AmountSubtype::InvestmentSold),
+            352 => AmountType::CreditSummary(code, AmountSubtype::TotalCashCenterCredits),
+            355 => AmountType::CreditSummary(code, AmountSubtype::InvestmentInterest),
+            356 => AmountType::CreditSummary(code, AmountSubtype::TotalCreditAdjustment),
+            360 => AmountType::CreditSummary(
                 code,
                 AmountSubtype::TotalCreditsLessWireTransferAndReturnedChecks,
             ),
-            "361" => AmountType::CreditSummary(
+            361 => AmountType::CreditSummary(
                 code,
                 AmountSubtype::GrandTotalCreditsLessGrandTotalDebits,
             ),
-            "370" => AmountType::CreditSummary(code, AmountSubtype::TotalBackValueCredits),
-            "385" => AmountType::CreditSummary(code, AmountSubtype::TotalUniversalCredits),
-            "389" => AmountType::CreditSummary(code, AmountSubtype::TotalFreightPaymentCredits),
-            "390" => AmountType::CreditSummary(code, AmountSubtype::TotalMiscellaneousCredits),
-            "400" => AmountType::DebitSummary(code, AmountSubtype::TotalDebits),
-            "401" => AmountType::DebitSummary(code, AmountSubtype::TotalDebitAmountMtd),
-            "403" => AmountType::DebitSummary(code, AmountSubtype::TodaysTotalDebits),
-            "405" => AmountType::DebitSummary(
+            370 => AmountType::CreditSummary(code, AmountSubtype::TotalBackValueCredits),
+            385 => AmountType::CreditSummary(code, AmountSubtype::TotalUniversalCredits),
+            389 => AmountType::CreditSummary(code, AmountSubtype::TotalFreightPaymentCredits),
+            390 => AmountType::CreditSummary(code, AmountSubtype::TotalMiscellaneousCredits),
+            400 => AmountType::DebitSummary(code, AmountSubtype::TotalDebits),
+            401 => AmountType::DebitSummary(code, AmountSubtype::TotalDebitAmountMtd),
+            403 => AmountType::DebitSummary(code, AmountSubtype::TodaysTotalDebits),
+            405 => AmountType::DebitSummary(
                 code,
                 AmountSubtype::TotalDebitLessWireTransfersAndChargeBacks,
             ),
-            "406" => AmountType::DebitSummary(code, AmountSubtype::DebitsNotDetailed),
-            "410" => AmountType::DebitSummary(code, AmountSubtype::TotalYtdAdjustment),
-            "412" => {
-                AmountType::DebitSummary(code, AmountSubtype::TotalDebitsExcludingReturnedItems)
-            }
-            "416" => AmountType::DebitSummary(code, AmountSubtype::TotalLockboxDebits),
-            "420" => AmountType::DebitSummary(code, AmountSubtype::EdiTransactionDebits),
-            "430" => AmountType::DebitSummary(code, AmountSubtype::TotalPayableThroughDrafts),
-            "446" => {
-                AmountType::DebitSummary(code, AmountSubtype::TotalAchDisbursementFundingDebits)
-            }
-            "450" => AmountType::DebitSummary(code, AmountSubtype::TotalAchDebits),
-            "463" => AmountType::DebitSummary(code, AmountSubtype::CorporateTradePaymentDebits),
-            "465" => AmountType::DebitSummary(code, AmountSubtype::CorporateTradePaymentSettlement),
-            "467" => AmountType::DebitSummary(code, AmountSubtype::AchSettlementDebits),
-            "470" => AmountType::DebitSummary(code, AmountSubtype::TotalCheckPaid),
-            "471" => AmountType::DebitSummary(code, AmountSubtype::TotalCheckPaidCumulativeMtd),
-            "478" => AmountType::DebitSummary(code, AmountSubtype::ListPostDebits),
-            "480" => AmountType::DebitSummary(code, AmountSubtype::TotalLoanPayments),
-            "482" => AmountType::DebitSummary(code, AmountSubtype::TotalBankOriginatedDebits),
-            "486" => AmountType::DebitSummary(code, AmountSubtype::TotalCashLetterDebits),
-            "490" => AmountType::DebitSummary(code, AmountSubtype::TotalOutgoingMoneyTransfers),
-            "500" => AmountType::DebitSummary(code, AmountSubtype::TotalAutomaticTransferDebits),
-            "505" => AmountType::DebitSummary(code, AmountSubtype::TotalBookTransferDebits),
-            "507" => {
+            406 => AmountType::DebitSummary(code, AmountSubtype::DebitsNotDetailed),
+            410 => AmountType::DebitSummary(code, AmountSubtype::TotalYtdAdjustment),
+            412 => AmountType::DebitSummary(code, AmountSubtype::TotalDebitsExcludingReturnedItems),
+            416 => AmountType::DebitSummary(code, AmountSubtype::TotalLockboxDebits),
+            420 => AmountType::DebitSummary(code, AmountSubtype::EdiTransactionDebits),
+            430 => AmountType::DebitSummary(code, AmountSubtype::TotalPayableThroughDrafts),
+            446 => AmountType::DebitSummary(code, AmountSubtype::TotalAchDisbursementFundingDebits),
+            450 => AmountType::DebitSummary(code, AmountSubtype::TotalAchDebits),
+            463 => AmountType::DebitSummary(code, AmountSubtype::CorporateTradePaymentDebits),
+            465 => AmountType::DebitSummary(code, AmountSubtype::CorporateTradePaymentSettlement),
+            467 => AmountType::DebitSummary(code, AmountSubtype::AchSettlementDebits),
+            470 => AmountType::DebitSummary(code, AmountSubtype::TotalCheckPaid),
+            471 => AmountType::DebitSummary(code, AmountSubtype::TotalCheckPaidCumulativeMtd),
+            478 => AmountType::DebitSummary(code, AmountSubtype::ListPostDebits),
+            480 => AmountType::DebitSummary(code, AmountSubtype::TotalLoanPayments),
+            482 => AmountType::DebitSummary(code, AmountSubtype::TotalBankOriginatedDebits),
+            486 => AmountType::DebitSummary(code, AmountSubtype::TotalCashLetterDebits),
+            490 => AmountType::DebitSummary(code, AmountSubtype::TotalOutgoingMoneyTransfers),
+            500 => AmountType::DebitSummary(code, AmountSubtype::TotalAutomaticTransferDebits),
+            505 => AmountType::DebitSummary(code, AmountSubtype::TotalBookTransferDebits),
+            507 => {
                 AmountType::DebitSummary(code, AmountSubtype::TotalInternationalMoneyTransferDebits)
             }
-            "510" => AmountType::DebitSummary(code, AmountSubtype::TotalInternationalDebits),
-            "515" => AmountType::DebitSummary(code, AmountSubtype::TotalLettersOfCredit),
-            "530" => AmountType::DebitSummary(code, AmountSubtype::TotalSecurityDebits),
-            "532" => {
-                AmountType::DebitSummary(code, AmountSubtype::TotalAmountOfSecuritiesPurchased)
-            }
-            "534" => {
-                AmountType::DebitSummary(code, AmountSubtype::TotalMiscellaneousSecuritiesDbFf)
-            }
-            "536" => {
+            510 => AmountType::DebitSummary(code, AmountSubtype::TotalInternationalDebits),
+            515 => AmountType::DebitSummary(code, AmountSubtype::TotalLettersOfCredit),
+            530 => AmountType::DebitSummary(code, AmountSubtype::TotalSecurityDebits),
+            532 => AmountType::DebitSummary(code, AmountSubtype::TotalAmountOfSecuritiesPurchased),
+            534 => AmountType::DebitSummary(code, AmountSubtype::TotalMiscellaneousSecuritiesDbFf),
+            536 => {
                 AmountType::DebitSummary(code, AmountSubtype::TotalMiscellaneousSecuritiesDebitChf)
             }
-            "537" => AmountType::DebitSummary(code, AmountSubtype::TotalCollectionDebit),
-            "539" => AmountType::DebitSummary(code, AmountSubtype::TotalBankersAcceptancesDebit),
-            "550" => AmountType::DebitSummary(code, AmountSubtype::TotalDepositedItemsReturned),
-            "551" => AmountType::DebitSummary(code, AmountSubtype::TotalCreditReversals),
-            "556" => AmountType::DebitSummary(code, AmountSubtype::TotalAchReturnItems),
-            "560" => AmountType::DebitSummary(code, AmountSubtype::TotalRejectedDebits),
-            "570" => AmountType::DebitSummary(code, AmountSubtype::TotalZbaDebits),
-            "580" => AmountType::DebitSummary(code, AmountSubtype::TotalControlledDisbursingDebits),
-            "583" => {
+            537 => AmountType::DebitSummary(code, AmountSubtype::TotalCollectionDebit),
+            539 => AmountType::DebitSummary(code, AmountSubtype::TotalBankersAcceptancesDebit),
+            550 => AmountType::DebitSummary(code, AmountSubtype::TotalDepositedItemsReturned),
+            551 => AmountType::DebitSummary(code, AmountSubtype::TotalCreditReversals),
+            556 => AmountType::DebitSummary(code, AmountSubtype::TotalAchReturnItems),
+            560 => AmountType::DebitSummary(code, AmountSubtype::TotalRejectedDebits),
+            570 => AmountType::DebitSummary(code, AmountSubtype::TotalZbaDebits),
+            580 => AmountType::DebitSummary(code, AmountSubtype::TotalControlledDisbursingDebits),
+            583 => {
                 AmountType::DebitSummary(code, AmountSubtype::TotalDisbursingChecksPaidEarlyAmount)
             }
-            "584" => {
+            584 => {
                 AmountType::DebitSummary(code, AmountSubtype::TotalDisbursingChecksPaidLaterAmount)
             }
-            "585" => AmountType::DebitSummary(code, AmountSubtype::DisbursingFundingRequirement),
-            "586" => AmountType::DebitSummary(code, AmountSubtype::FrbPresentmentEstimate),
-            "587" => AmountType::DebitSummary(code, AmountSubtype::LateDebitsAfterNotification),
-            "588" => {
+            585 => AmountType::DebitSummary(code, AmountSubtype::DisbursingFundingRequirement),
+            586 => AmountType::DebitSummary(code, AmountSubtype::FrbPresentmentEstimate),
+            587 => AmountType::DebitSummary(code, AmountSubtype::LateDebitsAfterNotification),
+            588 => {
                 AmountType::DebitSummary(code, AmountSubtype::TotalDisbursingChecksPaidLastAmount)
             }
-            "590" => AmountType::DebitSummary(code, AmountSubtype::TotalDtcDebits),
-            "594" => AmountType::DebitSummary(code, AmountSubtype::TotalAtmDebits),
-            "596" => AmountType::DebitSummary(code, AmountSubtype::TotalAprDebits),
-            "601" => AmountType::DebitSummary(code, AmountSubtype::EstimatedTotalDisbursement),
-            "602" => AmountType::DebitSummary(code, AmountSubtype::AdjustedTotalDisbursement),
-            "610" => AmountType::DebitSummary(code, AmountSubtype::TotalFundsRequired),
-            "611" => AmountType::DebitSummary(code, AmountSubtype::TotalWireTransfersOutChf),
-            "612" => AmountType::DebitSummary(code, AmountSubtype::TotalWireTransfersOutFf),
-            "613" => AmountType::DebitSummary(code, AmountSubtype::TotalInternationalDebitChf),
-            "614" => AmountType::DebitSummary(code, AmountSubtype::TotalInternationalDebitFf),
-            "615" => AmountType::DebitSummary(
+            590 => AmountType::DebitSummary(code, AmountSubtype::TotalDtcDebits),
+            594 => AmountType::DebitSummary(code, AmountSubtype::TotalAtmDebits),
+            596 => AmountType::DebitSummary(code, AmountSubtype::TotalAprDebits),
+            601 => AmountType::DebitSummary(code, AmountSubtype::EstimatedTotalDisbursement),
+            602 => AmountType::DebitSummary(code, AmountSubtype::AdjustedTotalDisbursement),
+            610 => AmountType::DebitSummary(code, AmountSubtype::TotalFundsRequired),
+            611 => AmountType::DebitSummary(code, AmountSubtype::TotalWireTransfersOutChf),
+            612 => AmountType::DebitSummary(code, AmountSubtype::TotalWireTransfersOutFf),
+            613 => AmountType::DebitSummary(code, AmountSubtype::TotalInternationalDebitChf),
+            614 => AmountType::DebitSummary(code, AmountSubtype::TotalInternationalDebitFf),
+            615 => AmountType::DebitSummary(
                 code,
                 AmountSubtype::TotalFederalReserveBankCommercialBankDebit,
             ),
-            "617" => AmountType::DebitSummary(code, AmountSubtype::TotalSecuritiesPurchasedChf),
-            "618" => AmountType::DebitSummary(code, AmountSubtype::TotalSecuritiesPurchasedFf),
-            "621" => AmountType::DebitSummary(code, AmountSubtype::TotalBrokerDebitsChf),
-            "623" => AmountType::DebitSummary(code, AmountSubtype::TotalBrokerDebitsFf),
-            "625" => AmountType::DebitSummary(code, AmountSubtype::TotalBrokerDebits),
-            "626" => AmountType::DebitSummary(code, AmountSubtype::TotalFedFundsPurchased),
-            "628" => AmountType::DebitSummary(code, AmountSubtype::TotalCashCenterDebits),
-            "630" => AmountType::DebitSummary(code, AmountSubtype::TotalDebitAdjustments),
-            "632" => AmountType::DebitSummary(code, AmountSubtype::TotalTrustDebits),
-            "640" => AmountType::DebitSummary(code, AmountSubtype::TotalEscrowDebits),
-            "646" => AmountType::DebitSummary(code, AmountSubtype::TransferCalculationDebit),
-            "650" => AmountType::DebitSummary(code, AmountSubtype::InvestmentsPurchased),
-            "655" => AmountType::DebitSummary(code, AmountSubtype::TotalInvestmentInterestDebits),
-            "665" => AmountType::DebitSummary(code, AmountSubtype::InterceptDebits),
-            "670" => AmountType::DebitSummary(code, AmountSubtype::TotalBackValueDebits),
-            "685" => AmountType::DebitSummary(code, AmountSubtype::TotalUniversalDebits),
-            "689" => AmountType::DebitSummary(code, AmountSubtype::FrbFreightPaymentDebits),
-            "690" => AmountType::DebitSummary(code, AmountSubtype::TotalMiscellaneousDebits),
-            "701" => AmountType::Status(code, AmountSubtype::PrincipalLoanBalance),
-            "703" => AmountType::Status(code, AmountSubtype::AvailableCommitmentAmount),
-            "705" => AmountType::Status(code, AmountSubtype::PaymentAmountDue),
-            "707" => AmountType::Status(code, AmountSubtype::PrincipalAmountPastDue),
-            "709" => AmountType::Status(code, AmountSubtype::InterestAmountPastDue),
-            "720" => AmountType::CreditSummary(code, AmountSubtype::TotalLoanPayment),
-            "760" => AmountType::DebitSummary(code, AmountSubtype::LoanDisbursement),
-            other_code => match other_code.parse::<i16>() {
-                Ok(n) if n >= 900 && n <= 919 => {
-                    AmountType::Status(code, AmountSubtype::CustomStatus)
-                }
-                Ok(n) if n >= 920 && n <= 959 => {
-                    AmountType::CreditSummary(code, AmountSubtype::CustomCreditSummary)
-                }
-                Ok(n) if n >= 960 && n <= 999 => {
-                    AmountType::DebitSummary(code, AmountSubtype::CustomDebitSummary)
-                }
-                _ => AmountType::Unknown(code, AmountSubtype::Unknown),
-            },
+            617 => AmountType::DebitSummary(code, AmountSubtype::TotalSecuritiesPurchasedChf),
+            618 => AmountType::DebitSummary(code, AmountSubtype::TotalSecuritiesPurchasedFf),
+            621 => AmountType::DebitSummary(code, AmountSubtype::TotalBrokerDebitsChf),
+            623 => AmountType::DebitSummary(code, AmountSubtype::TotalBrokerDebitsFf),
+            625 => AmountType::DebitSummary(code, AmountSubtype::TotalBrokerDebits),
+            626 => AmountType::DebitSummary(code, AmountSubtype::TotalFedFundsPurchased),
+            628 => AmountType::DebitSummary(code, AmountSubtype::TotalCashCenterDebits),
+            630 => AmountType::DebitSummary(code, AmountSubtype::TotalDebitAdjustments),
+            632 => AmountType::DebitSummary(code, AmountSubtype::TotalTrustDebits),
+            640 => AmountType::DebitSummary(code, AmountSubtype::TotalEscrowDebits),
+            646 => AmountType::DebitSummary(code, AmountSubtype::TransferCalculationDebit),
+            650 => AmountType::DebitSummary(code, AmountSubtype::InvestmentsPurchased),
+            655 => AmountType::DebitSummary(code, AmountSubtype::TotalInvestmentInterestDebits),
+            665 => AmountType::DebitSummary(code, AmountSubtype::InterceptDebits),
+            670 => AmountType::DebitSummary(code, AmountSubtype::TotalBackValueDebits),
+            685 => AmountType::DebitSummary(code, AmountSubtype::TotalUniversalDebits),
+            689 => AmountType::DebitSummary(code, AmountSubtype::FrbFreightPaymentDebits),
+            690 => AmountType::DebitSummary(code, AmountSubtype::TotalMiscellaneousDebits),
+            701 => AmountType::Status(code, AmountSubtype::PrincipalLoanBalance),
+            703 => AmountType::Status(code, AmountSubtype::AvailableCommitmentAmount),
+            705 => AmountType::Status(code, AmountSubtype::PaymentAmountDue),
+            707 => AmountType::Status(code, AmountSubtype::PrincipalAmountPastDue),
+            709 => AmountType::Status(code, AmountSubtype::InterestAmountPastDue),
+            720 => AmountType::CreditSummary(code, AmountSubtype::TotalLoanPayment),
+            760 => AmountType::DebitSummary(code, AmountSubtype::LoanDisbursement),
+            900..=919 => AmountType::Status(code, AmountSubtype::CustomStatus),
+            920..=959 => AmountType::CreditSummary(code, AmountSubtype::CustomCreditSummary),
+            960..=999 => AmountType::DebitSummary(code, AmountSubtype::CustomDebitSummary),
+            _ => AmountType::Unknown(code, AmountSubtype::Unknown),
         }
     }
 }
@@ -634,17 +1001,101 @@ impl Serialize for AmountType {
     where
         S: Serializer,
     {
-        let (code, type_name, sub_type) = match *self {
-            AmountType::Status(ref c, ref t) => (c, "status", t),
-            AmountType::CreditSummary(ref c, ref t) => (c, "credit_summary", t),
-            AmountType::DebitSummary(ref c, ref t) => (c, "debit_summary", t),
-            AmountType::Unknown(ref c, ref t) => (c, "unknown", t),
+        let (type_name, sub_type) = match *self {
+            AmountType::Status(_, ref t) => ("status", t),
+            AmountType::CreditSummary(_, ref t) => ("credit_summary", t),
+            AmountType::DebitSummary(_, ref t) => ("debit_summary", t),
+            AmountType::Unknown(_, ref t) => ("unknown", t),
         };
 
         let mut state = serializer.serialize_struct("AmountType", 3)?;
-        state.serialize_field("code", code)?;
+        state.serialize_field("code", &format!("{:03}", self.code()))?;
         state.serialize_field("type", type_name)?;
         state.serialize_field("subtype", sub_type)?;
         state.end()
     }
 }
+
+impl<'de> Deserialize<'de> for AmountType {
+    /// The type and subtype are derived from the code, except for a custom
+    /// code that was given a label, whose label and type are read back as they
+    /// were written.
+    fn deserialize<D>(deserializer: D) -> Result<AmountType, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Fields {
+            code: String,
+            subtype: Option<AmountSubtype>,
+            #[serde(rename = "type")]
+            type_name: Option<String>,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let custom_codes = match fields.subtype {
+            Some(AmountSubtype::Labeled(label)) => {
+                let code = parse_type_code(&fields.code).map_err(de::Error::custom)?;
+                let direction = match fields.type_name.as_deref() {
+                    Some("credit_summary") => Direction::Credit,
+                    Some("debit_summary") => Direction::Debit,
+                    _ => Direction::Unknown,
+                };
+                CustomCodes::new().set(code, label, direction)
+            }
+            _ => CustomCodes::new(),
+        };
+        AmountType::parse_with_custom_codes(&fields.code, &custom_codes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bai2File;
+
+    #[test]
+    fn summaries_are_read_across_continuations() {
+        let content = "01,A,B,200331,2300,1,,,2/
+02,,B,1,200331,2300,,/
+03,1,USD,010,500,,/
+88,015,-100,,,040,
+88,1000,2,D,2,0,300,
+88,1,700,/
+49,1400,5/
+98,1400,1,7/
+99,1400,1,9/";
+
+        let file = Bai2File::new(content.to_string()).unwrap();
+        let amounts = file.groups[0].accounts()[0].amounts();
+
+        assert_eq!(
+            amounts
+                .iter()
+                .map(|amount| (amount.amount_type().code(), amount.amount()))
+                .collect::<Vec<_>>(),
+            [(10, Some(500)), (15, Some(-100)), (40, Some(1000))]
+        );
+        assert_eq!(amounts[2].item_count(), Some(2));
+        assert_eq!(
+            amounts[2].availability(),
+            &BTreeMap::from([(0, 300), (1, 700)])
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn labeled_custom_codes_keep_their_label_and_type_through_json() {
+        let custom_codes = CustomCodes::new().set(905, "Target balance", Direction::Unknown);
+        let amount_type = AmountType::parse_with_custom_codes("905", &custom_codes).unwrap();
+
+        let json = serde_json::to_string(&amount_type).unwrap();
+        let read: AmountType = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+        assert!(matches!(
+            read,
+            AmountType::Status(905, AmountSubtype::Labeled(label)) if label == "Target balance"
+        ));
+    }
+}
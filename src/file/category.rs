@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use crate::file::util::parse_str;
+
+/// A broad category for transaction and summary type codes, derived from the
+/// code ranges in the BAI2 spec.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Ach,
+    Adjustment,
+    Check,
+    Fee,
+    Interest,
+    Investment,
+    Loan,
+    Lockbox,
+    Return,
+    Sweep,
+    Transfer,
+    Wire,
+}
+
+impl Category {
+    pub fn from_code(code: &str) -> Option<Category> {
+        let code = parse_str(code).parse::<u16>().ok()?;
+
+        match code {
+            // Fees are scattered through other ranges, so check them first
+            398 | 564 | 567 | 661 | 698 => Some(Category::Fee),
+            140..=169 | 256..=258 | 440..=469 | 556..=558 => Some(Category::Ach),
+            110..=119 | 415..=416 => Some(Category::Lockbox),
+            190..=197 | 207..=208 | 490..=497 | 507..=508 => Some(Category::Wire),
+            170..=179 | 395 | 470..=479 => Some(Category::Check),
+            200..=206 | 500..=506 => Some(Category::Transfer),
+            270..=279 | 346..=347 | 570..=579 | 656 => Some(Category::Sweep),
+            251..=254 | 356..=358 | 372 | 397 | 408 | 551..=554 | 630..=631 | 634 | 672 => {
+                Some(Category::Adjustment)
+            }
+            250..=268 | 550..=568 => Some(Category::Return),
+            354 | 359 | 654 | 659 => Some(Category::Interest),
+            230..=249 | 350..=351 | 530..=549 | 650..=651 => Some(Category::Investment),
+            180..=181 | 480..=481 | 720..=728 | 760 => Some(Category::Loan),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The alphabetic codes of the currencies and funds in ISO 4217, in order.
+const ISO_4217: [&str; 182] = [
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR",
+    "XOF", "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+/// A currency code from a group or account identifier. Codes in ISO 4217 are
+/// checked when they're parsed, and anything else is kept as `Other` so that
+/// invalid codes can be found without failing the parse.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Currency {
+    Iso(&'static str),
+    Other(String),
+}
+
+impl Currency {
+    pub fn parse(code: &str) -> Currency {
+        match ISO_4217.binary_search(&code) {
+            Ok(index) => Currency::Iso(ISO_4217[index]),
+            Err(_) => Currency::Other(code.to_string()),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Currency::Iso(code) => code,
+            Currency::Other(code) => code,
+        }
+    }
+
+    /// Whether the code is one of the currencies or funds in ISO 4217.
+    pub fn is_iso(&self) -> bool {
+        matches!(self, Currency::Iso(_))
+    }
+
+    /// The number of digits after the implied decimal point in amounts of the
+    /// currency. See `CurrencyExponents`.
+    pub fn exponent(&self) -> u32 {
+        exponent(self.code())
+    }
+
+    /// Format an amount in minor units of the currency as a decimal string,
+    /// e.g. 12345 as "123.45" in USD.
+    pub fn to_decimal(&self, amount: i64) -> String {
+        to_decimal(amount, self.code())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D>(deserializer: D) -> Result<Currency, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        Ok(Currency::parse(&code))
+    }
+}
+
+/// The number of digits after the implied decimal point in amounts of each
+/// currency, for turning amounts in minor units into decimals. Exponents come
+/// from ISO 4217 unless they've been overridden, e.g. for a bank that reports
+/// a currency in a unit of its own or uses a code that isn't in the standard.
+///
+/// ```
+/// use bai2::CurrencyExponents;
+///
+/// let exponents = CurrencyExponents::new().set("XYZ", 3);
+/// assert_eq!(exponents.exponent("JPY"), 0);
+/// assert_eq!(exponents.to_decimal(12345, "XYZ"), "12.345");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CurrencyExponents {
+    overrides: BTreeMap<String, u32>,
+}
+
+impl CurrencyExponents {
+    pub fn new() -> CurrencyExponents {
+        CurrencyExponents::default()
+    }
+
+    /// Use `exponent` for amounts in the currency instead of the exponent from
+    /// ISO 4217.
+    pub fn set(mut self, currency_code: &str, exponent: u32) -> CurrencyExponents {
+        self.overrides.insert(currency_code.to_string(), exponent);
+        self
+    }
+
+    /// The exponent of the currency, from the overrides or from ISO 4217.
+    /// Currencies that are in neither have two.
+    pub fn exponent(&self, currency_code: &str) -> u32 {
+        self.overrides
+            .get(currency_code)
+            .copied()
+            .unwrap_or_else(|| exponent(currency_code))
+    }
+
+    /// Format an amount in minor units as a decimal string in the given
+    /// currency, e.g. 12345 USD as "123.45" and 12345 JPY as "12345".
+    pub fn to_decimal(&self, amount: i64, currency_code: &str) -> String {
+        format_decimal(amount, self.exponent(currency_code))
+    }
+}
+
+/// The number of digits after the implied decimal point in amounts of the
+/// currency, from ISO 4217. Currencies that aren't listed have two, which
+/// covers most of them, including unknown codes.
+pub fn exponent(currency_code: &str) -> u32 {
+    match currency_code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+/// Format an amount in minor units as a decimal string in the given currency,
+/// e.g. 12345 USD as "123.45" and 12345 JPY as "12345".
+pub fn to_decimal(amount: i64, currency_code: &str) -> String {
+    format_decimal(amount, exponent(currency_code))
+}
+
+fn format_decimal(amount: i64, exponent: u32) -> String {
+    if exponent == 0 {
+        return amount.to_string();
+    }
+
+    let scale = 10u64.pow(exponent);
+    let sign = if amount < 0 { "-" } else { "" };
+    let units = amount.unsigned_abs();
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        units / scale,
+        units % scale,
+        width = exponent as usize
+    )
+}
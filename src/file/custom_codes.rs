@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+
+use super::direction::Direction;
+
+/// The type codes the spec sets aside for banks to define.
+const CUSTOM_CODES: std::ops::RangeInclusive<u16> = 900..=999;
+
+/// What a bank means by the type codes it defines itself, which the spec sets
+/// aside as 900 to 999. Without a meaning, they're read as custom statuses
+/// (900 to 919), credits (920 to 959), and debits (960 to 999) with no label.
+///
+/// A code given a meaning is read with the label as its subtype, and as a
+/// credit or debit in the direction given. A code in an account summary with
+/// an unknown direction is read as a status.
+///
+/// ```
+/// use bai2::{CustomCodes, Direction, ParseOptions};
+///
+/// let options = ParseOptions {
+///     custom_codes: CustomCodes::new()
+///         .set(925, "Payroll sweep", Direction::Credit)
+///         .set(905, "Target balance", Direction::Unknown),
+///     ..ParseOptions::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CustomCodes {
+    codes: BTreeMap<u16, (String, Direction)>,
+}
+
+impl CustomCodes {
+    pub fn new() -> CustomCodes {
+        CustomCodes::default()
+    }
+
+    /// Give the code a label and direction. Codes outside of 900 to 999 keep
+    /// the meaning the spec gives them, so they're ignored.
+    pub fn set(mut self, code: u16, label: impl Into<String>, direction: Direction) -> CustomCodes {
+        if CUSTOM_CODES.contains(&code) {
+            self.codes.insert(code, (label.into(), direction));
+        }
+        self
+    }
+
+    /// The label and direction given to the code, if it's been given one.
+    pub fn get(&self, code: u16) -> Option<(&str, Direction)> {
+        self.codes
+            .get(&code)
+            .map(|(label, direction)| (label.as_str(), *direction))
+    }
+}
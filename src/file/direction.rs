@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Whether a transaction or summary adds money to an account or takes it out,
+/// from the code ranges in the BAI2 spec. Balances and codes outside of those
+/// ranges have an unknown direction.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Credit,
+    Debit,
+    Unknown,
+}
+
+impl Direction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Credit => "credit",
+            Direction::Debit => "debit",
+            Direction::Unknown => "unknown",
+        }
+    }
+
+    /// The amount with the sign of the direction: negative for debits and
+    /// positive otherwise.
+    pub fn signed(&self, amount: i64) -> i64 {
+        match self {
+            Direction::Debit => -amount,
+            Direction::Credit | Direction::Unknown => amount,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
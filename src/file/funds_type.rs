@@ -1,6 +1,14 @@
-use serde::{Serialize, Serializer};
+use chrono::NaiveDate;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
 
-use crate::file::util::parse_string;
+use crate::file::time::Time;
+use crate::file::util::{
+    format_date, format_time, parse_amount, parse_date, parse_int, parse_str, parse_time,
+};
+use crate::file::version::Version;
+use crate::options::ParseOptions;
 
 #[derive(Debug)]
 pub enum FundsType {
@@ -14,7 +22,7 @@ pub enum FundsType {
 
 impl FundsType {
     pub fn parse(value: &str) -> FundsType {
-        match parse_string(value).as_str() {
+        match parse_str(value).as_ref() {
             "0" => FundsType::ImmediateAvailability,
             "1" => FundsType::OneDayAvailability,
             "2" => FundsType::TwoOrMoreDaysAvailability,
@@ -24,6 +32,43 @@ impl FundsType {
             _ => FundsType::Unknown,
         }
     }
+
+    /// The funds type code followed by its value date or availability fields.
+    pub(crate) fn fields(
+        &self,
+        value_date: Option<NaiveDate>,
+        value_time: Option<Time>,
+        availability: &BTreeMap<u16, i64>,
+        version: Option<Version>,
+    ) -> Vec<String> {
+        match self {
+            FundsType::Unknown => vec![String::new()],
+            FundsType::ImmediateAvailability => vec!["0".to_string()],
+            FundsType::OneDayAvailability => vec!["1".to_string()],
+            FundsType::TwoOrMoreDaysAvailability => vec!["2".to_string()],
+            FundsType::ValueDated => vec![
+                "V".to_string(),
+                format_date(value_date, version),
+                format_time(value_time, version),
+            ],
+            FundsType::DistributedAvailability(FundsSubType::S) => {
+                let mut fields = vec!["S".to_string()];
+                for days in 0..3 {
+                    let amount = availability.get(&days).copied().unwrap_or(0);
+                    fields.push(amount.to_string());
+                }
+                fields
+            }
+            FundsType::DistributedAvailability(FundsSubType::D) => {
+                let mut fields = vec!["D".to_string(), availability.len().to_string()];
+                for (days, amount) in availability {
+                    fields.push(days.to_string());
+                    fields.push(amount.to_string());
+                }
+                fields
+            }
+        }
+    }
 }
 
 impl Serialize for FundsType {
@@ -52,8 +97,202 @@ impl Serialize for FundsType {
     }
 }
 
-#[derive(Debug, Serialize)]
+impl<'de> Deserialize<'de> for FundsType {
+    /// The subtype of distributed availability isn't serialized, so it comes
+    /// back as `D`, which can hold availability for any number of days.
+    fn deserialize<D>(deserializer: D) -> Result<FundsType, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "unknown" => Ok(FundsType::Unknown),
+            "immediate_availability" => Ok(FundsType::ImmediateAvailability),
+            "one_day_availability" => Ok(FundsType::OneDayAvailability),
+            "two_or_more_days_availability" => Ok(FundsType::TwoOrMoreDaysAvailability),
+            "value_dated" => Ok(FundsType::ValueDated),
+            "distributed_availability" => Ok(FundsType::DistributedAvailability(FundsSubType::D)),
+            other => Err(de::Error::unknown_variant(
+                other,
+                &[
+                    "unknown",
+                    "immediate_availability",
+                    "one_day_availability",
+                    "two_or_more_days_availability",
+                    "value_dated",
+                    "distributed_availability",
+                ],
+            )),
+        }
+    }
+}
+
+/// A funds type along with the value date or availability fields that follow
+/// it in a summary or transaction detail.
+#[derive(Debug)]
+pub(crate) struct Funds {
+    pub(crate) availability: BTreeMap<u16, i64>,
+    pub(crate) funds_type: FundsType,
+    /// The number of fields used, including the funds type itself.
+    pub(crate) len: usize,
+    pub(crate) value_date: Option<NaiveDate>,
+    pub(crate) value_time: Option<Time>,
+}
+
+impl Funds {
+    /// Parse the funds type at the start of `fields` and whatever follows it
+    /// for that type. A missing funds type is treated as unknown. Errors hold
+    /// the index of the offending field in `fields` along with a description
+    /// of the problem.
+    pub(crate) fn parse(
+        fields: &[&str],
+        options: &ParseOptions,
+    ) -> Result<Funds, (usize, &'static str)> {
+        let mut funds = Funds {
+            availability: BTreeMap::new(),
+            funds_type: FundsType::parse(fields.first().unwrap_or(&"")),
+            len: 1,
+            value_date: None,
+            value_time: None,
+        };
+
+        match funds.funds_type {
+            FundsType::ValueDated => {
+                let value_fields = fields.get(1..3).ok_or((
+                    fields.len(),
+                    "Expected value date and time for value dated amount, but found less.",
+                ))?;
+                funds.value_date = parse_date(value_fields[0], options);
+                funds.value_time = parse_time(value_fields[1], options);
+                funds.len += 2;
+            }
+            FundsType::DistributedAvailability(FundsSubType::S) => {
+                let availability_fields = fields.get(1..4).ok_or((
+                    fields.len(),
+                    "Expected 3 availability amounts for distributed availability, but found less.",
+                ))?;
+                for (days, field) in availability_fields.iter().enumerate() {
+                    let amount = parse_amount(field).ok_or((
+                        1 + days,
+                        "Expected numeric availability amounts for distributed availability.",
+                    ))?;
+                    funds.availability.insert(days as u16, amount);
+                }
+                funds.len += 3;
+            }
+            FundsType::DistributedAvailability(FundsSubType::D) => {
+                let num_distributions: usize = fields
+                    .get(1)
+                    .and_then(|f| parse_int(f))
+                    .ok_or((
+                        1,
+                        "Expected number of distributions for distributed availability, but found none.",
+                    ))?;
+                let distribution_fields = num_distributions
+                    .checked_mul(2)
+                    .and_then(|len| fields.get(2..len.checked_add(2)?))
+                    .ok_or((
+                        fields.len(),
+                        "Expected availability days and amount for each distribution, but found less.",
+                    ))?;
+
+                for distribution in distribution_fields.chunks(2) {
+                    if let (Some(days), Some(amount)) =
+                        (parse_int(distribution[0]), parse_amount(distribution[1]))
+                    {
+                        funds.availability.insert(days, amount);
+                    }
+                }
+                funds.len += 1 + distribution_fields.len();
+            }
+            _ => (),
+        }
+
+        Ok(funds)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub enum FundsSubType {
     S,
     D,
 }
+
+/// An amount becoming available after a number of days, as distributed
+/// availability is serialized. A map keyed by number of days comes out with
+/// string keys in some formats and can't be written at all in others, so
+/// availability is written as a list of these instead.
+#[derive(Deserialize, Serialize)]
+struct AvailableAmount {
+    amount: i64,
+    days: u16,
+}
+
+pub(crate) fn serialize_availability<S>(
+    availability: &BTreeMap<u16, i64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(
+        availability
+            .iter()
+            .map(|(&days, &amount)| AvailableAmount { amount, days }),
+    )
+}
+
+/// Read availability as a list of days and amounts, or as a map of days to
+/// amounts as it was written before schema version 4.
+pub(crate) fn deserialize_availability<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<u16, i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Availability {
+        List(Vec<AvailableAmount>),
+        Map(BTreeMap<String, i64>),
+    }
+
+    match Availability::deserialize(deserializer)? {
+        Availability::List(amounts) => Ok(amounts
+            .into_iter()
+            .map(|available| (available.days, available.amount))
+            .collect()),
+        Availability::Map(amounts) => amounts
+            .into_iter()
+            .map(|(days, amount)| Ok((days.parse().map_err(de::Error::custom)?, amount)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distributed_availability_reads_each_distribution() {
+        let fields = ["D", "2", "0", "100", "1", "200", "text"];
+        let funds = Funds::parse(&fields, &ParseOptions::default()).unwrap();
+
+        assert_eq!(funds.len, 6);
+        assert_eq!(funds.availability, BTreeMap::from([(0, 100), (1, 200)]));
+    }
+
+    #[test]
+    fn distribution_count_too_large_to_fit_is_an_error() {
+        let fields = ["D", "9223372036854775808", "1", "2", "3", "4"];
+        let error = Funds::parse(&fields, &ParseOptions::default()).unwrap_err();
+
+        assert_eq!(
+            error,
+            (
+                6,
+                "Expected availability days and amount for each distribution, but found less."
+            )
+        );
+    }
+}
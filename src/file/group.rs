@@ -1,81 +1,360 @@
 use chrono::NaiveDate;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
+use crate::parallel;
 use crate::scanner::node::Node;
+use crate::writer;
 
-use super::account::Account;
-use super::util::{parse_currency, parse_date, parse_string, parse_time};
+use super::account::{Account, SkippedAccount};
+use super::currency::Currency;
+use super::opaque_record::OpaqueRecord;
+use super::original_format::OriginalFormat;
+use super::time::Time;
+use super::util::{parse_amount, parse_date, parse_optional_string, parse_string, parse_time};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Group {
-    accounts: Vec<Account>,
-    as_of_date: Option<NaiveDate>,
-    as_of_date_modifier: Option<AsOfDateModifier>,
-    as_of_time: Option<String>,
-    currency_code: String,
-    originator: String,
-    status: GroupStatus,
-    ultimate_receiver: String,
+    pub(crate) accounts: Vec<Account>,
+    pub(crate) as_of_date: Option<NaiveDate>,
+    pub(crate) as_of_date_modifier: Option<AsOfDateModifier>,
+    pub(crate) as_of_time: Option<Time>,
+    pub(crate) control_total: Option<i64>,
+    pub(crate) currency_code: Currency,
+    pub(crate) originator: String,
+    #[serde(skip)]
+    pub(crate) original: Option<OriginalFormat>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) other_records: Vec<OpaqueRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) raw: Option<String>,
+    pub(crate) sequence_number: usize,
+    #[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) skipped_accounts: Vec<SkippedAccount>,
+    pub(crate) status: GroupStatus,
+    pub(crate) ultimate_receiver: String,
 }
 
 impl Group {
-    pub fn from_node(node: &Node) -> Result<Group, &'static str> {
-        let header_fields = &node.fields();
-        if header_fields.len() < 7 {
-            return Err("Invalid group header. Expected 7 fields, but found less.");
-        }
+    /// Build a group from its header node. Groups are numbered from 1 in the
+    /// order they appear in the file, and accounts from 1 across all of the
+    /// groups, so `accounts_before` is the number of accounts in the groups
+    /// before this one.
+    pub fn from_node(
+        node: &Node,
+        sequence_number: usize,
+        accounts_before: usize,
+        options: &ParseOptions,
+    ) -> Result<Group, Bai2Error> {
+        let header_fields = Group::header_fields(node, options)?;
+        let group_currency = parse_optional_string(header_fields[6]);
+        let as_of_date = parse_date(header_fields[4], options);
 
-        let trailer_fields = node.sibling_fields();
-        if trailer_fields.len() < 4 {
-            return Err("Invalid group trailer. Expected 4 fields, but found less.");
-        }
+        let control_total = match node.sibling() {
+            Some(trailer) => {
+                let trailer_fields = trailer
+                    .required_fields(4, options.allow_short_records)
+                    .map_err(|(line, field)| Bai2Error::InvalidGroupTrailer { line, field })?;
+                parse_amount(trailer_fields[1])
+            }
+            None if options.allow_missing_trailers => None,
+            None => {
+                return Err(Bai2Error::GroupTrailerMissing {
+                    line: node.line_number(),
+                })
+            }
+        };
 
-        let currency_code = parse_currency(header_fields[6], "USD");
+        let currency_code = Currency::parse(
+            group_currency
+                .as_deref()
+                .unwrap_or(&options.default_currency),
+        );
 
-        let accounts_result = node
-            .children
-            .iter()
-            .map(|n| Account::from_node(n, &currency_code))
-            .collect::<Result<Vec<Account>, &'static str>>();
+        let account_nodes: Vec<_> = node.children().collect();
+        let parse_account = |i: usize, n: &Node| {
+            let sequence_number = accounts_before + i + 1;
+            Account::from_node(
+                n,
+                sequence_number,
+                group_currency.as_deref(),
+                as_of_date,
+                options,
+            )
+        };
+        let accounts_result = if options.skip_invalid_accounts {
+            let mut accounts = Vec::with_capacity(account_nodes.len());
+            let mut skipped_accounts = Vec::new();
+            let results = parallel::map(&account_nodes, options.parallel, parse_account);
+            for (i, result) in results.into_iter().enumerate() {
+                match result {
+                    Ok(account) => accounts.push(account),
+                    Err(error) => skipped_accounts.push(SkippedAccount {
+                        error,
+                        line: account_nodes[i].line_number(),
+                        sequence_number: accounts_before + i + 1,
+                    }),
+                }
+            }
+            Ok((accounts, skipped_accounts))
+        } else {
+            parallel::try_map(&account_nodes, options.parallel, parse_account)
+                .map(|accounts| (accounts, Vec::new()))
+        };
 
-        match accounts_result {
-            Err(e) => Err(e),
-            Ok(accounts) => Ok(Group {
+        let mut group = match accounts_result {
+            Err(e) => return Err(e),
+            Ok((accounts, skipped_accounts)) => Group {
                 accounts,
-                as_of_date: parse_date(header_fields[4]),
-                as_of_date_modifier: AsOfDateModifier::parse(header_fields[7]),
-                as_of_time: parse_time(header_fields[5]),
+                as_of_date,
+                as_of_date_modifier: AsOfDateModifier::parse(header_fields.get(7).unwrap_or(&"")),
+                as_of_time: parse_time(header_fields[5], options),
+                control_total,
                 currency_code,
                 originator: parse_string(header_fields[2]),
+                original: None,
+                other_records: OpaqueRecord::from_parent(node),
+                raw: options.keep_raw_records.then(|| node.raw_with_trailer()),
+                sequence_number,
+                skipped_accounts,
                 status: GroupStatus::parse(header_fields[3]),
                 ultimate_receiver: parse_string(header_fields[1]),
-            }),
+            },
+        };
+
+        if options.preserve_formatting {
+            let original = OriginalFormat::new(
+                &writer::group_header(&group, options.version),
+                node,
+                writer::group_contents(&group, options.version),
+            );
+            // The accounts that were left out won't be written, so the trailer
+            // no longer counts what's in the group
+            group.original = Some(if group.skipped_accounts.is_empty() {
+                original
+            } else {
+                original.without_trailer()
+            });
+        }
+
+        Ok(group)
+    }
+
+    /// The currency and as-of date from a group header that are passed down to
+    /// its accounts.
+    pub(crate) fn account_context(
+        node: &Node,
+        options: &ParseOptions,
+    ) -> Result<(Option<String>, Option<NaiveDate>), Bai2Error> {
+        let header_fields = Group::header_fields(node, options)?;
+        Ok((
+            parse_optional_string(header_fields[6]),
+            parse_date(header_fields[4], options),
+        ))
+    }
+
+    pub(crate) fn header_fields<'a>(
+        node: &Node<'a>,
+        options: &ParseOptions,
+    ) -> Result<Vec<&'a str>, Bai2Error> {
+        node.required_fields(7, options.allow_short_records)
+            .map_err(|(line, field)| Bai2Error::InvalidGroupHeader { line, field })
+    }
+
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    pub fn as_of_date(&self) -> Option<NaiveDate> {
+        self.as_of_date
+    }
+
+    pub fn as_of_date_modifier(&self) -> Option<&AsOfDateModifier> {
+        self.as_of_date_modifier.as_ref()
+    }
+
+    pub fn as_of_time(&self) -> Option<Time> {
+        self.as_of_time
+    }
+
+    /// The signed total of the accounts in the group from its trailer.
+    pub fn control_total(&self) -> Option<i64> {
+        self.control_total
+    }
+
+    /// The totals stated in the trailers of the group's accounts, summed per
+    /// currency. Groups can hold accounts in more than one currency, and the
+    /// single control total in the group trailer adds their minor units
+    /// together regardless, so these are the totals that mean something.
+    pub fn control_totals(&self) -> BTreeMap<Currency, i64> {
+        let mut totals = BTreeMap::new();
+        for account in &self.accounts {
+            if let Some(total) = account.control_total {
+                *totals.entry(account.currency_code.clone()).or_insert(0) += total;
+            }
         }
+
+        totals
+    }
+
+    /// The group's currency, or the default currency when the group header
+    /// doesn't have one.
+    pub fn currency(&self) -> &Currency {
+        &self.currency_code
+    }
+
+    pub fn currency_code(&self) -> &str {
+        self.currency_code.code()
+    }
+
+    pub fn originator(&self) -> &str {
+        &self.originator
+    }
+
+    /// The records with codes that aren't part of the format that were found
+    /// in the group but not in any of its accounts. See `OpaqueRecord`.
+    pub fn other_records(&self) -> &[OpaqueRecord] {
+        &self.other_records
+    }
+
+    /// The group header and group trailer records and their continuations
+    /// exactly as they appeared in the file, one line each, when they were
+    /// kept with `ParseOptions::keep_raw_records`. The accounts have their
+    /// own.
+    pub fn raw(&self) -> Option<&str> {
+        self.raw.as_deref()
     }
+
+    /// The position of the group in the file, starting from 1.
+    pub fn sequence_number(&self) -> usize {
+        self.sequence_number
+    }
+
+    /// The accounts that were left out because they couldn't be parsed, when
+    /// `ParseOptions::skip_invalid_accounts` is set. It's only serialized, so
+    /// it's empty for groups loaded from JSON.
+    pub fn skipped_accounts(&self) -> &[SkippedAccount] {
+        &self.skipped_accounts
+    }
+
+    pub fn status(&self) -> &GroupStatus {
+        &self.status
+    }
+
+    pub fn ultimate_receiver(&self) -> &str {
+        &self.ultimate_receiver
+    }
+
+    pub(crate) fn accounts_mut(&mut self) -> &mut [Account] {
+        &mut self.accounts
+    }
+
+    /// Whether both groups describe the same originator, receiver, and as-of date.
+    pub(crate) fn same_group(&self, other: &Group) -> bool {
+        self.originator == other.originator
+            && self.ultimate_receiver == other.ultimate_receiver
+            && self.as_of_date == other.as_of_date
+    }
+
+    /// Replace the header of this group with that of the update, and replace
+    /// or append each of the accounts it contains.
+    pub(crate) fn apply_update(&mut self, update: Group) {
+        for account in update.accounts {
+            match self.accounts.iter_mut().find(|a| a.same_account(&account)) {
+                Some(existing) => *existing = account,
+                None => self.accounts.push(account),
+            }
+        }
+
+        self.as_of_date_modifier = update.as_of_date_modifier;
+        self.as_of_time = update.as_of_time;
+        self.currency_code = update.currency_code;
+        self.status = update.status;
+        self.accounts_changed();
+    }
+
+    /// Remove the accounts contained in the deletion from this group. Returns
+    /// true if the whole group should be removed.
+    pub(crate) fn apply_deletion(&mut self, deletion: &Group) -> bool {
+        if deletion.accounts.is_empty() {
+            return true;
+        }
+
+        self.accounts
+            .retain(|a| !deletion.accounts.iter().any(|d| d.same_account(a)));
+        self.accounts_changed();
+        self.accounts.is_empty()
+    }
+
+    /// Total the accounts again and forget the records the group was parsed
+    /// from, which no longer describe it. The total is left out when an
+    /// account doesn't have one or it's too large to hold.
+    fn accounts_changed(&mut self) {
+        self.control_total = self.accounts.iter().try_fold(0i64, |total, account| {
+            total.checked_add(account.control_total?)
+        });
+        self.original = None;
+        self.raw = None;
+    }
+}
+
+/// A group that was left out of the file because it couldn't be parsed, when
+/// `ParseOptions::skip_invalid_groups` is set.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SkippedGroup {
+    pub error: Bai2Error,
+    /// The line of the group's header.
+    pub line: usize,
+    /// The number the group would have had among the groups of the file.
+    pub sequence_number: usize,
 }
 
-#[derive(Debug, Serialize)]
+/// Whether the data in a group is final or interim, and whether it's for the
+/// previous day or the same day. A code that isn't one of these is kept as it
+/// was sent.
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
 pub enum AsOfDateModifier {
     FinalPreviousDayData,
     FinalSameDayData,
     InterimPreviousDayData,
     InterimSameDayData,
+    Unknown(String),
 }
 
 impl AsOfDateModifier {
-    fn parse(value: &str) -> Option<AsOfDateModifier> {
+    /// The code the modifier was parsed from, as it appeared in the file.
+    pub fn as_code(&self) -> &str {
+        match self {
+            AsOfDateModifier::InterimPreviousDayData => "1",
+            AsOfDateModifier::FinalPreviousDayData => "2",
+            AsOfDateModifier::InterimSameDayData => "3",
+            AsOfDateModifier::FinalSameDayData => "4",
+            AsOfDateModifier::Unknown(code) => code,
+        }
+    }
+
+    /// Parse a modifier from its code, e.g. `"3"` for interim same-day data.
+    /// An empty code means there's no modifier.
+    pub fn parse(value: &str) -> Option<AsOfDateModifier> {
         match parse_string(value).as_str() {
+            "" => None,
             "1" => Some(AsOfDateModifier::InterimPreviousDayData),
             "2" => Some(AsOfDateModifier::FinalPreviousDayData),
             "3" => Some(AsOfDateModifier::InterimSameDayData),
             "4" => Some(AsOfDateModifier::FinalSameDayData),
-            _ => None,
+            code => Some(AsOfDateModifier::Unknown(code.to_string())),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+/// How a group relates to groups for the same originator, receiver, and as-of
+/// date received earlier. A code that isn't one of these is kept as it was
+/// sent.
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum GroupStatus {
     Update,
@@ -86,7 +365,19 @@ pub enum GroupStatus {
 }
 
 impl GroupStatus {
-    fn parse(value: &str) -> GroupStatus {
+    /// The code the status was parsed from, as it appeared in the file.
+    pub fn as_code(&self) -> &str {
+        match self {
+            GroupStatus::Update => "1",
+            GroupStatus::Deletion => "2",
+            GroupStatus::Correction => "3",
+            GroupStatus::TestOnly => "4",
+            GroupStatus::Unknown(code) => code,
+        }
+    }
+
+    /// Parse a status from its code, e.g. `"1"` for an update.
+    pub fn parse(value: &str) -> GroupStatus {
         match parse_string(value).as_str() {
             "1" => GroupStatus::Update,
             "2" => GroupStatus::Deletion,
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::currency::{Currency, CurrencyExponents};
+use super::direction::Direction;
+
+/// An amount in the minor units of its currency, e.g. cents for USD, which is
+/// how amounts are written in BAI2 files. Keeping amounts in this type rather
+/// than a bare integer stops them from being mixed up with decimal amounts,
+/// and its arithmetic is checked so that totals can't silently overflow.
+///
+/// ```
+/// use bai2::{Currency, MinorUnits};
+///
+/// let total = MinorUnits(12345).checked_add(MinorUnits(55)).unwrap();
+/// assert_eq!(total.to_decimal(&Currency::parse("USD")), "124.00");
+/// ```
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(transparent)]
+pub struct MinorUnits(pub i64);
+
+impl MinorUnits {
+    pub const ZERO: MinorUnits = MinorUnits(0);
+
+    /// The amount as a plain number of minor units.
+    pub fn value(self) -> i64 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: MinorUnits) -> Option<MinorUnits> {
+        self.0.checked_add(other.0).map(MinorUnits)
+    }
+
+    pub fn checked_sub(self, other: MinorUnits) -> Option<MinorUnits> {
+        self.0.checked_sub(other.0).map(MinorUnits)
+    }
+
+    pub fn checked_neg(self) -> Option<MinorUnits> {
+        self.0.checked_neg().map(MinorUnits)
+    }
+
+    /// The amount multiplied by a plain number, e.g. a count of items.
+    pub fn checked_mul(self, factor: i64) -> Option<MinorUnits> {
+        self.0.checked_mul(factor).map(MinorUnits)
+    }
+
+    /// The sum of the amounts, or `None` if it overflows.
+    pub fn checked_sum(amounts: impl IntoIterator<Item = MinorUnits>) -> Option<MinorUnits> {
+        amounts
+            .into_iter()
+            .try_fold(MinorUnits::ZERO, MinorUnits::checked_add)
+    }
+
+    /// The amount with the sign of the direction: negative for debits and
+    /// positive otherwise. See `Direction::signed`.
+    pub fn signed(self, direction: Direction) -> Option<MinorUnits> {
+        match direction {
+            Direction::Debit => self.checked_neg(),
+            Direction::Credit | Direction::Unknown => Some(self),
+        }
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Format the amount as a decimal string in the currency, e.g. 12345 as
+    /// "123.45" in USD.
+    pub fn to_decimal(self, currency: &Currency) -> String {
+        currency.to_decimal(self.0)
+    }
+
+    /// Format the amount as a decimal string in the currency, using the
+    /// exponents given rather than the ones from ISO 4217.
+    pub fn to_decimal_with(self, currency: &Currency, exponents: &CurrencyExponents) -> String {
+        exponents.to_decimal(self.0, currency.code())
+    }
+}
+
+impl From<i64> for MinorUnits {
+    fn from(amount: i64) -> MinorUnits {
+        MinorUnits(amount)
+    }
+}
+
+impl From<MinorUnits> for i64 {
+    fn from(amount: MinorUnits) -> i64 {
+        amount.0
+    }
+}
+
+impl TryFrom<u64> for MinorUnits {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(amount: u64) -> Result<MinorUnits, Self::Error> {
+        i64::try_from(amount).map(MinorUnits)
+    }
+}
+
+/// The number of minor units, without a currency or decimal point.
+impl fmt::Display for MinorUnits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
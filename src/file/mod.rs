@@ -1,6 +1,15 @@
 pub mod account;
+pub mod category;
+pub mod currency;
+pub mod custom_codes;
+pub mod direction;
 pub mod funds_type;
 pub mod group;
+pub mod minor_units;
+pub mod opaque_record;
+pub mod original_format;
+pub mod time;
 pub mod transaction;
 pub mod transaction_type;
 pub mod util;
+pub mod version;
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::scanner::node::Node;
+
+/// A record with a code that isn't part of BAI2, such as a bank's own
+/// extension, kept as it was found so that it isn't lost and can be written
+/// back out. Records like this are only accepted with
+/// `ParseOptions::allow_unknown_records`.
+///
+/// Each one is kept on the file, group, or account it was found within. A
+/// record between a transaction detail and the next record belongs to the
+/// transaction's account.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OpaqueRecord {
+    pub code: String,
+    /// The fields after the record code, including those from any
+    /// continuations, without the `/` that ends the record.
+    pub fields: Vec<String>,
+    /// The line number of the record in the file.
+    pub line: usize,
+    /// The number of records it came after among those it was found with:
+    /// the groups of a file, the accounts of a group, or the transactions of
+    /// an account.
+    pub position: usize,
+    /// The record and its continuations exactly as they appeared in the file,
+    /// one line each.
+    pub raw: String,
+}
+
+impl OpaqueRecord {
+    /// The unrecognized records found within the node, with their positions
+    /// among its children.
+    pub(crate) fn from_parent(parent: &Node) -> Vec<OpaqueRecord> {
+        parent
+            .others()
+            .map(|node| {
+                let position = parent
+                    .children()
+                    .take_while(|child| child.line_number() < node.line_number())
+                    .count();
+                OpaqueRecord::from_node(&node, position)
+            })
+            .collect()
+    }
+
+    fn from_node(node: &Node, position: usize) -> OpaqueRecord {
+        let mut fields = node.fields().into_iter();
+        let code = fields.next().unwrap_or_default().to_string();
+        let mut fields: Vec<String> = fields.map(str::to_string).collect();
+        if let Some(last) = fields.last_mut() {
+            let trimmed = last.trim_end();
+            *last = trimmed.strip_suffix('/').unwrap_or(trimmed).to_string();
+        }
+
+        OpaqueRecord {
+            code,
+            fields,
+            line: node.line_number(),
+            position,
+            raw: node.raw(),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::scanner::node::Node;
+
+/// How a record was laid out in the file it was parsed from, kept with
+/// `ParseOptions::preserve_formatting` so that the writer can write the
+/// record back exactly as it was when nothing in it has changed: with the
+/// same spacing, empty fields, and continuations.
+///
+/// A record counts as changed when the fields the writer would write for it
+/// are no longer the ones it would have written just after parsing. A
+/// trailer counts as changed when anything between it and its header has,
+/// including records being added, removed, or reordered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OriginalFormat {
+    /// A hash of the fields of every record from the record to its trailer.
+    contents: u64,
+    /// Whether the file ended with a line ending. Only used for the file.
+    final_line_ending: bool,
+    /// A hash of the fields the writer would have written for the record.
+    fingerprint: u64,
+    /// The line ending the file used. Only used for the file.
+    line_ending: &'static str,
+    /// The record and its continuations, one line each.
+    record: String,
+    /// The record's trailer and its continuations, if it had a trailer.
+    trailer: Option<String>,
+}
+
+impl OriginalFormat {
+    pub(crate) fn new(fields: &[String], node: &Node, contents: u64) -> OriginalFormat {
+        OriginalFormat {
+            contents,
+            final_line_ending: true,
+            fingerprint: fingerprint(fields),
+            line_ending: "\n",
+            record: node.raw(),
+            trailer: node.sibling().map(|trailer| trailer.raw()),
+        }
+    }
+
+    /// Forget the record's trailer, for a record that has lost some of what
+    /// was within it while it was parsed.
+    pub(crate) fn without_trailer(mut self) -> OriginalFormat {
+        self.trailer = None;
+        self
+    }
+
+    /// Take the line endings from the content of the file, which the scanner
+    /// doesn't keep.
+    pub(crate) fn set_line_endings(&mut self, content: &str) {
+        if content.contains("\r\n") {
+            self.line_ending = "\r\n";
+        }
+        self.final_line_ending = content.ends_with('\n');
+    }
+
+    /// The original lines of the record, if these are still the fields it was
+    /// parsed with.
+    pub(crate) fn record(&self, fields: &[String]) -> Option<&str> {
+        (fingerprint(fields) == self.fingerprint).then_some(self.record.as_str())
+    }
+
+    /// The original lines of the trailer, if every record within was written
+    /// as it was and they're still the records it was parsed with.
+    pub(crate) fn trailer(&self, unchanged: bool, contents: impl FnOnce() -> u64) -> Option<&str> {
+        self.trailer
+            .as_deref()
+            .filter(|_| unchanged && contents() == self.contents)
+    }
+
+    pub(crate) fn line_ending(&self) -> &'static str {
+        self.line_ending
+    }
+
+    pub(crate) fn final_line_ending(&self) -> bool {
+        self.final_line_ending
+    }
+}
+
+pub(crate) fn fingerprint(fields: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    fields.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bai2File, ParseOptions};
+
+    const CONTENT: &str = "01,A,B,200331,2300,1,,,2/\r
+02,,B,1,200331,2300,,/\r
+03,1,USD,010,500,,,015,\r
+88,-100,,/\r
+16,175,100,0,REF1  ,,first part of the text/\r
+88,and the rest/\r
+16,475,300,,,,/\r
+49,800,6/\r
+98,800,1,8/\r
+99,800,1,10/";
+
+    fn preserving() -> ParseOptions {
+        ParseOptions {
+            preserve_formatting: true,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn unchanged_files_are_written_byte_for_byte() {
+        let file = Bai2File::new_with_options(CONTENT.to_string(), &preserving()).unwrap();
+
+        assert_eq!(file.to_bai2(), CONTENT);
+    }
+
+    #[test]
+    fn only_changed_records_and_their_trailers_are_rewritten() {
+        let mut file = Bai2File::new_with_options(CONTENT.to_string(), &preserving()).unwrap();
+        file.transactions_mut().last().unwrap().amount = Some(400);
+
+        let written = file.to_bai2();
+
+        assert!(
+            written.starts_with(
+                "01,A,B,200331,2300,1,,,2/\r
+02,,B,1,200331,2300,,/\r
+03,1,USD,010,500,,,015,\r
+88,-100,,/\r
+16,175,100,0,REF1  ,,first part of the text/\r
+88,and the rest/\r
+16,475,400,"
+            ),
+            "{}",
+            written
+        );
+        assert!(
+            written.ends_with("49,900,6/\r\n98,900,1,8/\r\n99,900,1,10/"),
+            "{}",
+            written
+        );
+    }
+}
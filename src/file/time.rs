@@ -0,0 +1,70 @@
+use chrono::NaiveTime;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+
+/// A time from a BAI2 record. Banks report the end of the business day as
+/// 2400 or 9999 rather than a time of day, so that's kept as `EndOfDay`, or
+/// as `Raw` with the code that was sent when `ParseOptions::end_of_day` asks
+/// for it.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Time {
+    At(NaiveTime),
+    EndOfDay,
+    Raw(u16),
+}
+
+impl Time {
+    /// The time of day, or `None` for the end of the day.
+    pub fn time(&self) -> Option<NaiveTime> {
+        match *self {
+            Time::At(time) => Some(time),
+            Time::EndOfDay | Time::Raw(_) => None,
+        }
+    }
+
+    pub fn is_end_of_day(&self) -> bool {
+        matches!(self, Time::EndOfDay | Time::Raw(_))
+    }
+}
+
+impl From<NaiveTime> for Time {
+    fn from(time: NaiveTime) -> Time {
+        Time::At(time)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Time::At(time) => write!(f, "{}", time),
+            Time::EndOfDay => f.write_str("end of day"),
+            Time::Raw(code) => write!(f, "{:04}", code),
+        }
+    }
+}
+
+impl Serialize for Time {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D>(deserializer: D) -> Result<Time, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let time = String::deserialize(deserializer)?;
+        match time.as_str() {
+            "end of day" => Ok(Time::EndOfDay),
+            code if code.len() == 4 && code.bytes().all(|b| b.is_ascii_digit()) => {
+                code.parse().map(Time::Raw).map_err(de::Error::custom)
+            }
+            time => time.parse().map(Time::At).map_err(de::Error::custom),
+        }
+    }
+}
@@ -1,96 +1,330 @@
 use chrono::NaiveDate;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
-use super::funds_type::{FundsSubType, FundsType};
-use super::transaction_type::TransactionType;
-use super::util::{parse_date, parse_int, parse_string, parse_time};
+use super::category::Category;
+use super::currency::Currency;
+use super::funds_type::{deserialize_availability, serialize_availability, Funds, FundsType};
+use super::minor_units::MinorUnits;
+use super::original_format::OriginalFormat;
+use super::time::Time;
+use super::transaction_type::{InvalidTypeCode, TransactionType};
+use super::util::{parse_amount, parse_string, parse_text};
 
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
 use crate::scanner::node::Node;
+use crate::writer;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Transaction {
-    amount: Option<u64>,
-    availability: HashMap<u16, i64>,
-    bank_reference_number: String,
-    customer_reference_number: String,
-    funds_type: FundsType,
-    text: Vec<String>,
-    transaction_type: TransactionType,
-    value_date: Option<NaiveDate>,
-    value_time: Option<String>,
+    pub(crate) amount: Option<u64>,
+    pub(crate) as_of_date: Option<NaiveDate>,
+    #[serde(
+        serialize_with = "serialize_availability",
+        deserialize_with = "deserialize_availability"
+    )]
+    pub(crate) availability: BTreeMap<u16, i64>,
+    pub(crate) bank_reference_number: String,
+    pub(crate) category: Option<Category>,
+    pub(crate) currency_code: Currency,
+    pub(crate) customer_reference_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) custom_category: Option<String>,
+    pub(crate) decimal_amount: Option<String>,
+    pub(crate) funds_type: FundsType,
+    #[serde(skip)]
+    pub(crate) original: Option<OriginalFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) raw: Option<String>,
+    pub(crate) sequence_number: usize,
+    #[serde(deserialize_with = "deserialize_text")]
+    pub(crate) text: String,
+    pub(crate) transaction_type: TransactionType,
+    pub(crate) value_date: Option<NaiveDate>,
+    pub(crate) value_time: Option<Time>,
 }
 
 impl Transaction {
-    pub fn from_node(node: &Node) -> Result<Transaction, &'static str> {
-        let fields = node.fields();
-        let num_fields = fields.len();
-
-        let transaction_type_code = parse_string(fields[1]);
-        let transaction_type = TransactionType::parse(&transaction_type_code);
-
-        let mut next_start_index = 4;
-        let mut value_date: Option<NaiveDate> = None;
-        let mut value_time: Option<String> = None;
-        let mut availability: HashMap<u16, i64> = HashMap::new();
-        let funds_type = FundsType::parse(fields.get(3).unwrap_or(&""));
-
-        match funds_type {
-            FundsType::ValueDated => {
-                value_date = parse_date(fields[next_start_index]);
-                value_time = parse_time(fields[next_start_index + 1]);
-                next_start_index = next_start_index + 2;
-            }
-            FundsType::DistributedAvailability(FundsSubType::S) => {
-                availability.insert(0, parse_int(fields[next_start_index]).unwrap());
-                availability.insert(1, parse_int(fields[next_start_index + 1]).unwrap());
-                availability.insert(2, parse_int(fields[next_start_index + 2]).unwrap());
-                next_start_index = next_start_index + 3;
-            }
-            FundsType::DistributedAvailability(FundsSubType::D) => {
-                let num_distributions = parse_int(fields[next_start_index]).unwrap_or(0);
-                next_start_index = next_start_index + 1;
-
-                for _ in 0..num_distributions {
-                    match (
-                        parse_int(fields[next_start_index]),
-                        parse_int(fields[next_start_index + 1]),
-                    ) {
-                        (Some(days), Some(amt)) => {
-                            availability.insert(days, amt);
-                        }
-                        _ => {}
-                    }
+    pub fn from_node(
+        node: &Node,
+        sequence_number: usize,
+        currency_code: &Currency,
+        as_of_date: Option<NaiveDate>,
+        options: &ParseOptions,
+    ) -> Result<Transaction, Bai2Error> {
+        let fields = TransactionFields::from_node(node, options)?;
+        let text =
+            parse_text(node.text_from(fields.text_start, options.join_continued_text)).into_owned();
+        let funds = fields.funds;
 
-                    next_start_index = next_start_index + 2;
-                }
-            }
-            _ => (),
+        let amount: Option<u64> = parse_amount(fields.amount);
+        let mut transaction = Transaction {
+            amount,
+            as_of_date,
+            availability: funds.availability,
+            bank_reference_number: parse_string(fields.bank_reference_number),
+            category: Category::from_code(fields.type_code),
+            currency_code: currency_code.clone(),
+            customer_reference_number: parse_string(fields.customer_reference_number),
+            custom_category: None,
+            // An amount too large to be signed has no decimal amount, as it
+            // has no minor units
+            decimal_amount: amount.and_then(|a| i64::try_from(a).ok()).map(|a| {
+                options
+                    .currency_exponents
+                    .to_decimal(a, currency_code.code())
+            }),
+            funds_type: funds.funds_type,
+            original: None,
+            raw: (options.keep_raw_transactions || options.keep_raw_records).then(|| node.raw()),
+            sequence_number,
+            text,
+            transaction_type: fields.transaction_type,
+            value_date: funds.value_date,
+            value_time: funds.value_time,
+        };
+
+        if options.preserve_formatting {
+            let (fields, _) = writer::transaction_detail(&transaction, options.version);
+            transaction.original = Some(OriginalFormat::new(&fields, node, 0));
         }
 
-        let raw_bank_ref = fields.get(next_start_index).unwrap_or(&"");
-        let raw_customer_ref = fields.get(next_start_index + 1).unwrap_or(&"");
-        next_start_index += 2;
+        Ok(transaction)
+    }
 
-        let num_remaining_fields = num_fields - next_start_index;
-        let mut text = Vec::new();
+    /// The date the transaction takes effect, which is its value date when it
+    /// has one and the as-of date of its group otherwise.
+    pub fn effective_date(&self) -> Option<NaiveDate> {
+        self.value_date.or(self.as_of_date)
+    }
 
-        for i in 0..num_remaining_fields {
-            let raw_text = fields.get(next_start_index + i).unwrap_or(&"");
-            let parsed_text = parse_string(raw_text);
-            text.push(parsed_text);
-        }
+    pub fn amount(&self) -> Option<u64> {
+        self.amount
+    }
 
-        Ok(Transaction {
-            amount: parse_int(fields[2]),
-            availability: HashMap::new(),
-            bank_reference_number: parse_string(raw_bank_ref),
-            customer_reference_number: parse_string(raw_customer_ref),
-            funds_type,
-            text,
+    /// The amount as `MinorUnits`, or `None` when there's no amount or it's too
+    /// large to be signed.
+    pub fn minor_units(&self) -> Option<MinorUnits> {
+        self.amount
+            .and_then(|amount| MinorUnits::try_from(amount).ok())
+    }
+
+    /// The amount as `MinorUnits`, negative for debits.
+    pub fn signed_minor_units(&self) -> Option<MinorUnits> {
+        self.minor_units()?
+            .signed(self.transaction_type.direction())
+    }
+
+    /// The as-of date of the group the transaction was reported in.
+    pub fn as_of_date(&self) -> Option<NaiveDate> {
+        self.as_of_date
+    }
+
+    /// The amount becoming available after each number of days, for
+    /// distributed availability.
+    pub fn availability(&self) -> &BTreeMap<u16, i64> {
+        &self.availability
+    }
+
+    pub fn bank_reference_number(&self) -> &str {
+        &self.bank_reference_number
+    }
+
+    pub fn category(&self) -> Option<Category> {
+        self.category
+    }
+
+    /// The currency of the account the transaction was reported in.
+    pub fn currency(&self) -> &Currency {
+        &self.currency_code
+    }
+
+    pub fn currency_code(&self) -> &str {
+        self.currency_code.code()
+    }
+
+    pub fn customer_reference_number(&self) -> &str {
+        &self.customer_reference_number
+    }
+
+    /// The category assigned by `CategoryRules`, if any.
+    pub fn custom_category(&self) -> Option<&str> {
+        self.custom_category.as_deref()
+    }
+
+    /// The amount as a decimal in the account's currency, e.g. "123.45" for an
+    /// amount of 12345 in USD.
+    pub fn decimal_amount(&self) -> Option<&str> {
+        self.decimal_amount.as_deref()
+    }
+
+    pub fn funds_type(&self) -> &FundsType {
+        &self.funds_type
+    }
+
+    /// The transaction detail record and its continuations exactly as they
+    /// appeared in the file, one line each, when they were kept with
+    /// `ParseOptions::keep_raw_transactions` or `ParseOptions::keep_raw_records`.
+    pub fn raw(&self) -> Option<&str> {
+        self.raw.as_deref()
+    }
+
+    /// The position of the transaction in its account, starting from 1. This
+    /// keeps the order of the file when transactions are sorted.
+    pub fn sequence_number(&self) -> usize {
+        self.sequence_number
+    }
+
+    /// The text of the transaction, which is everything after the customer
+    /// reference, commas included, with any continuations joined on.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The text as a single string.
+    #[deprecated(note = "the text is kept whole, so use `text()`")]
+    pub fn text_joined(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn transaction_type(&self) -> &TransactionType {
+        &self.transaction_type
+    }
+
+    pub fn value_date(&self) -> Option<NaiveDate> {
+        self.value_date
+    }
+
+    pub fn value_time(&self) -> Option<Time> {
+        self.value_time
+    }
+
+    pub(crate) fn set_custom_category(&mut self, category: Option<String>) {
+        self.custom_category = category;
+    }
+}
+
+/// The fields of a transaction detail and its continuations, before they're
+/// parsed.
+pub(crate) struct TransactionFields<'a> {
+    pub(crate) amount: &'a str,
+    pub(crate) bank_reference_number: &'a str,
+    pub(crate) customer_reference_number: &'a str,
+    pub(crate) funds: Funds,
+    /// Where the text starts in the record's fields. It's put together with
+    /// `Node::text_from` only when it's wanted, since it has to be copied when
+    /// it's continued.
+    pub(crate) text_start: usize,
+    pub(crate) transaction_type: TransactionType,
+    pub(crate) type_code: &'a str,
+}
+
+impl<'a> TransactionFields<'a> {
+    pub(crate) fn from_node(
+        node: &Node<'a>,
+        options: &ParseOptions,
+    ) -> Result<TransactionFields<'a>, Bai2Error> {
+        let fields = node
+            .required_fields(3, options.allow_short_records)
+            .map_err(|(line, field)| Bai2Error::InvalidTransactionDetail {
+                line,
+                field,
+                detail: "Expected a type code and amount, but found less.",
+            })?;
+
+        let funds =
+            Funds::parse(fields.get(3..).unwrap_or(&[]), options).map_err(|(index, detail)| {
+                let (line, field) = node.field_position(index + 3);
+                Bai2Error::InvalidTransactionDetail {
+                    line,
+                    field,
+                    detail,
+                }
+            })?;
+        let references_start = 3 + funds.len;
+        let transaction_type =
+            TransactionType::parse_with_custom_codes(fields[1], &options.custom_codes).map_err(
+                |InvalidTypeCode(code)| {
+                    let (line, field) = node.field_position(1);
+                    Bai2Error::InvalidTransactionDetail {
+                        line,
+                        field,
+                        detail: match code.is_empty() {
+                            true => "Expected a type code, but found none.",
+                            false => "Expected a type code, but found a non-number.",
+                        },
+                    }
+                },
+            )?;
+
+        Ok(TransactionFields {
+            amount: fields[2],
+            bank_reference_number: fields.get(references_start).copied().unwrap_or(""),
+            customer_reference_number: fields.get(references_start + 1).copied().unwrap_or(""),
+            funds,
+            text_start: references_start + 2,
             transaction_type,
-            value_date,
-            value_time,
+            type_code: fields[1],
         })
     }
 }
+
+/// Read the text as a single string, or as an array of the fragments between
+/// its commas, as it was written before text was kept whole.
+fn deserialize_text<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Text {
+        Fragments(Vec<String>),
+        Whole(String),
+    }
+
+    Ok(match Text::deserialize(deserializer)? {
+        Text::Fragments(fragments) => fragments.join(","),
+        Text::Whole(text) => text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bai2Error, Bai2File};
+
+    #[test]
+    fn distribution_count_too_large_to_fit_is_an_error() {
+        let content = "01,A,B,200331,2300,1,,,2/
+02,,B,1,200331,2300,,/
+03,1,USD,010,1,,/
+16,175,100,D,9223372036854775808,1,2,3,4/
+49,101,3/
+98,101,1,5/
+99,101,1,7/";
+
+        match Bai2File::new(content.to_string()) {
+            Err(Bai2Error::InvalidTransactionDetail { line, field, .. }) => {
+                assert_eq!((line, field), (4, 10))
+            }
+            other => panic!("expected an invalid transaction detail, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn amount_too_large_to_be_signed_has_no_decimal_amount() {
+        let content = "01,A,B,200331,2300,1,,,2/
+02,,B,1,200331,2300,,/
+03,1,USD,010,1,,/
+16,175,18000000000000000000,,,/
+49,18000000000000000001,3/
+98,18000000000000000001,1,5/
+99,18000000000000000001,1,7/";
+
+        let file = Bai2File::new(content.to_string()).unwrap();
+        let transaction = &file.groups[0].accounts()[0].transactions()[0];
+
+        assert_eq!(transaction.amount(), Some(18000000000000000000));
+        assert_eq!(transaction.decimal_amount(), None);
+    }
+}
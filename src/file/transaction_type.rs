@@ -1,14 +1,27 @@
 use serde::ser::{SerializeStruct, Serializer};
-use serde::Serialize;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+use crate::codes;
+
+use super::custom_codes::CustomCodes;
+use super::direction::Direction;
+use super::util::parse_str;
+
+/// Credit codes whose debit 300 codes above isn't the entry that offsets them,
+/// despite the spec numbering most pairs that way.
+const UNPAIRED_CREDITS: [u16; 17] = [
+    108, 135, 147, 172, 174, 176, 233, 235, 238, 242, 243, 331, 357, 358, 394, 395, 397,
+];
 
 #[derive(Debug)]
 pub enum TransactionType {
-    Credit(String, TransactionSubType),
-    Debit(String, TransactionSubType),
-    Unknown(String, TransactionSubType),
+    Credit(u16, TransactionSubType),
+    Debit(u16, TransactionSubType),
+    Unknown(u16, TransactionSubType),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TransactionSubType {
     AccountAnalysisFee,
@@ -242,373 +255,500 @@ pub enum TransactionSubType {
     ZbaDebitAdjustment,
     ZbaDebitTransfer,
     ZbaFloatAdjustment,
+    /// A custom code with the label given to it in `ParseOptions::custom_codes`,
+    /// serialized as the label alone.
+    #[serde(untagged)]
+    Labeled(String),
+}
+
+/// A type code that isn't in the spec's table of transaction or summary type
+/// codes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnknownTypeCode(pub u16);
+
+impl fmt::Display for UnknownTypeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown type code {:03}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownTypeCode {}
+
+/// A type code in a file that isn't a number, which can't be read as any
+/// code.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidTypeCode(pub String);
+
+impl fmt::Display for InvalidTypeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid type code `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTypeCode {}
+
+/// The number in a type code, with the whitespace and slashes around it
+/// removed.
+pub(crate) fn parse_type_code(type_code: &str) -> Result<u16, InvalidTypeCode> {
+    let code = parse_str(type_code);
+    match code.bytes().all(|b| b.is_ascii_digit()) {
+        true => code.parse().map_err(|_| InvalidTypeCode(code.to_string())),
+        false => Err(InvalidTypeCode(code.to_string())),
+    }
+}
+
+impl TransactionSubType {
+    /// Every transaction type code with a subtype, in order, including the
+    /// codes set aside for custom credits and debits.
+    pub fn all_codes() -> impl Iterator<Item = u16> {
+        (0..=999).filter(|&code| TransactionSubType::try_from(code).is_ok())
+    }
+
+    /// Every type code with this subtype, in order, e.g. 198 and 498 for
+    /// compensation, which can be a credit or a debit.
+    pub fn codes(&self) -> Vec<u16> {
+        TransactionSubType::all_codes()
+            .filter(|&code| TransactionSubType::try_from(code).as_ref() == Ok(self))
+            .collect()
+    }
+}
+
+impl TryFrom<u16> for TransactionSubType {
+    type Error = UnknownTypeCode;
+
+    fn try_from(code: u16) -> Result<TransactionSubType, UnknownTypeCode> {
+        let (TransactionType::Credit(_, subtype)
+        | TransactionType::Debit(_, subtype)
+        | TransactionType::Unknown(_, subtype)) = TransactionType::from_code(code);
+
+        match subtype {
+            TransactionSubType::Unknown => Err(UnknownTypeCode(code)),
+            subtype => Ok(subtype),
+        }
+    }
 }
 
 impl TransactionType {
-    pub fn parse(type_code: &str) -> TransactionType {
-        let code = type_code.to_string();
-
-        match type_code {
-            "108" => TransactionType::Credit(code, TransactionSubType::Credit),
-            "115" => TransactionType::Credit(code, TransactionSubType::LockboxDeposit),
-            "116" => TransactionType::Credit(code, TransactionSubType::ItemInLockboxDeposit),
-            "118" => TransactionType::Credit(code, TransactionSubType::LockboxAdjustmentCredit),
-            "121" => TransactionType::Credit(code, TransactionSubType::EdiTransactionCredit),
-            "122" => TransactionType::Credit(code, TransactionSubType::EdibanxCreditReceived),
-            "123" => TransactionType::Credit(code, TransactionSubType::EdibanxCreditReturn),
-            "135" => TransactionType::Credit(code, TransactionSubType::DtcConcentrationCredit),
-            "136" => TransactionType::Credit(code, TransactionSubType::ItemInDtcDeposit),
-            "142" => TransactionType::Credit(code, TransactionSubType::AchCreditReceived),
-            "143" => TransactionType::Credit(code, TransactionSubType::ItemInAchDeposit),
-            "145" => TransactionType::Credit(code, TransactionSubType::AchConcentrationCredit),
-            "147" => TransactionType::Credit(code, TransactionSubType::IndividualBankCardDeposit),
-            "155" => TransactionType::Credit(code, TransactionSubType::PreauthorizedDraftCredit),
-            "156" => TransactionType::Credit(code, TransactionSubType::ItemInPacDeposit),
-            "164" => TransactionType::Credit(code, TransactionSubType::CorporateTradePaymentCredit),
-            "165" => TransactionType::Credit(code, TransactionSubType::PreauthorizedAchCredit),
-            "166" => TransactionType::Credit(code, TransactionSubType::AchSettlement),
-            "168" => TransactionType::Credit(
+    pub fn code(&self) -> u16 {
+        match *self {
+            TransactionType::Credit(c, _)
+            | TransactionType::Debit(c, _)
+            | TransactionType::Unknown(c, _) => c,
+        }
+    }
+
+    /// The code of the entry in the other direction that offsets this one,
+    /// e.g. 466 for an ACH settlement credit (166) and 166 for an ACH
+    /// settlement debit, for netting matching entries against each other.
+    pub fn paired_code(&self) -> Option<u16> {
+        let (credit, debit) = match self {
+            TransactionType::Credit(code, _) => (*code, code.checked_add(300)?),
+            TransactionType::Debit(code, _) => (code.checked_sub(300)?, *code),
+            TransactionType::Unknown(..) => return None,
+        };
+
+        let credit_type = TransactionType::from_code(credit);
+        let debit_type = TransactionType::from_code(debit);
+        let is_pair = matches!(credit_type, TransactionType::Credit(..))
+            && matches!(debit_type, TransactionType::Debit(..))
+            && !UNPAIRED_CREDITS.contains(&credit);
+
+        match self {
+            _ if !is_pair => None,
+            TransactionType::Credit(..) => Some(debit),
+            _ => Some(credit),
+        }
+    }
+
+    /// The transaction type of the entry that offsets this one. See
+    /// `paired_code`.
+    pub fn paired(&self) -> Option<TransactionType> {
+        self.paired_code().map(TransactionType::from_code)
+    }
+
+    /// Whether the transaction is a credit or a debit, which is unknown for
+    /// codes that aren't in the spec.
+    pub fn direction(&self) -> Direction {
+        match self {
+            TransactionType::Credit(..) => Direction::Credit,
+            TransactionType::Debit(..) => Direction::Debit,
+            TransactionType::Unknown(..) => Direction::Unknown,
+        }
+    }
+
+    /// What the code is for, e.g. `AchCreditReceived` for 142.
+    pub fn subtype(&self) -> &TransactionSubType {
+        match self {
+            TransactionType::Credit(_, t)
+            | TransactionType::Debit(_, t)
+            | TransactionType::Unknown(_, t) => t,
+        }
+    }
+
+    /// A description of what the code is for, e.g. "ACH credit received" for
+    /// 142, or the label given to a custom code.
+    pub fn description(&self) -> String {
+        match self.subtype() {
+            TransactionSubType::Labeled(label) => label.clone(),
+            subtype => codes::describe(&format!("{:?}", subtype)),
+        }
+    }
+
+    /// Parse the type code, reading the custom codes that have been given a
+    /// label and direction with those.
+    pub fn parse_with_custom_codes(
+        type_code: &str,
+        custom_codes: &CustomCodes,
+    ) -> Result<TransactionType, InvalidTypeCode> {
+        let code = parse_type_code(type_code)?;
+        let Some((label, direction)) = custom_codes.get(code) else {
+            return Ok(TransactionType::from_code(code));
+        };
+
+        let subtype = TransactionSubType::Labeled(label.to_string());
+        Ok(match direction {
+            Direction::Credit => TransactionType::Credit(code, subtype),
+            Direction::Debit => TransactionType::Debit(code, subtype),
+            Direction::Unknown => TransactionType::Unknown(code, subtype),
+        })
+    }
+
+    /// Parse a type code as it's written in a file. A code that isn't a number
+    /// is an error, rather than being read as some other code.
+    pub fn parse(type_code: &str) -> Result<TransactionType, InvalidTypeCode> {
+        parse_type_code(type_code).map(TransactionType::from_code)
+    }
+
+    pub fn from_code(code: u16) -> TransactionType {
+        match code {
+            108 => TransactionType::Credit(code, TransactionSubType::Credit),
+            115 => TransactionType::Credit(code, TransactionSubType::LockboxDeposit),
+            116 => TransactionType::Credit(code, TransactionSubType::ItemInLockboxDeposit),
+            118 => TransactionType::Credit(code, TransactionSubType::LockboxAdjustmentCredit),
+            121 => TransactionType::Credit(code, TransactionSubType::EdiTransactionCredit),
+            122 => TransactionType::Credit(code, TransactionSubType::EdibanxCreditReceived),
+            123 => TransactionType::Credit(code, TransactionSubType::EdibanxCreditReturn),
+            135 => TransactionType::Credit(code, TransactionSubType::DtcConcentrationCredit),
+            136 => TransactionType::Credit(code, TransactionSubType::ItemInDtcDeposit),
+            142 => TransactionType::Credit(code, TransactionSubType::AchCreditReceived),
+            143 => TransactionType::Credit(code, TransactionSubType::ItemInAchDeposit),
+            145 => TransactionType::Credit(code, TransactionSubType::AchConcentrationCredit),
+            147 => TransactionType::Credit(code, TransactionSubType::IndividualBankCardDeposit),
+            155 => TransactionType::Credit(code, TransactionSubType::PreauthorizedDraftCredit),
+            156 => TransactionType::Credit(code, TransactionSubType::ItemInPacDeposit),
+            164 => TransactionType::Credit(code, TransactionSubType::CorporateTradePaymentCredit),
+            165 => TransactionType::Credit(code, TransactionSubType::PreauthorizedAchCredit),
+            166 => TransactionType::Credit(code, TransactionSubType::AchSettlement),
+            168 => TransactionType::Credit(
                 code,
                 TransactionSubType::AchReturnItemOrAdjustmentSettlement,
             ),
-            "169" => TransactionType::Credit(code, TransactionSubType::MiscellaneousAchCredit),
-            "171" => TransactionType::Credit(code, TransactionSubType::IndividualLoanDeposit),
-            "172" => TransactionType::Credit(code, TransactionSubType::DepositCorrection),
-            "173" => TransactionType::Credit(code, TransactionSubType::BankPreparedDeposit),
-            "174" => TransactionType::Credit(code, TransactionSubType::OtherDeposit),
-            "175" => TransactionType::Credit(code, TransactionSubType::CheckDepositPackage),
-            "176" => TransactionType::Credit(code, TransactionSubType::RePresentedCheckDeposit),
-            "184" => TransactionType::Credit(code, TransactionSubType::DraftDeposit),
-            "187" => TransactionType::Credit(code, TransactionSubType::CashLetterCredit),
-            "189" => TransactionType::Credit(code, TransactionSubType::CashLetterAdjustment),
-            "191" => TransactionType::Credit(
+            169 => TransactionType::Credit(code, TransactionSubType::MiscellaneousAchCredit),
+            171 => TransactionType::Credit(code, TransactionSubType::IndividualLoanDeposit),
+            172 => TransactionType::Credit(code, TransactionSubType::DepositCorrection),
+            173 => TransactionType::Credit(code, TransactionSubType::BankPreparedDeposit),
+            174 => TransactionType::Credit(code, TransactionSubType::OtherDeposit),
+            175 => TransactionType::Credit(code, TransactionSubType::CheckDepositPackage),
+            176 => TransactionType::Credit(code, TransactionSubType::RePresentedCheckDeposit),
+            184 => TransactionType::Credit(code, TransactionSubType::DraftDeposit),
+            187 => TransactionType::Credit(code, TransactionSubType::CashLetterCredit),
+            189 => TransactionType::Credit(code, TransactionSubType::CashLetterAdjustment),
+            191 => TransactionType::Credit(
                 code,
                 TransactionSubType::IndividualIncomingInternalMoneyTransfer,
             ),
-            "195" => TransactionType::Credit(code, TransactionSubType::IncomingMoneyTransfer),
-            "196" => TransactionType::Credit(code, TransactionSubType::MoneyTransferAdjustment),
-            "198" => TransactionType::Credit(code, TransactionSubType::Compensation),
-            "201" => {
+            195 => TransactionType::Credit(code, TransactionSubType::IncomingMoneyTransfer),
+            196 => TransactionType::Credit(code, TransactionSubType::MoneyTransferAdjustment),
+            198 => TransactionType::Credit(code, TransactionSubType::Compensation),
+            201 => {
                 TransactionType::Credit(code, TransactionSubType::IndividualAutomaticTransferCredit)
             }
-            "202" => TransactionType::Credit(code, TransactionSubType::BondOperationsCredit),
-            "206" => TransactionType::Credit(code, TransactionSubType::BookTransferCredit),
-            "208" => TransactionType::Credit(
+            202 => TransactionType::Credit(code, TransactionSubType::BondOperationsCredit),
+            206 => TransactionType::Credit(code, TransactionSubType::BookTransferCredit),
+            208 => TransactionType::Credit(
                 code,
                 TransactionSubType::IndividualInternationalMoneyTransferCredit,
             ),
-            "212" => TransactionType::Credit(code, TransactionSubType::ForeignLetterOfCredit),
-            "213" => TransactionType::Credit(code, TransactionSubType::LetterOfCredit),
-            "214" => TransactionType::Credit(code, TransactionSubType::ForeignExchangeOfCredit),
-            "216" => TransactionType::Credit(code, TransactionSubType::ForeignRemittanceCredit),
-            "218" => TransactionType::Credit(code, TransactionSubType::ForeignCollectionCredit),
-            "221" => TransactionType::Credit(code, TransactionSubType::ForeignCheckPurchase),
-            "222" => TransactionType::Credit(code, TransactionSubType::ForeignChecksDeposited),
-            "224" => TransactionType::Credit(code, TransactionSubType::Commission),
-            "226" => {
+            212 => TransactionType::Credit(code, TransactionSubType::ForeignLetterOfCredit),
+            213 => TransactionType::Credit(code, TransactionSubType::LetterOfCredit),
+            214 => TransactionType::Credit(code, TransactionSubType::ForeignExchangeOfCredit),
+            216 => TransactionType::Credit(code, TransactionSubType::ForeignRemittanceCredit),
+            218 => TransactionType::Credit(code, TransactionSubType::ForeignCollectionCredit),
+            221 => TransactionType::Credit(code, TransactionSubType::ForeignCheckPurchase),
+            222 => TransactionType::Credit(code, TransactionSubType::ForeignChecksDeposited),
+            224 => TransactionType::Credit(code, TransactionSubType::Commission),
+            226 => {
                 TransactionType::Credit(code, TransactionSubType::InternationalMoneyMarketTrading)
             }
-            "227" => TransactionType::Credit(code, TransactionSubType::StandingOrder),
-            "229" => {
+            227 => TransactionType::Credit(code, TransactionSubType::StandingOrder),
+            229 => {
                 TransactionType::Credit(code, TransactionSubType::MiscellaneousInternationalCredit)
             }
-            "232" => TransactionType::Credit(code, TransactionSubType::SaleOfDebtSecurity),
-            "233" => TransactionType::Credit(code, TransactionSubType::SecuritiesSold),
-            "234" => TransactionType::Credit(code, TransactionSubType::SaleOfEquitySecurity),
-            "235" => {
-                TransactionType::Credit(code, TransactionSubType::MaturedReverseRepurchaseOrder)
-            }
-            "236" => TransactionType::Credit(code, TransactionSubType::MaturityOfDebtSecurity),
-            "237" => TransactionType::Credit(code, TransactionSubType::IndividualCollectionCredit),
-            "238" => TransactionType::Credit(code, TransactionSubType::CollectionOfDividends),
-            "240" => TransactionType::Credit(code, TransactionSubType::CouponCollectionsBanks),
-            "241" => TransactionType::Credit(code, TransactionSubType::BankersAcceptances),
-            "242" => TransactionType::Credit(code, TransactionSubType::CollectionOfInterestIncome),
-            "243" => TransactionType::Credit(code, TransactionSubType::MaturedFedFundsPurchased),
-            "244" => {
+            232 => TransactionType::Credit(code, TransactionSubType::SaleOfDebtSecurity),
+            233 => TransactionType::Credit(code, TransactionSubType::SecuritiesSold),
+            234 => TransactionType::Credit(code, TransactionSubType::SaleOfEquitySecurity),
+            235 => TransactionType::Credit(code, TransactionSubType::MaturedReverseRepurchaseOrder),
+            236 => TransactionType::Credit(code, TransactionSubType::MaturityOfDebtSecurity),
+            237 => TransactionType::Credit(code, TransactionSubType::IndividualCollectionCredit),
+            238 => TransactionType::Credit(code, TransactionSubType::CollectionOfDividends),
+            240 => TransactionType::Credit(code, TransactionSubType::CouponCollectionsBanks),
+            241 => TransactionType::Credit(code, TransactionSubType::BankersAcceptances),
+            242 => TransactionType::Credit(code, TransactionSubType::CollectionOfInterestIncome),
+            243 => TransactionType::Credit(code, TransactionSubType::MaturedFedFundsPurchased),
+            244 => {
                 TransactionType::Credit(code, TransactionSubType::InterestMaturedPrincipalPayment)
             }
-            "246" => TransactionType::Credit(code, TransactionSubType::CommercialPaper),
-            "247" => TransactionType::Credit(code, TransactionSubType::CapitalChange),
-            "248" => TransactionType::Credit(code, TransactionSubType::SavingsBondsSalesAdjustment),
-            "249" => TransactionType::Credit(code, TransactionSubType::MiscellaneousSecurityCredit),
-            "252" => TransactionType::Credit(code, TransactionSubType::DebitReversal),
-            "254" => {
-                TransactionType::Credit(code, TransactionSubType::PostingErrorCorrectionCredit)
-            }
-            "255" => TransactionType::Credit(code, TransactionSubType::CheckPostedAndReturned),
-            "257" => TransactionType::Credit(code, TransactionSubType::IndividualAchReturnItem),
-            "258" => TransactionType::Credit(code, TransactionSubType::AchReversalCredit),
-            "261" => TransactionType::Credit(code, TransactionSubType::IndividualRejectedCredit),
-            "263" => TransactionType::Credit(code, TransactionSubType::Overdraft),
-            "266" => TransactionType::Credit(code, TransactionSubType::ReturnItem),
-            "268" => TransactionType::Credit(code, TransactionSubType::ReturnItemAdjustment),
-            "274" => TransactionType::Credit(
+            246 => TransactionType::Credit(code, TransactionSubType::CommercialPaper),
+            247 => TransactionType::Credit(code, TransactionSubType::CapitalChange),
+            248 => TransactionType::Credit(code, TransactionSubType::SavingsBondsSalesAdjustment),
+            249 => TransactionType::Credit(code, TransactionSubType::MiscellaneousSecurityCredit),
+            252 => TransactionType::Credit(code, TransactionSubType::DebitReversal),
+            254 => TransactionType::Credit(code, TransactionSubType::PostingErrorCorrectionCredit),
+            255 => TransactionType::Credit(code, TransactionSubType::CheckPostedAndReturned),
+            257 => TransactionType::Credit(code, TransactionSubType::IndividualAchReturnItem),
+            258 => TransactionType::Credit(code, TransactionSubType::AchReversalCredit),
+            261 => TransactionType::Credit(code, TransactionSubType::IndividualRejectedCredit),
+            263 => TransactionType::Credit(code, TransactionSubType::Overdraft),
+            266 => TransactionType::Credit(code, TransactionSubType::ReturnItem),
+            268 => TransactionType::Credit(code, TransactionSubType::ReturnItemAdjustment),
+            274 => TransactionType::Credit(
                 code,
                 TransactionSubType::CumulativeZbaOrDisbursementCredits,
             ),
-            "275" => TransactionType::Credit(code, TransactionSubType::ZbaCredit),
-            "276" => TransactionType::Credit(code, TransactionSubType::ZbaFloatAdjustment),
-            "277" => TransactionType::Credit(code, TransactionSubType::ZbaCreditTransfer),
-            "278" => TransactionType::Credit(code, TransactionSubType::ZbaCreditAdjustment),
-            "281" => TransactionType::Credit(
+            275 => TransactionType::Credit(code, TransactionSubType::ZbaCredit),
+            276 => TransactionType::Credit(code, TransactionSubType::ZbaFloatAdjustment),
+            277 => TransactionType::Credit(code, TransactionSubType::ZbaCreditTransfer),
+            278 => TransactionType::Credit(code, TransactionSubType::ZbaCreditAdjustment),
+            281 => TransactionType::Credit(
                 code,
                 TransactionSubType::IndividualControlledDisbursingCredit,
             ),
-            "286" => {
-                TransactionType::Credit(code, TransactionSubType::IndividualDtcDisbursingCredit)
-            }
-            "295" => TransactionType::Credit(code, TransactionSubType::AtmCredit),
-            "301" => TransactionType::Credit(code, TransactionSubType::CommercialDeposit),
-            "306" => TransactionType::Credit(code, TransactionSubType::FedFundsSold),
-            "308" => TransactionType::Credit(code, TransactionSubType::TrustCredit),
-            "331" => TransactionType::Credit(code, TransactionSubType::IndividualEscrowCredit),
-            "342" => TransactionType::Credit(code, TransactionSubType::BrokerDeposit),
-            "344" => TransactionType::Credit(code, TransactionSubType::IndividualBackValueCredit),
-            "345" => TransactionType::Credit(code, TransactionSubType::ItemInBrokersDeposit),
-            "346" => TransactionType::Credit(code, TransactionSubType::SweepInterestIncome),
-            "347" => TransactionType::Credit(code, TransactionSubType::SweepPrincipalSell),
-            "348" => TransactionType::Credit(code, TransactionSubType::FuturesCredit),
-            "349" => TransactionType::Credit(code, TransactionSubType::PrincipalPaymentsCredit),
-            "351" => TransactionType::Credit(code, TransactionSubType::IndividualInvestmentSold),
-            "353" => TransactionType::Credit(code, TransactionSubType::CashCenterCredit),
-            "354" => TransactionType::Credit(code, TransactionSubType::InterestCredit),
-            "357" => TransactionType::Credit(code, TransactionSubType::CreditAdjustment),
-            "358" => TransactionType::Credit(code, TransactionSubType::YtdAdjustmentCredit),
-            "359" => TransactionType::Credit(code, TransactionSubType::InterestAdjustmentCredit),
-            "362" => TransactionType::Credit(code, TransactionSubType::CorrespondentCollection),
-            "363" => {
+            286 => TransactionType::Credit(code, TransactionSubType::IndividualDtcDisbursingCredit),
+            295 => TransactionType::Credit(code, TransactionSubType::AtmCredit),
+            301 => TransactionType::Credit(code, TransactionSubType::CommercialDeposit),
+            306 => TransactionType::Credit(code, TransactionSubType::FedFundsSold),
+            308 => TransactionType::Credit(code, TransactionSubType::TrustCredit),
+            331 => TransactionType::Credit(code, TransactionSubType::IndividualEscrowCredit),
+            342 => TransactionType::Credit(code, TransactionSubType::BrokerDeposit),
+            344 => TransactionType::Credit(code, TransactionSubType::IndividualBackValueCredit),
+            345 => TransactionType::Credit(code, TransactionSubType::ItemInBrokersDeposit),
+            346 => TransactionType::Credit(code, TransactionSubType::SweepInterestIncome),
+            347 => TransactionType::Credit(code, TransactionSubType::SweepPrincipalSell),
+            348 => TransactionType::Credit(code, TransactionSubType::FuturesCredit),
+            349 => TransactionType::Credit(code, TransactionSubType::PrincipalPaymentsCredit),
+            351 => TransactionType::Credit(code, TransactionSubType::IndividualInvestmentSold),
+            353 => TransactionType::Credit(code, TransactionSubType::CashCenterCredit),
+            354 => TransactionType::Credit(code, TransactionSubType::InterestCredit),
+            357 => TransactionType::Credit(code, TransactionSubType::CreditAdjustment),
+            358 => TransactionType::Credit(code, TransactionSubType::YtdAdjustmentCredit),
+            359 => TransactionType::Credit(code, TransactionSubType::InterestAdjustmentCredit),
+            362 => TransactionType::Credit(code, TransactionSubType::CorrespondentCollection),
+            363 => {
                 TransactionType::Credit(code, TransactionSubType::CorrespondentCollectionAdjustment)
             }
-            "364" => TransactionType::Credit(code, TransactionSubType::LoanParticipation),
-            "366" => TransactionType::Credit(code, TransactionSubType::CurrencyAndCoinDeposited),
-            "367" => TransactionType::Credit(code, TransactionSubType::FoodStampLetter),
-            "368" => TransactionType::Credit(code, TransactionSubType::FoodStampAdjustment),
-            "369" => TransactionType::Credit(code, TransactionSubType::ClearingSettlementCredit),
-            "372" => TransactionType::Credit(code, TransactionSubType::BackValueAdjustment),
-            "373" => TransactionType::Credit(code, TransactionSubType::CustomerPayroll),
-            "374" => TransactionType::Credit(code, TransactionSubType::FrbStatementRecap),
-            "376" => {
-                TransactionType::Credit(code, TransactionSubType::SavingsBondLetterOrAdjustment)
-            }
-            "377" => TransactionType::Credit(code, TransactionSubType::TreasuryTaxAndLoanCredit),
-            "378" => TransactionType::Credit(code, TransactionSubType::TransferOfTreasuryCredit),
-            "379" => TransactionType::Credit(
+            364 => TransactionType::Credit(code, TransactionSubType::LoanParticipation),
+            366 => TransactionType::Credit(code, TransactionSubType::CurrencyAndCoinDeposited),
+            367 => TransactionType::Credit(code, TransactionSubType::FoodStampLetter),
+            368 => TransactionType::Credit(code, TransactionSubType::FoodStampAdjustment),
+            369 => TransactionType::Credit(code, TransactionSubType::ClearingSettlementCredit),
+            372 => TransactionType::Credit(code, TransactionSubType::BackValueAdjustment),
+            373 => TransactionType::Credit(code, TransactionSubType::CustomerPayroll),
+            374 => TransactionType::Credit(code, TransactionSubType::FrbStatementRecap),
+            376 => TransactionType::Credit(code, TransactionSubType::SavingsBondLetterOrAdjustment),
+            377 => TransactionType::Credit(code, TransactionSubType::TreasuryTaxAndLoanCredit),
+            378 => TransactionType::Credit(code, TransactionSubType::TransferOfTreasuryCredit),
+            379 => TransactionType::Credit(
                 code,
                 TransactionSubType::FrbGovernmentChecksCashLetterCredit,
             ),
-            "381" => {
-                TransactionType::Credit(code, TransactionSubType::FrbGovernmentCheckAdjustment)
-            }
-            "382" => TransactionType::Credit(code, TransactionSubType::FrbPostalMoneyOrderCredit),
-            "383" => {
-                TransactionType::Credit(code, TransactionSubType::FrbPostalMoneyOrderAdjustment)
-            }
-            "384" => {
-                TransactionType::Credit(code, TransactionSubType::FrbCashLetterAutoChargeCredit)
-            }
-            "386" => {
+            381 => TransactionType::Credit(code, TransactionSubType::FrbGovernmentCheckAdjustment),
+            382 => TransactionType::Credit(code, TransactionSubType::FrbPostalMoneyOrderCredit),
+            383 => TransactionType::Credit(code, TransactionSubType::FrbPostalMoneyOrderAdjustment),
+            384 => TransactionType::Credit(code, TransactionSubType::FrbCashLetterAutoChargeCredit),
+            386 => {
                 TransactionType::Credit(code, TransactionSubType::FrbCashLetterAutoChargeAdjustment)
             }
-            "387" => TransactionType::Credit(code, TransactionSubType::FrbFineSortCashLetterCredit),
-            "388" => TransactionType::Credit(code, TransactionSubType::FrbFineSortAdjustment),
-            "391" => TransactionType::Credit(code, TransactionSubType::UniversalCredit),
-            "392" => TransactionType::Credit(code, TransactionSubType::FreightPaymentCredit),
-            "393" => TransactionType::Credit(code, TransactionSubType::ItemizedCreditOver10000),
-            "394" => TransactionType::Credit(code, TransactionSubType::CumulativeCredits),
-            "395" => TransactionType::Credit(code, TransactionSubType::CheckReversal),
-            "397" => TransactionType::Credit(code, TransactionSubType::FloatAdjustment),
-            "398" => TransactionType::Credit(code, TransactionSubType::MiscellaneousFeeRefund),
-            "399" => TransactionType::Credit(code, TransactionSubType::MiscellaneousCredit),
-            "408" => TransactionType::Debit(code, TransactionSubType::FloatAdjustment),
-            "409" => TransactionType::Debit(code, TransactionSubType::DebitAnyType),
-            "415" => TransactionType::Debit(code, TransactionSubType::LockboxDebit),
-            "421" => TransactionType::Debit(code, TransactionSubType::EdiTransactionDebit),
-            "422" => TransactionType::Debit(code, TransactionSubType::EdibanxSettlementDebit),
-            "423" => TransactionType::Debit(code, TransactionSubType::EdibanxReturnItemDebit),
-            "435" => TransactionType::Debit(code, TransactionSubType::PayableThroughDraft),
-            "445" => TransactionType::Debit(code, TransactionSubType::AchConcentrationDebit),
-            "447" => TransactionType::Debit(code, TransactionSubType::AchDisbursementFundingDebit),
-            "451" => TransactionType::Debit(code, TransactionSubType::AchDebitReceived),
-            "452" => TransactionType::Debit(code, TransactionSubType::ItemInAchDisbursementOrDebit),
-            "455" => TransactionType::Debit(code, TransactionSubType::PreauthorizedAchDebit),
-            "462" => {
-                TransactionType::Debit(code, TransactionSubType::AccountHolderInitiatedAchDebit)
-            }
-            "464" => TransactionType::Debit(code, TransactionSubType::CorporateTradePaymentDebit),
-            "466" => TransactionType::Debit(code, TransactionSubType::AchSettlement),
-            "468" => TransactionType::Debit(
+            387 => TransactionType::Credit(code, TransactionSubType::FrbFineSortCashLetterCredit),
+            388 => TransactionType::Credit(code, TransactionSubType::FrbFineSortAdjustment),
+            391 => TransactionType::Credit(code, TransactionSubType::UniversalCredit),
+            392 => TransactionType::Credit(code, TransactionSubType::FreightPaymentCredit),
+            393 => TransactionType::Credit(code, TransactionSubType::ItemizedCreditOver10000),
+            394 => TransactionType::Credit(code, TransactionSubType::CumulativeCredits),
+            395 => TransactionType::Credit(code, TransactionSubType::CheckReversal),
+            397 => TransactionType::Credit(code, TransactionSubType::FloatAdjustment),
+            398 => TransactionType::Credit(code, TransactionSubType::MiscellaneousFeeRefund),
+            399 => TransactionType::Credit(code, TransactionSubType::MiscellaneousCredit),
+            408 => TransactionType::Debit(code, TransactionSubType::FloatAdjustment),
+            409 => TransactionType::Debit(code, TransactionSubType::DebitAnyType),
+            415 => TransactionType::Debit(code, TransactionSubType::LockboxDebit),
+            421 => TransactionType::Debit(code, TransactionSubType::EdiTransactionDebit),
+            422 => TransactionType::Debit(code, TransactionSubType::EdibanxSettlementDebit),
+            423 => TransactionType::Debit(code, TransactionSubType::EdibanxReturnItemDebit),
+            435 => TransactionType::Debit(code, TransactionSubType::PayableThroughDraft),
+            445 => TransactionType::Debit(code, TransactionSubType::AchConcentrationDebit),
+            447 => TransactionType::Debit(code, TransactionSubType::AchDisbursementFundingDebit),
+            451 => TransactionType::Debit(code, TransactionSubType::AchDebitReceived),
+            452 => TransactionType::Debit(code, TransactionSubType::ItemInAchDisbursementOrDebit),
+            455 => TransactionType::Debit(code, TransactionSubType::PreauthorizedAchDebit),
+            462 => TransactionType::Debit(code, TransactionSubType::AccountHolderInitiatedAchDebit),
+            464 => TransactionType::Debit(code, TransactionSubType::CorporateTradePaymentDebit),
+            466 => TransactionType::Debit(code, TransactionSubType::AchSettlement),
+            468 => TransactionType::Debit(
                 code,
                 TransactionSubType::AchReturnItemOrAdjustmentSettlement,
             ),
-            "469" => TransactionType::Debit(code, TransactionSubType::MiscellaneousAchDebit),
-            "472" => TransactionType::Debit(code, TransactionSubType::CumulativeChecksPaid),
-            "474" => TransactionType::Debit(code, TransactionSubType::CertifiedCheckDebit),
-            "475" => TransactionType::Debit(code, TransactionSubType::CheckPaid),
-            "476" => {
-                TransactionType::Debit(code, TransactionSubType::FederalReserveBankLetterDebit)
-            }
-            "477" => TransactionType::Debit(code, TransactionSubType::BankOriginatedDebit),
-            "479" => TransactionType::Debit(code, TransactionSubType::ListPostDebit),
-            "481" => TransactionType::Debit(code, TransactionSubType::IndividualLoanPayment),
-            "484" => TransactionType::Debit(code, TransactionSubType::Draft),
-            "485" => TransactionType::Debit(code, TransactionSubType::DtcDebit),
-            "487" => TransactionType::Debit(code, TransactionSubType::CashLetterDebit),
-            "489" => TransactionType::Debit(code, TransactionSubType::CashLetterAdjustment),
-            "491" => TransactionType::Debit(
+            469 => TransactionType::Debit(code, TransactionSubType::MiscellaneousAchDebit),
+            472 => TransactionType::Debit(code, TransactionSubType::CumulativeChecksPaid),
+            474 => TransactionType::Debit(code, TransactionSubType::CertifiedCheckDebit),
+            475 => TransactionType::Debit(code, TransactionSubType::CheckPaid),
+            476 => TransactionType::Debit(code, TransactionSubType::FederalReserveBankLetterDebit),
+            477 => TransactionType::Debit(code, TransactionSubType::BankOriginatedDebit),
+            479 => TransactionType::Debit(code, TransactionSubType::ListPostDebit),
+            481 => TransactionType::Debit(code, TransactionSubType::IndividualLoanPayment),
+            484 => TransactionType::Debit(code, TransactionSubType::Draft),
+            485 => TransactionType::Debit(code, TransactionSubType::DtcDebit),
+            487 => TransactionType::Debit(code, TransactionSubType::CashLetterDebit),
+            489 => TransactionType::Debit(code, TransactionSubType::CashLetterAdjustment),
+            491 => TransactionType::Debit(
                 code,
                 TransactionSubType::IndividualOutgoingInternalMoneyTransfer,
             ),
-            "493" => TransactionType::Debit(
+            493 => TransactionType::Debit(
                 code,
                 TransactionSubType::CustomerTerminalInitiatedMoneyTransfer,
             ),
-            "495" => TransactionType::Debit(code, TransactionSubType::OutgoingMoneyTransfer),
-            "496" => TransactionType::Debit(code, TransactionSubType::MoneyTransferAdjustment),
-            "498" => TransactionType::Debit(code, TransactionSubType::Compensation),
-            "501" => {
+            495 => TransactionType::Debit(code, TransactionSubType::OutgoingMoneyTransfer),
+            496 => TransactionType::Debit(code, TransactionSubType::MoneyTransferAdjustment),
+            498 => TransactionType::Debit(code, TransactionSubType::Compensation),
+            501 => {
                 TransactionType::Debit(code, TransactionSubType::IndividualAutomaticTransferDebit)
             }
-            "502" => TransactionType::Debit(code, TransactionSubType::BondOperationsDebit),
-            "506" => TransactionType::Debit(code, TransactionSubType::BookTransferDebit),
-            "508" => TransactionType::Debit(
+            502 => TransactionType::Debit(code, TransactionSubType::BondOperationsDebit),
+            506 => TransactionType::Debit(code, TransactionSubType::BookTransferDebit),
+            508 => TransactionType::Debit(
                 code,
                 TransactionSubType::IndividualInternationalMoneyTransferDebits,
             ),
-            "512" => TransactionType::Debit(code, TransactionSubType::LetterOfCreditDebit),
-            "513" => TransactionType::Debit(code, TransactionSubType::LetterOfCredit),
-            "514" => TransactionType::Debit(code, TransactionSubType::ForeignExchangeDebit),
-            "516" => TransactionType::Debit(code, TransactionSubType::ForeignRemittanceDebit),
-            "518" => TransactionType::Debit(code, TransactionSubType::ForeignCollectionDebit),
-            "522" => TransactionType::Debit(code, TransactionSubType::ForeignChecksPaid),
-            "524" => TransactionType::Debit(code, TransactionSubType::Commission),
-            "526" => {
+            512 => TransactionType::Debit(code, TransactionSubType::LetterOfCreditDebit),
+            513 => TransactionType::Debit(code, TransactionSubType::LetterOfCredit),
+            514 => TransactionType::Debit(code, TransactionSubType::ForeignExchangeDebit),
+            516 => TransactionType::Debit(code, TransactionSubType::ForeignRemittanceDebit),
+            518 => TransactionType::Debit(code, TransactionSubType::ForeignCollectionDebit),
+            522 => TransactionType::Debit(code, TransactionSubType::ForeignChecksPaid),
+            524 => TransactionType::Debit(code, TransactionSubType::Commission),
+            526 => {
                 TransactionType::Debit(code, TransactionSubType::InternationalMoneyMarketTrading)
             }
-            "527" => TransactionType::Debit(code, TransactionSubType::StandingOrder),
-            "529" => {
+            527 => TransactionType::Debit(code, TransactionSubType::StandingOrder),
+            529 => {
                 TransactionType::Debit(code, TransactionSubType::MiscellaneousInternationalDebit)
             }
-            "531" => TransactionType::Debit(code, TransactionSubType::SecuritiesPurchased),
-            "533" => TransactionType::Debit(code, TransactionSubType::SecurityCollectionDebit),
-            "535" => TransactionType::Debit(code, TransactionSubType::PurchaseOfEquitySecurities),
-            "538" => TransactionType::Debit(code, TransactionSubType::MaturedRepurchaseOrder),
-            "540" => TransactionType::Debit(code, TransactionSubType::CouponCollectionDebit),
-            "541" => TransactionType::Debit(code, TransactionSubType::BankersAcceptances),
-            "542" => TransactionType::Debit(code, TransactionSubType::PurchaseOfDebtSecurities),
-            "543" => TransactionType::Debit(code, TransactionSubType::DomesticCollection),
-            "544" => {
+            531 => TransactionType::Debit(code, TransactionSubType::SecuritiesPurchased),
+            533 => TransactionType::Debit(code, TransactionSubType::SecurityCollectionDebit),
+            535 => TransactionType::Debit(code, TransactionSubType::PurchaseOfEquitySecurities),
+            538 => TransactionType::Debit(code, TransactionSubType::MaturedRepurchaseOrder),
+            540 => TransactionType::Debit(code, TransactionSubType::CouponCollectionDebit),
+            541 => TransactionType::Debit(code, TransactionSubType::BankersAcceptances),
+            542 => TransactionType::Debit(code, TransactionSubType::PurchaseOfDebtSecurities),
+            543 => TransactionType::Debit(code, TransactionSubType::DomesticCollection),
+            544 => {
                 TransactionType::Debit(code, TransactionSubType::InterestMaturedPrincipalPayment)
             }
-            "546" => TransactionType::Debit(code, TransactionSubType::CommercialPaper),
-            "547" => TransactionType::Debit(code, TransactionSubType::CapitalChange),
-            "548" => TransactionType::Debit(code, TransactionSubType::SavingsBondsSalesAdjustment),
-            "549" => TransactionType::Debit(code, TransactionSubType::MiscellaneousSecurityDebit),
-            "552" => TransactionType::Debit(code, TransactionSubType::CreditReversal),
-            "554" => TransactionType::Debit(code, TransactionSubType::PostingErrorCorrectionDebit),
-            "555" => TransactionType::Debit(code, TransactionSubType::DepositedItemReturned),
-            "557" => TransactionType::Debit(code, TransactionSubType::IndividualAchReturnItem),
-            "558" => TransactionType::Debit(code, TransactionSubType::AchReversalDebit),
-            "561" => TransactionType::Debit(code, TransactionSubType::IndividualRejectedDebit),
-            "563" => TransactionType::Debit(code, TransactionSubType::Overdraft),
-            "564" => TransactionType::Debit(code, TransactionSubType::OverdraftFee),
-            "566" => TransactionType::Debit(code, TransactionSubType::ReturnItem),
-            "567" => TransactionType::Debit(code, TransactionSubType::ReturnItemFee),
-            "568" => TransactionType::Debit(code, TransactionSubType::ReturnItemAdjustment),
-            "574" => TransactionType::Debit(code, TransactionSubType::CumulativeZbaDebits),
-            "575" => TransactionType::Debit(code, TransactionSubType::ZbaDebit),
-            "577" => TransactionType::Debit(code, TransactionSubType::ZbaDebitTransfer),
-            "578" => TransactionType::Debit(code, TransactionSubType::ZbaDebitAdjustment),
-            "581" => TransactionType::Debit(
+            546 => TransactionType::Debit(code, TransactionSubType::CommercialPaper),
+            547 => TransactionType::Debit(code, TransactionSubType::CapitalChange),
+            548 => TransactionType::Debit(code, TransactionSubType::SavingsBondsSalesAdjustment),
+            549 => TransactionType::Debit(code, TransactionSubType::MiscellaneousSecurityDebit),
+            552 => TransactionType::Debit(code, TransactionSubType::CreditReversal),
+            554 => TransactionType::Debit(code, TransactionSubType::PostingErrorCorrectionDebit),
+            555 => TransactionType::Debit(code, TransactionSubType::DepositedItemReturned),
+            557 => TransactionType::Debit(code, TransactionSubType::IndividualAchReturnItem),
+            558 => TransactionType::Debit(code, TransactionSubType::AchReversalDebit),
+            561 => TransactionType::Debit(code, TransactionSubType::IndividualRejectedDebit),
+            563 => TransactionType::Debit(code, TransactionSubType::Overdraft),
+            564 => TransactionType::Debit(code, TransactionSubType::OverdraftFee),
+            566 => TransactionType::Debit(code, TransactionSubType::ReturnItem),
+            567 => TransactionType::Debit(code, TransactionSubType::ReturnItemFee),
+            568 => TransactionType::Debit(code, TransactionSubType::ReturnItemAdjustment),
+            574 => TransactionType::Debit(code, TransactionSubType::CumulativeZbaDebits),
+            575 => TransactionType::Debit(code, TransactionSubType::ZbaDebit),
+            577 => TransactionType::Debit(code, TransactionSubType::ZbaDebitTransfer),
+            578 => TransactionType::Debit(code, TransactionSubType::ZbaDebitAdjustment),
+            581 => TransactionType::Debit(
                 code,
                 TransactionSubType::IndividualControlledDisbursingDebit,
             ),
-            "595" => TransactionType::Debit(code, TransactionSubType::AtmDebit),
-            "597" => TransactionType::Debit(code, TransactionSubType::ArpDebit),
-            "616" => TransactionType::Debit(
+            595 => TransactionType::Debit(code, TransactionSubType::AtmDebit),
+            597 => TransactionType::Debit(code, TransactionSubType::ArpDebit),
+            616 => TransactionType::Debit(
                 code,
                 TransactionSubType::FederalReserveBankCommercialBankDebit,
             ),
-            "622" => TransactionType::Debit(code, TransactionSubType::BrokerDebit),
-            "627" => TransactionType::Debit(code, TransactionSubType::FedFundsPurchased),
-            "629" => TransactionType::Debit(code, TransactionSubType::CashCenterDebit),
-            "631" => TransactionType::Debit(code, TransactionSubType::DebitAdjustment),
-            "633" => TransactionType::Debit(code, TransactionSubType::TrustDebit),
-            "634" => TransactionType::Debit(code, TransactionSubType::YtdAdjustmentDebit),
-            "641" => TransactionType::Debit(code, TransactionSubType::IndividualEscrowDebit),
-            "644" => TransactionType::Debit(code, TransactionSubType::IndividualBackValueDebit),
-            "651" => {
-                TransactionType::Debit(code, TransactionSubType::IndividualInvestmentPurchased)
-            }
-            "654" => TransactionType::Debit(code, TransactionSubType::InterestDebit),
-            "656" => TransactionType::Debit(code, TransactionSubType::SweepPrincipalBuy),
-            "657" => TransactionType::Debit(code, TransactionSubType::FuturesDebit),
-            "658" => TransactionType::Debit(code, TransactionSubType::PrincipalPaymentsDebit),
-            "659" => TransactionType::Debit(code, TransactionSubType::InterestAdjustmentDebit),
-            "661" => TransactionType::Debit(code, TransactionSubType::AccountAnalysisFee),
-            "662" => TransactionType::Debit(code, TransactionSubType::CorrespondentCollectionDebit),
-            "663" => {
+            622 => TransactionType::Debit(code, TransactionSubType::BrokerDebit),
+            627 => TransactionType::Debit(code, TransactionSubType::FedFundsPurchased),
+            629 => TransactionType::Debit(code, TransactionSubType::CashCenterDebit),
+            631 => TransactionType::Debit(code, TransactionSubType::DebitAdjustment),
+            633 => TransactionType::Debit(code, TransactionSubType::TrustDebit),
+            634 => TransactionType::Debit(code, TransactionSubType::YtdAdjustmentDebit),
+            641 => TransactionType::Debit(code, TransactionSubType::IndividualEscrowDebit),
+            644 => TransactionType::Debit(code, TransactionSubType::IndividualBackValueDebit),
+            651 => TransactionType::Debit(code, TransactionSubType::IndividualInvestmentPurchased),
+            654 => TransactionType::Debit(code, TransactionSubType::InterestDebit),
+            656 => TransactionType::Debit(code, TransactionSubType::SweepPrincipalBuy),
+            657 => TransactionType::Debit(code, TransactionSubType::FuturesDebit),
+            658 => TransactionType::Debit(code, TransactionSubType::PrincipalPaymentsDebit),
+            659 => TransactionType::Debit(code, TransactionSubType::InterestAdjustmentDebit),
+            661 => TransactionType::Debit(code, TransactionSubType::AccountAnalysisFee),
+            662 => TransactionType::Debit(code, TransactionSubType::CorrespondentCollectionDebit),
+            663 => {
                 TransactionType::Debit(code, TransactionSubType::CorrespondentCollectionAdjustment)
             }
-            "664" => TransactionType::Debit(code, TransactionSubType::LoanParticipation),
-            "666" => TransactionType::Debit(code, TransactionSubType::CurrencyAndCoinShipped),
-            "667" => TransactionType::Debit(code, TransactionSubType::FoodStampLetter),
-            "668" => TransactionType::Debit(code, TransactionSubType::FoodStampAdjustment),
-            "669" => TransactionType::Debit(code, TransactionSubType::ClearingSettlementDebit),
-            "672" => TransactionType::Debit(code, TransactionSubType::BackValueAdjustment),
-            "673" => TransactionType::Debit(code, TransactionSubType::CustomerPayroll),
-            "674" => TransactionType::Debit(code, TransactionSubType::FrbStatementRecap),
-            "676" => {
-                TransactionType::Debit(code, TransactionSubType::SavingsBondLetterOrAdjustment)
-            }
-            "677" => TransactionType::Debit(code, TransactionSubType::TreasuryTaxAndLoanDebit),
-            "678" => TransactionType::Debit(code, TransactionSubType::TransferOfTreasuryDebit),
-            "679" => {
+            664 => TransactionType::Debit(code, TransactionSubType::LoanParticipation),
+            666 => TransactionType::Debit(code, TransactionSubType::CurrencyAndCoinShipped),
+            667 => TransactionType::Debit(code, TransactionSubType::FoodStampLetter),
+            668 => TransactionType::Debit(code, TransactionSubType::FoodStampAdjustment),
+            669 => TransactionType::Debit(code, TransactionSubType::ClearingSettlementDebit),
+            672 => TransactionType::Debit(code, TransactionSubType::BackValueAdjustment),
+            673 => TransactionType::Debit(code, TransactionSubType::CustomerPayroll),
+            674 => TransactionType::Debit(code, TransactionSubType::FrbStatementRecap),
+            676 => TransactionType::Debit(code, TransactionSubType::SavingsBondLetterOrAdjustment),
+            677 => TransactionType::Debit(code, TransactionSubType::TreasuryTaxAndLoanDebit),
+            678 => TransactionType::Debit(code, TransactionSubType::TransferOfTreasuryDebit),
+            679 => {
                 TransactionType::Debit(code, TransactionSubType::FrbGovernmentChecksCashLetterDebit)
             }
-            "681" => TransactionType::Debit(code, TransactionSubType::FrbGovernmentCheckAdjustment),
-            "682" => TransactionType::Debit(code, TransactionSubType::FrbPostalMoneyOrderDebit),
-            "683" => {
-                TransactionType::Debit(code, TransactionSubType::FrbPostalMoneyOrderAdjustment)
-            }
-            "684" => TransactionType::Debit(code, TransactionSubType::FrbCashLetterAutoChargeDebit),
-            "686" => {
+            681 => TransactionType::Debit(code, TransactionSubType::FrbGovernmentCheckAdjustment),
+            682 => TransactionType::Debit(code, TransactionSubType::FrbPostalMoneyOrderDebit),
+            683 => TransactionType::Debit(code, TransactionSubType::FrbPostalMoneyOrderAdjustment),
+            684 => TransactionType::Debit(code, TransactionSubType::FrbCashLetterAutoChargeDebit),
+            686 => {
                 TransactionType::Debit(code, TransactionSubType::FrbCashLetterAutoChargeAdjustment)
             }
-            "687" => TransactionType::Debit(code, TransactionSubType::FrbFineSortCashLetterDebit),
-            "688" => TransactionType::Debit(code, TransactionSubType::FrbFineSortAdjustment),
-            "691" => TransactionType::Debit(code, TransactionSubType::UniversalDebit),
-            "692" => TransactionType::Debit(code, TransactionSubType::FreightPaymentDebit),
-            "693" => TransactionType::Debit(code, TransactionSubType::ItemizedDebitOver10000),
-            "694" => TransactionType::Debit(code, TransactionSubType::DepositReversal),
-            "695" => TransactionType::Debit(code, TransactionSubType::DepositCorrectionDebit),
-            "696" => TransactionType::Debit(code, TransactionSubType::RegularCollectionDebit),
-            "697" => TransactionType::Debit(code, TransactionSubType::CumulativeDebits),
-            "698" => TransactionType::Debit(code, TransactionSubType::MiscellaneousFees),
-            "699" => TransactionType::Debit(code, TransactionSubType::MiscellaneousDebit),
-            "721" => TransactionType::Credit(code, TransactionSubType::AmountAppliedToInterest),
-            "722" => TransactionType::Credit(code, TransactionSubType::AmountAppliedToPrincipal),
-            "723" => TransactionType::Credit(code, TransactionSubType::AmountAppliedToEscrow),
-            "724" => TransactionType::Credit(code, TransactionSubType::AmountAppliedToLateCharges),
-            "725" => TransactionType::Credit(code, TransactionSubType::AmountAppliedToBuydown),
-            "726" => TransactionType::Credit(code, TransactionSubType::AmountAppliedToMiscFees),
-            "727" => TransactionType::Credit(
+            687 => TransactionType::Debit(code, TransactionSubType::FrbFineSortCashLetterDebit),
+            688 => TransactionType::Debit(code, TransactionSubType::FrbFineSortAdjustment),
+            691 => TransactionType::Debit(code, TransactionSubType::UniversalDebit),
+            692 => TransactionType::Debit(code, TransactionSubType::FreightPaymentDebit),
+            693 => TransactionType::Debit(code, TransactionSubType::ItemizedDebitOver10000),
+            694 => TransactionType::Debit(code, TransactionSubType::DepositReversal),
+            695 => TransactionType::Debit(code, TransactionSubType::DepositCorrectionDebit),
+            696 => TransactionType::Debit(code, TransactionSubType::RegularCollectionDebit),
+            697 => TransactionType::Debit(code, TransactionSubType::CumulativeDebits),
+            698 => TransactionType::Debit(code, TransactionSubType::MiscellaneousFees),
+            699 => TransactionType::Debit(code, TransactionSubType::MiscellaneousDebit),
+            721 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToInterest),
+            722 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToPrincipal),
+            723 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToEscrow),
+            724 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToLateCharges),
+            725 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToBuydown),
+            726 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToMiscFees),
+            727 => TransactionType::Credit(
                 code,
                 TransactionSubType::AmountAppliedToDeferredInterestDetail,
             ),
-            "728" => {
-                TransactionType::Credit(code, TransactionSubType::AmountAppliedToServiceCharge)
-            }
-            "890" => TransactionType::Unknown(code, TransactionSubType::Info),
-            other_code => match other_code.parse::<i16>() {
-                Ok(n) if n >= 920 && n <= 959 => {
-                    return TransactionType::Credit(code, TransactionSubType::Custom);
-                }
-                Ok(n) if n >= 960 && n <= 999 => {
-                    return TransactionType::Debit(code, TransactionSubType::Custom);
-                }
-                _ => TransactionType::Unknown(code, TransactionSubType::Unknown),
-            },
+            728 => TransactionType::Credit(code, TransactionSubType::AmountAppliedToServiceCharge),
+            890 => TransactionType::Unknown(code, TransactionSubType::Info),
+            920..=959 => TransactionType::Credit(code, TransactionSubType::Custom),
+            960..=999 => TransactionType::Debit(code, TransactionSubType::Custom),
+            _ => TransactionType::Unknown(code, TransactionSubType::Unknown),
         }
     }
 }
@@ -619,25 +759,80 @@ impl Serialize for TransactionType {
         S: Serializer,
     {
         let mut state = serializer.serialize_struct("TransactionType", 3)?;
+        state.serialize_field("code", &format!("{:03}", self.code()))?;
+        state.serialize_field("direction", &self.direction())?;
+        state.serialize_field("type", self.subtype())?;
+        state.end()
+    }
+}
 
-        match *self {
-            TransactionType::Credit(ref c, ref t) => {
-                state.serialize_field("code", c)?;
-                state.serialize_field("direction", "credit")?;
-                state.serialize_field("type", t)?;
-            }
-            TransactionType::Debit(ref c, ref t) => {
-                state.serialize_field("code", c)?;
-                state.serialize_field("direction", "debit")?;
-                state.serialize_field("type", t)?;
-            }
-            TransactionType::Unknown(ref c, ref t) => {
-                state.serialize_field("code", c)?;
-                state.serialize_field("direction", "unknown")?;
-                state.serialize_field("type", t)?;
-            }
+/// The type code and a description of it, e.g. "142 ACH credit received", or
+/// the label given to a custom code.
+impl fmt::Display for TransactionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:03} {}", self.code(), self.description())
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionType {
+    /// The direction and type are derived from the code, except for a custom
+    /// code that was given a label, whose label and direction are read back
+    /// as they were written.
+    fn deserialize<D>(deserializer: D) -> Result<TransactionType, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Fields {
+            code: String,
+            direction: Option<Direction>,
+            #[serde(rename = "type")]
+            subtype: Option<TransactionSubType>,
         }
 
-        state.end()
+        let fields = Fields::deserialize(deserializer)?;
+        let custom_codes = match (fields.subtype, fields.direction) {
+            (Some(TransactionSubType::Labeled(label)), Some(direction)) => {
+                let code = parse_type_code(&fields.code).map_err(de::Error::custom)?;
+                CustomCodes::new().set(code, label, direction)
+            }
+            _ => CustomCodes::new(),
+        };
+        TransactionType::parse_with_custom_codes(&fields.code, &custom_codes)
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labeled_custom_codes_keep_their_label_and_direction_through_json() {
+        let custom_codes = CustomCodes::new().set(950, "Crypto sweep", Direction::Debit);
+        let transaction_type =
+            TransactionType::parse_with_custom_codes("950", &custom_codes).unwrap();
+
+        let json = serde_json::to_string(&transaction_type).unwrap();
+        assert_eq!(
+            json,
+            r#"{"code":"950","direction":"debit","type":"Crypto sweep"}"#
+        );
+
+        let read: TransactionType = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.direction(), Direction::Debit);
+        assert_eq!(
+            read.subtype(),
+            &TransactionSubType::Labeled("Crypto sweep".to_string())
+        );
+    }
+
+    #[test]
+    fn other_codes_are_read_from_the_code_alone() {
+        let read: TransactionType =
+            serde_json::from_str(r#"{"code":"950","direction":"debit","type":"custom"}"#).unwrap();
+
+        assert_eq!(read.direction(), Direction::Credit);
+        assert_eq!(read.subtype(), &TransactionSubType::Custom);
     }
 }
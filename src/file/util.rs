@@ -1,47 +1,134 @@
 use chrono::{NaiveDate, NaiveTime};
+use std::borrow::Cow;
 use std::str::FromStr;
 
+use super::time::Time;
+use super::version::Version;
+use crate::options::{EndOfDayTime, ParseOptions};
+
+/// The field with surrounding whitespace and any slashes removed. The field is
+/// borrowed rather than copied unless there's a slash in it, so fields that
+/// are only looked at or parsed further don't need to be allocated.
+pub fn parse_str(string: &str) -> Cow<'_, str> {
+    let string = string.trim();
+    if string.contains('/') {
+        Cow::Owned(string.replace('/', ""))
+    } else {
+        Cow::Borrowed(string)
+    }
+}
+
+/// Like `parse_str`, for fields that are kept.
 pub fn parse_string(string: &str) -> String {
-    string.trim().replace("/", "")
+    parse_str(string).into_owned()
 }
 
-pub fn parse_currency(string: &str, default: &str) -> String {
-    return match parse_string(string).as_str() {
-        "" => default.to_string(),
-        c => c.to_string(),
-    };
+/// A transaction's text from `Node::text_from`, with the whitespace around it
+/// removed. Slashes within it are kept, since they're part of the text.
+pub fn parse_text(text: Cow<'_, str>) -> Cow<'_, str> {
+    match text {
+        Cow::Borrowed(text) => Cow::Borrowed(text.trim()),
+        Cow::Owned(text) => Cow::Owned(text.trim().to_string()),
+    }
 }
 
-pub fn parse_date(string: &str) -> Option<NaiveDate> {
-    let date = parse_string(string);
-    let maybe_date = NaiveDate::parse_from_str(&date, "%y%m%d");
-    match maybe_date {
-        Ok(d) => Some(d),
-        Err(_) => None,
+pub fn parse_optional_string(string: &str) -> Option<String> {
+    match parse_str(string) {
+        s if s.is_empty() => None,
+        s => Some(s.into_owned()),
     }
 }
 
-pub fn parse_time(string: &str) -> Option<String> {
-    match parse_string(string).as_str() {
+/// A date in YYMMDD format, with the century given by
+/// `ParseOptions::year_pivot`, or in YYYYMMDD format as well for version 3
+/// files.
+pub fn parse_date(string: &str, options: &ParseOptions) -> Option<NaiveDate> {
+    let date = parse_str(string);
+    if !date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let number = |range| date.get(range).and_then(|n: &str| n.parse::<u32>().ok());
+    let (year, rest) = match (options.version, date.len()) {
+        (Some(Version::Bai3), 8) => (number(0..4)? as i32, 4),
+        (_, 6) => {
+            let year = number(0..2)?;
+            let century = if year >= u32::from(options.year_pivot) {
+                1900
+            } else {
+                2000
+            };
+            ((century + year) as i32, 2)
+        }
+        _ => return None,
+    };
+
+    NaiveDate::from_ymd_opt(year, number(rest..rest + 2)?, number(rest + 2..rest + 4)?)
+}
+
+pub fn parse_time(string: &str, options: &ParseOptions) -> Option<Time> {
+    let end_of_day = |code| match options.end_of_day {
+        EndOfDayTime::EndOfDay => Some(Time::EndOfDay),
+        EndOfDayTime::LastSecond => NaiveTime::from_hms_opt(23, 59, 59).map(Time::At),
+        EndOfDayTime::Raw => Some(Time::Raw(code)),
+    };
+
+    match parse_str(string).as_ref() {
         "" => None,
-        "2400" => Some("end of day".to_string()),
-        "9999" => Some("end of day".to_string()),
-        time => match NaiveTime::parse_from_str(time, "%H%M") {
-            Ok(t) => Some(t.to_string()),
-            Err(_) => None,
-        },
+        "2400" => end_of_day(2400),
+        "9999" => end_of_day(9999),
+        "0000" if options.midnight_is_end_of_day => end_of_day(0),
+        time => {
+            let format = match (options.version, time.len()) {
+                (Some(Version::Bai3), 6) => "%H%M%S",
+                _ => "%H%M",
+            };
+            match NaiveTime::parse_from_str(time, format) {
+                Ok(t) => Some(Time::At(t)),
+                Err(_) => None,
+            }
+        }
     }
 }
 
 pub fn parse_int<T: FromStr>(string: &str) -> Option<T> {
-    let number = string
-        .trim()
-        .replace("/", "")
-        .trim_start_matches('0')
-        .parse::<T>();
-
-    match number {
-        Ok(n) => Some(n),
-        Err(_) => None,
+    parse_str(string).parse::<T>().ok()
+}
+
+/// An amount in minor units, with an optional leading `+` or `-`. Only a `/`
+/// ending the record is removed, so a field with a slash anywhere else isn't
+/// read as an amount. An empty field is `None` rather than an amount of zero.
+pub fn parse_amount<T: FromStr>(string: &str) -> Option<T> {
+    let string = string.trim();
+    let string = string.strip_suffix('/').unwrap_or(string).trim_end();
+    string.parse::<T>().ok()
+}
+
+/// The date as YYMMDD, or as YYYYMMDD in a version 3 file.
+pub fn format_date(date: Option<NaiveDate>, version: Option<Version>) -> String {
+    let format = match version {
+        Some(Version::Bai3) => "%Y%m%d",
+        _ => "%y%m%d",
+    };
+    date.map(|d| d.format(format).to_string())
+        .unwrap_or_default()
+}
+
+/// The time as HHMM, or as HHMMSS in a version 3 file. The end of the day and
+/// raw codes are written as they're read, as four digits.
+pub fn format_time(time: Option<Time>, version: Option<Version>) -> String {
+    let format = match version {
+        Some(Version::Bai3) => "%H%M%S",
+        _ => "%H%M",
+    };
+    match time {
+        None => String::new(),
+        Some(Time::EndOfDay) => "2400".to_string(),
+        Some(Time::Raw(code)) => format!("{:04}", code),
+        Some(Time::At(time)) => time.format(format).to_string(),
     }
 }
+
+pub fn format_int<T: ToString>(number: Option<T>) -> String {
+    number.map(|n| n.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use super::util::parse_int;
+use crate::options::ParseOptions;
+use crate::scanner::node::Node;
+
+/// The version of the BAI format a file was written in, as given by the
+/// version number in its file header.
+///
+/// Version 3 files can write dates with a four-digit year (YYYYMMDD) and times
+/// with seconds (HHMMSS), which are read as well as the version 2 formats when
+/// a file is version 3.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(into = "u8", from = "u8")]
+pub enum Version {
+    Bai1,
+    Bai2,
+    Bai3,
+    Unknown(u8),
+}
+
+impl Version {
+    pub fn from_number(number: u8) -> Version {
+        match number {
+            1 => Version::Bai1,
+            2 => Version::Bai2,
+            3 => Version::Bai3,
+            number => Version::Unknown(number),
+        }
+    }
+
+    /// The version number as it's written in the file header.
+    pub fn number(self) -> u8 {
+        match self {
+            Version::Bai1 => 1,
+            Version::Bai2 => 2,
+            Version::Bai3 => 3,
+            Version::Unknown(number) => number,
+        }
+    }
+}
+
+impl From<u8> for Version {
+    fn from(number: u8) -> Version {
+        Version::from_number(number)
+    }
+}
+
+impl From<Version> for u8 {
+    fn from(version: Version) -> u8 {
+        version.number()
+    }
+}
+
+/// The options to parse a file with, with the version taken from its file
+/// header unless the options already give one. Headers without a version
+/// number are read as version 2.
+pub(crate) fn with_version<'a>(options: &'a ParseOptions, header: &Node) -> Cow<'a, ParseOptions> {
+    if options.version.is_some() {
+        return Cow::Borrowed(options);
+    }
+
+    let version = header
+        .fields()
+        .get(8)
+        .and_then(|field| parse_int(field))
+        .map_or(Version::Bai2, Version::from_number);
+    Cow::Owned(ParseOptions {
+        version: Some(version),
+        ..options.clone()
+    })
+}
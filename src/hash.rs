@@ -0,0 +1,9 @@
+use sha2::{Digest, Sha256};
+
+/// The hex encoded SHA-256 hash of the raw bytes of a file.
+pub fn raw_hash(raw: &[u8]) -> String {
+    Sha256::digest(raw)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
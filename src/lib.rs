@@ -1,63 +1,543 @@
 use chrono::NaiveDate;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
 
-use crate::file::group::Group;
-use crate::file::util::{parse_date, parse_int, parse_string, parse_time};
+use crate::file::util::{parse_amount, parse_date, parse_int, parse_string, parse_time};
+use crate::file::version;
 
-use crate::scanner::node::Node;
+use crate::scanner::node::{Node, Tree};
 use crate::scanner::Scanner;
 
+mod aggregate;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod availability;
+mod borrowed;
+mod builder;
+mod codes;
+#[cfg(feature = "json")]
+mod corpus;
+mod csv;
+mod day_view;
+mod encoding;
+mod error;
 mod file;
+mod hash;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod ofx;
+mod options;
+mod parallel;
+mod parser;
+mod profile;
+mod provenance;
+mod reader;
+mod reconcile;
+mod rules;
 mod scanner;
+mod schedule;
+mod snapshot;
+mod stats;
+mod validation;
+mod writer;
 
-#[derive(Debug, Serialize)]
+pub use crate::aggregate::Aggregator;
+pub use crate::availability::{AvailabilityDate, Calendar, HolidayCalendar, Weekends};
+pub use crate::borrowed::{AccountRef, AmountRef, Bai2FileRef, GroupRef, TextRef, TransactionRef};
+pub use crate::builder::{AccountBuilder, FileBuilder, GroupBuilder};
+pub use crate::codes::{code_table, CodeInfo, CodeLevel, CodeTable, CustomCodeRange};
+#[cfg(feature = "json")]
+pub use crate::corpus::{Corpus, CorpusFile};
+pub use crate::day_view::DayView;
+pub use crate::encoding::Encoding;
+pub use crate::error::Bai2Error;
+pub use crate::file::account::{
+    Account, Amount, AmountSubtype, AmountType, CurrencySource, SkippedAccount,
+};
+pub use crate::file::category::Category;
+pub use crate::file::currency::{Currency, CurrencyExponents};
+pub use crate::file::custom_codes::CustomCodes;
+pub use crate::file::direction::Direction;
+pub use crate::file::funds_type::{FundsSubType, FundsType};
+pub use crate::file::group::{AsOfDateModifier, Group, GroupStatus, SkippedGroup};
+pub use crate::file::minor_units::MinorUnits;
+pub use crate::file::opaque_record::OpaqueRecord;
+pub use crate::file::original_format::OriginalFormat;
+pub use crate::file::time::Time;
+pub use crate::file::transaction::Transaction;
+pub use crate::file::transaction_type::{
+    InvalidTypeCode, TransactionSubType, TransactionType, UnknownTypeCode,
+};
+pub use crate::file::version::Version;
+pub use crate::hash::raw_hash;
+#[allow(deprecated)]
+pub use crate::options::JsonOptions;
+pub use crate::options::{EndOfDayTime, ParseOptions, WriteOptions};
+pub use crate::parser::Bai2Parser;
+pub use crate::profile::{Profile, Profiles};
+pub use crate::provenance::Provenance;
+pub use crate::reader::Bai2Reader;
+pub use crate::reconcile::Discrepancy;
+pub use crate::rules::{CategoryRule, CategoryRules};
+pub use crate::schedule::{AvailabilitySchedule, ScheduledFunds};
+pub use crate::snapshot::Snapshot;
+pub use crate::stats::{FileStats, RecordStats, RecordTypeStats, SectionStats};
+pub use crate::validation::{Severity, ValidationCheck, ValidationConfig, ValidationFailure};
+
+/// The version of the serialized output shape. See the README for the rules on
+/// when this changes.
+pub const SCHEMA_VERSION: u32 = 5;
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Bai2File {
+    pub block_size: Option<u16>,
+    pub control_total: Option<i64>,
     pub creation_date: Option<NaiveDate>,
-    pub creation_time: Option<String>,
+    pub creation_time: Option<Time>,
     pub file_id: String,
     pub groups: Vec<Group>,
+    /// How the file header and trailer were laid out, along with the file's
+    /// line endings, when the file was parsed with
+    /// `ParseOptions::preserve_formatting`. It isn't serialized.
+    #[serde(skip)]
+    pub original: Option<OriginalFormat>,
+    /// The records with codes that aren't part of the format that were found
+    /// outside of any group, when unknown records are allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_records: Vec<OpaqueRecord>,
+    pub physical_record_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// The file header and file trailer records and their continuations
+    /// exactly as they appeared in the file, one line each, when they were
+    /// kept with `ParseOptions::keep_raw_records`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
     pub receiver: String,
+    pub schema_version: u32,
     pub sender: String,
+    /// The groups that were left out because they couldn't be parsed, when
+    /// `ParseOptions::skip_invalid_groups` is set. It's only serialized, so
+    /// it's empty for files loaded from JSON.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_groups: Vec<SkippedGroup>,
+    /// How the records were laid out across lines in the file that was
+    /// parsed. It isn't serialized, so it's empty for files loaded from JSON
+    /// or built from scratch.
+    #[serde(skip)]
+    pub stats: FileStats,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation_failures: Vec<ValidationFailure>,
     pub version_number: Option<u8>,
 }
 
 impl Bai2File {
-    pub fn new(content: String) -> Result<Bai2File, &'static str> {
-        let mut scanner = Scanner::new(&content);
-        match scanner.scan() {
-            Ok(scan_tree) => Bai2File::from_scan(scan_tree),
-            Err(e) => Err(e),
+    pub fn new(content: String) -> Result<Bai2File, Bai2Error> {
+        Bai2File::new_with_options(content, &ParseOptions::default())
+    }
+
+    pub fn new_with_options(
+        content: String,
+        options: &ParseOptions,
+    ) -> Result<Bai2File, Bai2Error> {
+        let mut scanner = Scanner::new(options);
+        let mut file = match scanner.scan(&content) {
+            Ok(scan_tree) => Bai2File::from_scan(&scan_tree, scanner.skipped_records(), options)?,
+            Err(e) => return Err(e),
+        };
+
+        if let Some(original) = &mut file.original {
+            original.set_line_endings(&content);
+        }
+        Ok(file)
+    }
+
+    /// Read and parse the file at the path.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Bai2File, Bai2Error> {
+        Bai2File::from_path_with_options(path, &ParseOptions::default())
+    }
+
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<Bai2File, Bai2Error> {
+        let path = path.as_ref();
+        let file = fs::File::open(path).map_err(|e| Bai2Error::OpenFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        Bai2File::from_reader_with_options(file, options)
+    }
+
+    /// Parse the file at the path from a memory map of it, scanning it a line
+    /// at a time rather than reading it into a string first, for files too
+    /// large to comfortably hold twice. The lines are still copied into the
+    /// scanned records, so the content is held in memory once rather than
+    /// not at all. The file must not be changed while it's being parsed.
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap(path: impl AsRef<Path>) -> Result<Bai2File, Bai2Error> {
+        Bai2File::from_path_mmap_with_options(path, &ParseOptions::default())
+    }
+
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<Bai2File, Bai2Error> {
+        mmap::parse(path.as_ref(), options)
+    }
+
+    /// Read everything from the reader as UTF-8 and parse it.
+    pub fn from_reader(reader: impl Read) -> Result<Bai2File, Bai2Error> {
+        Bai2File::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    pub fn from_reader_with_options(
+        mut reader: impl Read,
+        options: &ParseOptions,
+    ) -> Result<Bai2File, Bai2Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| Bai2Error::ReadFailed {
+                // anything read before the error is left in the buffer
+                line: 1 + bytes.iter().filter(|&&b| b == b'\n').count(),
+                message: e.to_string(),
+            })?;
+
+        Bai2File::new_with_options(Encoding::Utf8.decode(&bytes)?, options)
+    }
+
+    /// Read and parse a file from an async reader one line at a time, so that
+    /// parsing a file as it streams in doesn't block the runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader(
+        reader: impl tokio::io::AsyncBufRead + Unpin,
+    ) -> Result<Bai2File, Bai2Error> {
+        Bai2File::from_async_reader_with_options(reader, &ParseOptions::default()).await
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader_with_options(
+        reader: impl tokio::io::AsyncBufRead + Unpin,
+        options: &ParseOptions,
+    ) -> Result<Bai2File, Bai2Error> {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut scanner = Scanner::new(options);
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await.map_err(|e| Bai2Error::ReadFailed {
+            line: scanner.line_number() + 1,
+            message: e.to_string(),
+        })? {
+            let line = match scanner.line_number() {
+                0 => line.strip_prefix('\u{feff}').unwrap_or(&line),
+                _ => &line,
+            };
+            scanner.push_line(line)?;
+        }
+        scanner.close()?;
+
+        Bai2File::from_scan(scanner.tree(), scanner.skipped_records(), options)
+    }
+
+    /// Attach source metadata for the raw content this file was parsed from.
+    pub fn with_provenance(mut self, filename: Option<&str>, raw: &[u8]) -> Bai2File {
+        self.provenance = Some(Provenance::new(filename, raw));
+        self
+    }
+
+    /// Load a file from the JSON it was serialized to.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Bai2File, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    #[cfg(feature = "json")]
+    #[deprecated(
+        note = "the text of a transaction is always written as one string, so use `to_json`"
+    )]
+    #[allow(deprecated)]
+    pub fn to_json_with_options(
+        &self,
+        _options: &JsonOptions,
+    ) -> Result<String, serde_json::Error> {
+        self.to_json()
+    }
+
+    #[cfg(feature = "json")]
+    #[deprecated(
+        note = "the text of a transaction is always written as one string, so use `to_json_pretty`"
+    )]
+    #[allow(deprecated)]
+    pub fn to_json_pretty_with_options(
+        &self,
+        _options: &JsonOptions,
+    ) -> Result<String, serde_json::Error> {
+        self.to_json_pretty()
+    }
+
+    /// The hex encoded SHA-256 hash of the file's canonical JSON form, which
+    /// excludes provenance and has its keys sorted. Two transmissions with the
+    /// same content have the same hash regardless of their formatting.
+    #[cfg(feature = "json")]
+    pub fn content_hash(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap();
+        if let Some(object) = value.as_object_mut() {
+            object.remove("provenance");
         }
+
+        raw_hash(value.to_string().as_bytes())
+    }
+
+    /// Write the file out in the BAI2 format, with control totals and record
+    /// counts computed from its contents.
+    pub fn to_bai2(&self) -> String {
+        self.to_bai2_with_options(&WriteOptions::default())
+    }
+
+    pub fn to_bai2_with_options(&self, options: &WriteOptions) -> String {
+        writer::write(self, options)
+    }
+
+    /// Write every transaction out as a row of CSV, with the date, account, and
+    /// currency it belongs to, for loading into spreadsheets and warehouses.
+    pub fn to_csv(&self) -> String {
+        csv::write(self)
+    }
+
+    /// Convert the transactions to an Arrow record batch, one row per
+    /// transaction, for loading into data warehouses and lakehouses.
+    #[cfg(feature = "arrow")]
+    pub fn transactions_record_batch(
+        &self,
+    ) -> Result<arrow_array::RecordBatch, arrow_schema::ArrowError> {
+        arrow::transactions(self)
+    }
+
+    /// Convert the balances and summaries in the account identifiers to an
+    /// Arrow record batch, one row per amount.
+    #[cfg(feature = "arrow")]
+    pub fn amounts_record_batch(
+        &self,
+    ) -> Result<arrow_array::RecordBatch, arrow_schema::ArrowError> {
+        arrow::amounts(self)
+    }
+
+    /// Write the transactions to a Parquet file with the columns of
+    /// `transactions_record_batch()`.
+    #[cfg(feature = "parquet")]
+    pub fn write_transactions_parquet<W: std::io::Write + Send>(
+        &self,
+        writer: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        arrow::write_parquet(&self.transactions_record_batch()?, writer)
     }
 
-    fn from_scan(root_node: Node) -> Result<Bai2File, &'static str> {
-        let header_fields = &root_node.fields();
-        if header_fields.len() < 9 {
-            return Err("Invalid file header. Expected 9 fields, but found less.");
+    /// Write the balances and summaries to a Parquet file with the columns of
+    /// `amounts_record_batch()`.
+    #[cfg(feature = "parquet")]
+    pub fn write_amounts_parquet<W: std::io::Write + Send>(
+        &self,
+        writer: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        arrow::write_parquet(&self.amounts_record_batch()?, writer)
+    }
+
+    /// Write the accounts and transactions out as an OFX bank statement, for
+    /// tools that only import OFX.
+    pub fn to_ofx(&self) -> String {
+        ofx::write(self)
+    }
+
+    /// Sort the transactions in every account chronologically. See
+    /// `Account::sort_transactions`.
+    pub fn sort_transactions(&mut self) {
+        for group in &mut self.groups {
+            for account in group.accounts_mut() {
+                account.sort_transactions();
+            }
         }
+    }
 
-        let trailer_fields = root_node.sibling_fields();
-        if trailer_fields.len() < 4 {
-            return Err("Invalid file trailer. Expected 4 fields, but found less.");
+    /// Keep only the transactions for which `keep` returns true, given each
+    /// transaction and the account it's in. Sequence numbers aren't changed,
+    /// so the transactions that are left still show where they were.
+    pub fn retain_transactions<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Account, &Transaction) -> bool,
+    {
+        for group in &mut self.groups {
+            for account in group.accounts_mut() {
+                let transactions = std::mem::take(&mut account.transactions);
+                account.transactions = transactions
+                    .into_iter()
+                    .filter(|t| keep(account, t))
+                    .collect();
+            }
         }
+    }
+
+    /// Compare the credit and debit summaries on each account to the detail
+    /// transactions in the matching type code family, returning the summaries
+    /// whose amount or item count doesn't agree with the details.
+    pub fn reconcile(&self) -> Vec<Discrepancy> {
+        reconcile::file(self)
+    }
+
+    /// The funds becoming available on each date, per currency. See
+    /// `AvailabilitySchedule`.
+    pub fn availability_schedule(&self) -> AvailabilitySchedule {
+        AvailabilitySchedule::new(self)
+    }
 
-        let groups_result = root_node
-            .children
+    /// The version of the format the file header says the file is in.
+    pub fn version(&self) -> Option<Version> {
+        self.version_number.map(Version::from_number)
+    }
+
+    /// The groups of the file in the order of the intraday reports they
+    /// make up. See `Snapshot`.
+    pub fn snapshots(&self) -> Vec<Snapshot<'_>> {
+        Snapshot::order(&self.groups)
+    }
+
+    pub(crate) fn transactions_mut(&mut self) -> impl Iterator<Item = &mut Transaction> {
+        self.groups
+            .iter_mut()
+            .flat_map(|g| g.accounts_mut().iter_mut())
+            .flat_map(|a| a.transactions_mut().iter_mut())
+    }
+
+    fn from_scan(
+        tree: &Tree,
+        skipped_records: usize,
+        options: &ParseOptions,
+    ) -> Result<Bai2File, Bai2Error> {
+        let root_node = tree.root();
+        let options = &*version::with_version(options, &root_node);
+        let header_fields = root_node
+            .required_fields(9, options.allow_short_records)
+            .map_err(|(line, field)| Bai2Error::InvalidFileHeader { line, field })?;
+
+        let control_total = match root_node.sibling() {
+            Some(trailer) => {
+                let trailer_fields = trailer
+                    .required_fields(4, options.allow_short_records)
+                    .map_err(|(line, field)| Bai2Error::InvalidFileTrailer { line, field })?;
+                parse_amount(trailer_fields[1])
+            }
+            None if options.allow_missing_trailers => None,
+            None => {
+                return Err(Bai2Error::FileTrailerMissing {
+                    line: root_node.line_number(),
+                })
+            }
+        };
+
+        let group_nodes: Vec<_> = root_node.children().collect();
+        let accounts_before: Vec<usize> = group_nodes
+            .iter()
+            .scan(0, |before, n| {
+                let count = *before;
+                *before += n.children().count();
+                Some(count)
+            })
+            .collect();
+        let parse_group =
+            |i: usize, n: &Node| Group::from_node(n, i + 1, accounts_before[i], options);
+        let (groups, skipped_groups) = if options.skip_invalid_groups {
+            let mut groups = Vec::with_capacity(group_nodes.len());
+            let mut skipped_groups = Vec::new();
+            let results = parallel::map(&group_nodes, options.parallel, parse_group);
+            for (i, result) in results.into_iter().enumerate() {
+                match result {
+                    Ok(group) => groups.push(group),
+                    Err(error) => skipped_groups.push(SkippedGroup {
+                        error,
+                        line: group_nodes[i].line_number(),
+                        sequence_number: i + 1,
+                    }),
+                }
+            }
+            (groups, skipped_groups)
+        } else {
+            let groups = parallel::try_map(&group_nodes, options.parallel, parse_group)?;
+            (groups, Vec::new())
+        };
+
+        let skipped_lines: Vec<usize> = skipped_groups
             .iter()
-            .map(Group::from_node)
-            .collect::<Result<Vec<Group>, &'static str>>();
-
-        match groups_result {
-            Err(e) => Err(e),
-            Ok(groups) => Ok(Bai2File {
-                creation_date: parse_date(header_fields[3]),
-                creation_time: parse_time(header_fields[4]),
-                file_id: parse_string(header_fields[5]),
-                groups,
-                receiver: parse_string(header_fields[2]),
-                sender: parse_string(header_fields[1]),
-                version_number: parse_int(header_fields[8]),
-            }),
+            .map(|g| g.line)
+            .chain(
+                groups
+                    .iter()
+                    .flat_map(|g| g.skipped_accounts())
+                    .map(|a| a.line),
+            )
+            .collect();
+        let errors = skipped_records + skipped_lines.len();
+        if let Some(max) = options.max_errors.filter(|&max| errors > max) {
+            return Err(Bai2Error::TooManyErrors {
+                line: skipped_lines.into_iter().max().unwrap_or(0),
+                max,
+            });
         }
+        let validation_failures =
+            validation::check_trailers(&root_node, &groups, &skipped_groups, options, errors)?;
+
+        let mut file = Bai2File {
+            control_total,
+            creation_date: parse_date(header_fields[3], options),
+            creation_time: parse_time(header_fields[4], options),
+            file_id: parse_string(header_fields[5]),
+            block_size: parse_int(header_fields[7]),
+            groups,
+            original: None,
+            other_records: OpaqueRecord::from_parent(&root_node),
+            physical_record_length: parse_int(header_fields[6]),
+            provenance: None,
+            raw: options
+                .keep_raw_records
+                .then(|| root_node.raw_with_trailer()),
+            receiver: parse_string(header_fields[2]),
+            schema_version: SCHEMA_VERSION,
+            sender: parse_string(header_fields[1]),
+            skipped_groups,
+            stats: stats::collect(&root_node),
+            validation_failures,
+            version_number: parse_int(header_fields[8]),
+        };
+
+        if options.preserve_formatting {
+            let original = OriginalFormat::new(
+                &writer::file_header(&file),
+                &root_node,
+                writer::file_contents(&file),
+            );
+            // The groups and accounts that were left out won't be written, so
+            // the trailer no longer counts what's in the file
+            let skipped_accounts = file.groups.iter().any(|g| !g.skipped_accounts.is_empty());
+            file.original = Some(if file.skipped_groups.is_empty() && !skipped_accounts {
+                original
+            } else {
+                original.without_trailer()
+            });
+        }
+
+        Ok(file)
     }
 }
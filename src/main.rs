@@ -1,19 +1,239 @@
-use bai2::Bai2File;
-use clap::Parser;
+use bai2::{
+    code_table, raw_hash, AvailabilitySchedule, Bai2File, DayView, Encoding, ParseOptions,
+    Provenance, Severity, ValidationCheck, Weekends,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cli::filter::TransactionFilter;
+use cli::manifest::{Manifest, ManifestEntry, Status};
 use env_logger::Env;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+mod cli;
 
 /// Parse a BAI2 file into a rust object
 #[derive(Debug, Parser)]
 #[command(name = "bai2")]
 #[command(about = "Parse a BAI2 file", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
-    /// path to your BAI2 file
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    parse: ParseArgs,
+
+    /// the character encoding of the file: utf8, latin1, cp1252, ebcdic, or
+    /// auto to detect it
+    #[arg(long, global = true, default_value = "utf8")]
+    encoding: Encoding,
+
+    /// fail on anything that doesn't follow the spec: missing trailers, short
+    /// records, unknown record codes, and trailer checks that don't add up
+    #[arg(long, global = true, conflicts_with = "lenient")]
+    strict: bool,
+
+    /// parse as much of the file as possible, allowing missing trailers and
+    /// short records and leaving out groups that can't be parsed, which are
+    /// listed with their errors in `skipped_groups`
+    #[arg(long, global = true)]
+    lenient: bool,
+
+    /// make a trailer check an error, a warning, or ignored, e.g.
+    /// `--validation file_record_count=ignore` (repeatable)
+    #[arg(long, global = true, value_name = "CHECK=LEVEL", value_parser = parse_validation)]
+    validation: Vec<(ValidationCheck, Severity)>,
+
+    /// fail on records with a record code that isn't part of BAI2, instead of
+    /// keeping them in `other_records`
+    #[arg(long, global = true)]
+    reject_unknown_records: bool,
+
+    /// give up after this many errors, counting skipped records and
+    /// validation failures
+    #[arg(long, global = true, value_name = "COUNT")]
+    max_errors: Option<usize>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Parse a BAI2 file and print it as JSON (the default)
+    Parse(ParseArgs),
+
+    /// Print the funds becoming available on each date, per currency
+    Availability(AvailabilityArgs),
+
+    /// List every transaction in the file, one per line
+    Transactions(TransactionsArgs),
+
+    /// Convert the accounts and transactions to an OFX bank statement
+    Ofx(OfxArgs),
+
+    /// Print how the records are laid out across lines and continuations
+    Stats(StatsArgs),
+
+    /// Print the balances and transaction totals of each account
+    Report(ReportArgs),
+
+    /// Parse many BAI2 files into JSON files in a directory, listing each one
+    /// in a manifest
+    Convert(ConvertArgs),
+
+    /// Apply a day's files in a directory in the order they were created,
+    /// with their updates, deletions, and corrections, and print the result
+    ApplyCorrections(ApplyCorrectionsArgs),
+
+    /// Print every type code with its description, direction, and level, and
+    /// the ranges left for custom codes
+    Codes(CodesArgs),
+}
+
+#[derive(Debug, Args)]
+struct ParseArgs {
+    /// path to your BAI2 file, or an s3:// or sftp:// URL when those features
+    /// are enabled
+    #[arg(required = true)]
+    path: Option<PathBuf>,
+
+    /// include the filename, hash, and parse time of the input in the output
+    #[arg(long)]
+    provenance: bool,
+
+    /// sort each account's transactions by value date
+    #[arg(long)]
+    sort_transactions: bool,
+
+    /// include the original lines of each transaction in the output
+    #[arg(long)]
+    raw: bool,
+
+    /// join text continued onto the next line straight on, for banks that
+    /// wrap text mid-word, rather than after a comma
+    #[arg(long)]
+    join_text: bool,
+
+    /// compress the JSON output with gzip, when the `gzip` feature is enabled
+    #[arg(long)]
+    gzip: bool,
+
+    /// POST the JSON to this URL instead of printing it, when the `webhook`
+    /// feature is enabled. It's signed with `BAI2_WEBHOOK_SECRET` when set
+    #[arg(long, value_name = "URL", conflicts_with_all = ["gzip", "check_against"])]
+    post_to: Option<String>,
+
+    /// how many times to retry posting when the request fails or the server
+    /// returns a 5xx status
+    #[arg(long, value_name = "COUNT", default_value_t = 3, requires = "post_to")]
+    post_retries: u32,
+
+    /// read the file as a stream of records that each end with `/`, for
+    /// banks that don't put each record on its own line
+    #[arg(long)]
+    continuous_stream: bool,
+
+    /// reassemble records from fixed-length physical records of this many
+    /// characters, e.g. 80, that long records are wrapped across
+    #[arg(long, value_name = "LENGTH")]
+    record_length: Option<usize>,
+
+    /// compare the output to a JSON snapshot, exiting non-zero if they differ
+    #[arg(long, value_name = "EXPECTED")]
+    check_against: Option<PathBuf>,
+
+    #[command(flatten)]
+    filter: TransactionFilter,
+}
+
+#[derive(Debug, Args)]
+struct AvailabilityArgs {
+    /// path to your BAI2 file, or an s3:// or sftp:// URL when those features
+    /// are enabled
+    path: PathBuf,
+
+    /// count availability in weekdays rather than calendar days
+    #[arg(long)]
+    business_days: bool,
+}
+
+#[derive(Debug, Args)]
+struct TransactionsArgs {
+    /// path to your BAI2 file, or an s3:// or sftp:// URL when those features
+    /// are enabled
+    path: PathBuf,
+
+    /// print the transactions as CSV instead of columns
+    #[arg(long)]
+    csv: bool,
+
+    #[command(flatten)]
+    filter: TransactionFilter,
+}
+
+#[derive(Debug, Args)]
+struct OfxArgs {
+    /// path to your BAI2 file, or an s3:// or sftp:// URL when those features
+    /// are enabled
+    path: PathBuf,
+
+    #[command(flatten)]
+    filter: TransactionFilter,
+}
+
+#[derive(Debug, Args)]
+struct StatsArgs {
+    /// path to your BAI2 file, or an s3:// or sftp:// URL when those features
+    /// are enabled
+    path: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct ReportArgs {
+    /// path to your BAI2 file, or an s3:// or sftp:// URL when those features
+    /// are enabled
     path: PathBuf,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[derive(Debug, Args)]
+struct ConvertArgs {
+    /// paths to your BAI2 files, or s3:// or sftp:// URLs when those features
+    /// are enabled
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// the directory to write the JSON for each file to
+    #[arg(long, value_name = "DIR")]
+    out_dir: PathBuf,
+
+    /// where to write the manifest, `manifest.json` in the output directory by
+    /// default. Files listed in it as parsed from the same bytes are skipped.
+    #[arg(long, value_name = "PATH")]
+    manifest: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct ApplyCorrectionsArgs {
+    /// the directory holding the day's original and corrected files
+    dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct CodesArgs {
+    /// print the codes as columns, or as JSON for loading into another system
+    #[arg(long, value_enum, default_value_t = CodesFormat::Table)]
+    format: CodesFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CodesFormat {
+    Table,
+    Json,
+}
+
+fn main() {
     let env = Env::default()
         .filter_or("LOG_LEVEL", "warning")
         .write_style_or("LOG_STYLE", "always");
@@ -21,15 +241,376 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let cli = Cli::parse();
 
-    let content = fs::read_to_string(&cli.path)
-        .map_err(|_| format!("could not read file `{}`", &cli.path.display()))?;
+    let encoding = cli.encoding;
+    let options = if cli.strict {
+        ParseOptions::strict()
+    } else if cli.lenient {
+        ParseOptions::lenient()
+    } else {
+        ParseOptions::default()
+    };
+    let validation = cli
+        .validation
+        .into_iter()
+        .fold(options.validation, |config, (check, severity)| {
+            config.set(check, severity)
+        });
+    let options = ParseOptions {
+        allow_unknown_records: options.allow_unknown_records && !cli.reject_unknown_records,
+        max_errors: cli.max_errors,
+        validation,
+        ..options
+    };
+    let result = match cli.command.unwrap_or(Command::Parse(cli.parse)) {
+        Command::Parse(args) => parse(args, encoding, options),
+        Command::Availability(args) => availability(args, encoding, &options),
+        Command::Transactions(args) => transactions(args, encoding, &options),
+        Command::Ofx(args) => ofx(args, encoding, &options),
+        Command::Stats(args) => stats(args, encoding, &options),
+        Command::Report(args) => report(args, encoding, &options),
+        Command::Convert(args) => convert(args, encoding, &options),
+        Command::ApplyCorrections(args) => apply_corrections(args, encoding, &options),
+        Command::Codes(args) => codes(args),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn parse(
+    args: ParseArgs,
+    encoding: Encoding,
+    options: ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // clap makes sure the path is present, it's only optional so that it can be
+    // left out when a subcommand is given
+    let path = args.path.expect("path is required");
+    let bytes = cli::source::read(&path)?;
 
-    match Bai2File::new(content) {
-        Err(err) => println!("Failed to parse file: {}", err),
-        Ok(file) => {
-            println!("{}", serde_json::to_string_pretty(&file).unwrap());
+    let provenance = args
+        .provenance
+        .then(|| Provenance::new(Some(&cli::source::redacted(&path)), &bytes));
+
+    let options = ParseOptions {
+        continuous_stream: args.continuous_stream,
+        join_continued_text: args.join_text,
+        keep_raw_transactions: args.raw,
+        record_length: args.record_length,
+        sort_transactions: args.sort_transactions,
+        ..options
+    };
+
+    let mut file = parse_bytes(&bytes, encoding, &options)?;
+    file.provenance = provenance;
+    args.filter.apply(&mut file);
+
+    if let Some(url) = args.post_to {
+        cli::output::post(&file.to_json()?, &url, args.post_retries)?;
+        return Ok(());
+    }
+
+    match args.check_against {
+        None => cli::output::print(&file.to_json_pretty()?, args.gzip)?,
+        Some(expected_path) => {
+            let expected = serde_json::from_str(&read_file(&expected_path)?)
+                .map_err(|e| format!("could not parse `{}`: {}", expected_path.display(), e))?;
+            let actual = serde_json::from_str(&file.to_json()?)?;
+            let differences = cli::check::diff(&expected, &actual);
+
+            if !differences.is_empty() {
+                println!("Output differs from `{}`:", expected_path.display());
+                for difference in differences {
+                    println!("  {}", difference);
+                }
+                process::exit(1);
+            }
         }
+    }
+
+    Ok(())
+}
+
+fn availability(
+    args: AvailabilityArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = parse_file(&args.path, encoding, options)?;
+
+    let schedule = if args.business_days {
+        AvailabilitySchedule::with_calendar(&file, &Weekends)
+    } else {
+        file.availability_schedule()
     };
+    println!("{}", serde_json::to_string_pretty(&schedule)?);
+
+    Ok(())
+}
+
+fn transactions(
+    args: TransactionsArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = parse_file(&args.path, encoding, options)?;
+    args.filter.apply(&mut file);
+
+    if args.csv {
+        print!("{}", file.to_csv());
+    } else {
+        print!("{}", cli::transactions::table(&file));
+    }
 
     Ok(())
 }
+
+fn ofx(
+    args: OfxArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = parse_file(&args.path, encoding, options)?;
+    args.filter.apply(&mut file);
+
+    print!("{}", file.to_ofx());
+
+    Ok(())
+}
+
+fn stats(
+    args: StatsArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = parse_file(&args.path, encoding, options)?;
+
+    println!("{}", serde_json::to_string_pretty(&file.stats)?);
+
+    Ok(())
+}
+
+fn report(
+    args: ReportArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = parse_file(&args.path, encoding, options)?;
+
+    print!("{}", cli::report::table(&file, &options.currency_exponents));
+
+    Ok(())
+}
+
+fn convert(
+    args: ConvertArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(&args.out_dir)
+        .map_err(|_| format!("could not create `{}`", args.out_dir.display()))?;
+    let manifest_path = args
+        .manifest
+        .unwrap_or_else(|| args.out_dir.join("manifest.json"));
+    let previous = Manifest::load(&manifest_path)?;
+
+    let mut manifest = Manifest::default();
+    let (mut unchanged, mut failed) = (0, 0);
+    let total = args.paths.len();
+    for path in args.paths {
+        let bytes = match cli::source::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                failed += 1;
+                manifest.files.push(ManifestEntry {
+                    error: Some(err.to_string()),
+                    hash: None,
+                    input: path,
+                    output: None,
+                    status: Status::Failed,
+                    warnings: 0,
+                });
+                continue;
+            }
+        };
+        let hash = raw_hash(&bytes);
+
+        if let Some(entry) = previous.unchanged(&path, &hash) {
+            unchanged += 1;
+            manifest.files.push(entry.clone());
+            continue;
+        }
+
+        // A file that can't be written is recorded as failed like one that
+        // can't be parsed, so that the manifest still covers the rest
+        let result = encoding
+            .decode(&bytes)
+            .and_then(|content| Bai2File::new_with_options(content, options))
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                let output = output_path(&args.out_dir, &path, |output| {
+                    manifest.has_output(output) || previous.has_other_output(output, &path)
+                });
+                let json = file.to_json_pretty().map_err(|err| err.to_string())?;
+                fs::write(&output, json + "\n")
+                    .map_err(|err| format!("could not write `{}`: {}", output.display(), err))?;
+                Ok((output, file.validation_failures.len()))
+            });
+        let entry = match result {
+            Err(error) => {
+                failed += 1;
+                ManifestEntry {
+                    error: Some(error),
+                    hash: Some(hash),
+                    input: path,
+                    output: None,
+                    status: Status::Failed,
+                    warnings: 0,
+                }
+            }
+            Ok((output, warnings)) => ManifestEntry {
+                error: None,
+                hash: Some(hash),
+                input: path,
+                output: Some(output),
+                status: Status::Parsed,
+                warnings,
+            },
+        };
+        manifest.files.push(entry);
+    }
+    manifest.keep_others(previous);
+    manifest.save(&manifest_path)?;
+
+    println!(
+        "Converted {} files, {} failed, {} unchanged",
+        total - unchanged - failed,
+        failed,
+        unchanged
+    );
+    if failed > 0 {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Where to write the JSON for an input in the output directory: its name with
+/// a `.json` extension, or, when another input already has that output, its
+/// full name with `.json` added, and then a number after it.
+fn output_path(out_dir: &Path, input: &Path, taken: impl Fn(&Path) -> bool) -> PathBuf {
+    let name = input.file_name().unwrap_or(input.as_os_str());
+    let stem = Path::new(name)
+        .file_stem()
+        .unwrap_or(name)
+        .to_string_lossy();
+    let mut candidates = [
+        format!("{}.json", stem),
+        format!("{}.json", name.to_string_lossy()),
+    ]
+    .into_iter()
+    .chain((2..).map(|n| format!("{}-{}.json", stem, n)))
+    .map(|candidate| out_dir.join(candidate));
+
+    candidates
+        .find(|candidate| !taken(candidate))
+        .expect("there's always an unused name")
+}
+
+fn apply_corrections(
+    args: ApplyCorrectionsArgs,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(&args.dir)
+        .map_err(|_| format!("could not read directory `{}`", args.dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        let bytes = cli::source::read(&path)?;
+        // Other files kept with the day's files, like a README or a
+        // manifest, are left out rather than stopping the replay
+        let content = match encoding.decode(&bytes) {
+            Ok(content) if starts_with_file_header(&content) => content,
+            _ => {
+                eprintln!("Skipping `{}`, which isn't a BAI2 file", path.display());
+                continue;
+            }
+        };
+        let file = Bai2File::new_with_options(content, options)
+            .map_err(|err| format!("Failed to parse `{}`: {}", path.display(), err))?;
+        files.push(file);
+    }
+
+    // Files created at the same time, or without a creation time, stay in
+    // the order of their names
+    files.sort_by_key(|file| (file.creation_date, file.creation_time));
+    let mut day = DayView::new();
+    for file in files {
+        day.apply(file);
+    }
+    println!("{}", serde_json::to_string_pretty(&day)?);
+
+    Ok(())
+}
+
+/// Whether the content begins with a file header, after any byte order mark
+/// and blank lines, as a BAI2 file does.
+fn starts_with_file_header(content: &str) -> bool {
+    content
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .starts_with("01,")
+}
+
+fn codes(args: CodesArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let codes = code_table();
+    match args.format {
+        CodesFormat::Table => print!("{}", cli::codes::table(&codes)),
+        CodesFormat::Json => println!("{}", serde_json::to_string_pretty(&codes)?),
+    }
+
+    Ok(())
+}
+
+/// Read and parse a file, failing with the reason when it can't be parsed.
+fn parse_file(
+    path: &Path,
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<Bai2File, Box<dyn std::error::Error>> {
+    let bytes = cli::source::read(path)?;
+    Ok(parse_bytes(&bytes, encoding, options)?)
+}
+
+fn parse_bytes(
+    bytes: &[u8],
+    encoding: Encoding,
+    options: &ParseOptions,
+) -> Result<Bai2File, String> {
+    encoding
+        .decode(bytes)
+        .and_then(|content| Bai2File::new_with_options(content, options))
+        .map_err(|err| format!("Failed to parse file: {}", err))
+}
+
+fn parse_validation(value: &str) -> Result<(ValidationCheck, Severity), String> {
+    let (check, severity) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected CHECK=LEVEL, found `{}`", value))?;
+
+    Ok((check.parse()?, severity.parse()?))
+}
+
+fn read_file(path: &PathBuf) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|_| format!("could not read file `{}`", path.display()))
+}
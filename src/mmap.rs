@@ -0,0 +1,45 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
+use crate::scanner::Scanner;
+use crate::Bai2File;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Parse the file at the path by mapping it into memory and scanning it one
+/// line at a time, so that the whole file is never read into a string of its
+/// own. Each line is still copied into the scanned records, so the content is
+/// held in memory once rather than not at all. Each line must be valid UTF-8.
+pub(crate) fn parse(path: &Path, options: &ParseOptions) -> Result<Bai2File, Bai2Error> {
+    let open_failed = |e: std::io::Error| Bai2Error::OpenFailed {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+    let file = File::open(path).map_err(open_failed)?;
+    // Safety: the mapping is only read while parsing, and the caller is told
+    // not to change the file until parsing returns.
+    let mapping = unsafe { Mmap::map(&file) }.map_err(open_failed)?;
+
+    let mut scanner = Scanner::new(options);
+    let mut rest = mapping.strip_prefix(UTF8_BOM).unwrap_or(&mapping);
+    while !rest.is_empty() {
+        let (line, next) = match rest.iter().position(|&b| b == b'\n') {
+            Some(end) => (&rest[..end], &rest[end + 1..]),
+            None => (rest, &[][..]),
+        };
+        rest = next;
+
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let line = std::str::from_utf8(line).map_err(|_| Bai2Error::ReadFailed {
+            line: scanner.line_number() + 1,
+            message: "stream did not contain valid UTF-8".to_string(),
+        })?;
+        scanner.push_line(line)?;
+    }
+    scanner.close()?;
+
+    Bai2File::from_scan(scanner.tree(), scanner.skipped_records(), options)
+}
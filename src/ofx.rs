@@ -0,0 +1,225 @@
+use chrono::NaiveDate;
+
+use crate::file::account::{Account, AmountSubtype, AmountType};
+use crate::file::category::Category;
+use crate::file::direction::Direction;
+use crate::file::group::Group;
+use crate::file::time::Time;
+use crate::file::transaction::Transaction;
+use crate::Bai2File;
+
+/// Write the accounts and transactions of a file out as an OFX 2.2 bank
+/// statement response, with one statement per account.
+///
+/// BAI2 doesn't carry everything OFX asks for, so the originator of each group
+/// is used as the bank ID, every account is a checking account, and
+/// transactions without a bank reference number get an ID made from the
+/// sequence numbers of their account and transaction.
+pub(crate) fn write(file: &Bai2File) -> String {
+    let mut writer = Writer {
+        depth: 0,
+        lines: Vec::new(),
+    };
+    writer.write_file(file);
+
+    let mut output = writer.lines.join("\n");
+    output.push('\n');
+    output
+}
+
+#[derive(Debug)]
+struct Writer {
+    depth: usize,
+    lines: Vec<String>,
+}
+
+impl Writer {
+    fn line(&mut self, line: String) {
+        self.lines
+            .push(format!("{}{}", "  ".repeat(self.depth), line));
+    }
+
+    fn open(&mut self, tag: &str) {
+        self.line(format!("<{}>", tag));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.line(format!("</{}>", tag));
+    }
+
+    fn element(&mut self, tag: &str, value: &str) {
+        self.line(format!("<{}>{}</{}>", tag, escape(value), tag));
+    }
+
+    fn status(&mut self) {
+        self.open("STATUS");
+        self.element("CODE", "0");
+        self.element("SEVERITY", "INFO");
+        self.close("STATUS");
+    }
+
+    fn write_file(&mut self, file: &Bai2File) {
+        self.lines
+            .push(r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#.to_string());
+        self.lines.push(
+            r#"<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>"#
+                .to_string(),
+        );
+        self.open("OFX");
+
+        self.open("SIGNONMSGSRSV1");
+        self.open("SONRS");
+        self.status();
+        self.element(
+            "DTSERVER",
+            &format_date_time(file.creation_date, file.creation_time),
+        );
+        self.element("LANGUAGE", "ENG");
+        self.close("SONRS");
+        self.close("SIGNONMSGSRSV1");
+
+        self.open("BANKMSGSRSV1");
+        for group in &file.groups {
+            for account in &group.accounts {
+                self.write_statement(group, account);
+            }
+        }
+        self.close("BANKMSGSRSV1");
+
+        self.close("OFX");
+    }
+
+    fn write_statement(&mut self, group: &Group, account: &Account) {
+        let currency_code = account.currency_code.code();
+        let as_of = format_date_time(group.as_of_date, group.as_of_time);
+
+        self.open("STMTTRNRS");
+        self.element("TRNUID", &account.sequence_number.to_string());
+        self.status();
+        self.open("STMTRS");
+        self.element("CURDEF", currency_code);
+
+        self.open("BANKACCTFROM");
+        self.element("BANKID", &group.originator);
+        self.element("ACCTID", &account.customer_account_number);
+        self.element("ACCTTYPE", "CHECKING");
+        self.close("BANKACCTFROM");
+
+        self.open("BANKTRANLIST");
+        self.element("DTSTART", &format_date_time(group.as_of_date, None));
+        self.element("DTEND", &as_of);
+        for transaction in &account.transactions {
+            self.write_transaction(account, transaction);
+        }
+        self.close("BANKTRANLIST");
+
+        // OFX requires a ledger balance, so it's zero when the account summary
+        // doesn't report one
+        let ledger = balance(account, AmountSubtype::ClosingLedger)
+            .or_else(|| balance(account, AmountSubtype::CurrentLedger))
+            .unwrap_or_else(|| account.currency_code.to_decimal(0));
+        self.open("LEDGERBAL");
+        self.element("BALAMT", &ledger);
+        self.element("DTASOF", &as_of);
+        self.close("LEDGERBAL");
+
+        let available = balance(account, AmountSubtype::ClosingAvailable)
+            .or_else(|| balance(account, AmountSubtype::CurrentAvailable));
+        if let Some(available) = available {
+            self.open("AVAILBAL");
+            self.element("BALAMT", &available);
+            self.element("DTASOF", &as_of);
+            self.close("AVAILBAL");
+        }
+
+        self.close("STMTRS");
+        self.close("STMTTRNRS");
+    }
+
+    fn write_transaction(&mut self, account: &Account, transaction: &Transaction) {
+        let direction = transaction.transaction_type.direction();
+        let paid_out = direction == Direction::Debit && transaction.amount.unwrap_or(0) > 0;
+        // The decimal amount was scaled with the exponents the file was
+        // parsed with
+        let decimal_amount = match (&transaction.decimal_amount, direction) {
+            (Some(amount), Direction::Debit) => format!("-{}", amount),
+            (Some(amount), _) => amount.clone(),
+            (None, _) => transaction.currency_code.to_decimal(0),
+        };
+        let transaction_type = match direction {
+            Direction::Credit => "CREDIT",
+            Direction::Debit => "DEBIT",
+            Direction::Unknown => "OTHER",
+        };
+        let transaction_type = match transaction.category() {
+            Some(Category::Check) if paid_out => "CHECK",
+            Some(Category::Fee) => "FEE",
+            Some(Category::Interest) => "INT",
+            Some(Category::Transfer) => "XFER",
+            _ => transaction_type,
+        };
+        let id = if transaction.bank_reference_number.is_empty() {
+            format!(
+                "{}-{}",
+                account.sequence_number, transaction.sequence_number
+            )
+        } else {
+            transaction.bank_reference_number.clone()
+        };
+
+        self.open("STMTTRN");
+        self.element("TRNTYPE", transaction_type);
+        self.element(
+            "DTPOSTED",
+            &format_date_time(transaction.effective_date(), None),
+        );
+        self.element("TRNAMT", &decimal_amount);
+        self.element("FITID", &id);
+        if !transaction.customer_reference_number.is_empty() {
+            if transaction_type == "CHECK" {
+                self.element("CHECKNUM", &transaction.customer_reference_number);
+            } else {
+                self.element("REFNUM", &transaction.customer_reference_number);
+            }
+        }
+        if !transaction.text.is_empty() {
+            self.element("MEMO", &transaction.text);
+        }
+        self.close("STMTTRN");
+    }
+}
+
+/// The decimal amount of the first status summary of the given kind in the
+/// account.
+fn balance(account: &Account, subtype: AmountSubtype) -> Option<String> {
+    account
+        .amounts
+        .iter()
+        .find_map(|amount| match &amount.amount_type {
+            AmountType::Status(_, s) if *s == subtype => amount.decimal_amount.clone(),
+            _ => None,
+        })
+}
+
+/// OFX dates are written as `YYYYMMDD`, followed by `HHMMSS` when the time is
+/// known. The end of the day is written as the last second of it.
+fn format_date_time(date: Option<NaiveDate>, time: Option<Time>) -> String {
+    let Some(date) = date else {
+        return String::new();
+    };
+
+    match time {
+        None => date.format("%Y%m%d").to_string(),
+        Some(Time::EndOfDay | Time::Raw(_)) => format!("{}235959", date.format("%Y%m%d")),
+        Some(Time::At(time)) => format!("{}{}", date.format("%Y%m%d"), time.format("%H%M%S")),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,208 @@
+use crate::file::currency::CurrencyExponents;
+use crate::file::custom_codes::CustomCodes;
+use crate::file::version::Version;
+use crate::validation::{Severity, ValidationConfig};
+
+/// Options that control how a file is parsed.
+///
+/// The defaults follow the spec except for skipping record codes that aren't
+/// recognized. `ParseOptions::strict()` and `ParseOptions::lenient()` turn all
+/// of the checks on or off at once for banks that bend the spec.
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// Close groups and accounts whose trailer is missing at the point where
+    /// the next record shows they must have ended, instead of returning an
+    /// error.
+    pub allow_missing_trailers: bool,
+
+    /// Treat missing fields at the end of a header, trailer, or transaction
+    /// detail as empty, instead of returning an error.
+    pub allow_short_records: bool,
+
+    /// Skip records with a record code that isn't recognized, instead of
+    /// returning an error. They're kept in `Bai2File::other_records`.
+    pub allow_unknown_records: bool,
+
+    /// Read the file as a continuous stream of records that each end with a
+    /// `/`, as some banks send them, instead of one record per line. Line
+    /// numbers in errors then count records rather than lines.
+    pub continuous_stream: bool,
+
+    /// The exponents used to turn amounts into decimals, e.g. for
+    /// `Transaction::decimal_amount`.
+    pub currency_exponents: CurrencyExponents,
+
+    /// The labels and directions a bank gives the type codes it defines
+    /// itself, from 900 to 999.
+    pub custom_codes: CustomCodes,
+
+    /// The currency used when neither an account nor its group specify one.
+    pub default_currency: String,
+
+    /// How to read the times 2400 and 9999 that banks send for the end of the
+    /// business day, and 0000 as well when `midnight_is_end_of_day` is set.
+    pub end_of_day: EndOfDayTime,
+
+    /// Join the text of a transaction that's continued onto a continuation
+    /// (88) record straight on to the text before it, for banks that wrap
+    /// text mid-word, rather than after a comma as the start of a new field.
+    pub join_continued_text: bool,
+
+    /// Keep the exact lines of every record on what was parsed from it: the
+    /// header and trailer of the file, of each group, and of each account,
+    /// and each transaction detail as with `keep_raw_transactions`.
+    pub keep_raw_records: bool,
+
+    /// Keep the exact lines of each transaction detail and its continuations
+    /// on the transaction, so they can be quoted without the original file.
+    pub keep_raw_transactions: bool,
+
+    /// The most errors to tolerate before giving up with
+    /// `Bai2Error::TooManyErrors`, counting records with unknown record codes,
+    /// accounts and groups left out with `skip_invalid_accounts` and
+    /// `skip_invalid_groups`, and validation failures kept as warnings.
+    /// There's no limit by default.
+    pub max_errors: Option<usize>,
+
+    /// Read a time of 0000 as the end of the business day, as some banks
+    /// send it, rather than as midnight at the start of the day.
+    pub midnight_is_end_of_day: bool,
+
+    /// Build groups and accounts on multiple threads once the file has been
+    /// scanned. This has no effect unless the `rayon` feature is enabled.
+    pub parallel: bool,
+
+    /// Keep how each record was laid out in the file, so that the records
+    /// that haven't changed are written back exactly as they were, down to
+    /// their spacing, empty fields, and continuations. A file that's written
+    /// without changes comes out byte for byte the same, apart from blank
+    /// lines, which aren't kept, unrecognized records after the file trailer,
+    /// which are written before it, and the line endings of files read a line
+    /// at a time, e.g. with `Bai2Reader`, which are written with `\n`. Files
+    /// read as fixed-length records or a continuous stream are written one
+    /// record per line.
+    pub preserve_formatting: bool,
+
+    /// Reassemble records from fixed-length physical records of this many
+    /// characters, as older transmissions send them, with records that are
+    /// longer wrapped onto the next physical record and shorter ones padded
+    /// with spaces. Line numbers in errors then count records rather than
+    /// lines.
+    pub record_length: Option<usize>,
+
+    /// Leave out accounts that can't be parsed, listing them with their error
+    /// in `Group::skipped_accounts`, instead of failing their whole group.
+    /// The group's control total isn't checked when an account has been left
+    /// out, and neither is the file's.
+    pub skip_invalid_accounts: bool,
+
+    /// Leave out groups that can't be parsed, listing them with their error
+    /// in `Bai2File::skipped_groups`, instead of failing the whole file. The
+    /// file's control total isn't checked when a group has been left out.
+    pub skip_invalid_groups: bool,
+
+    /// Sort each account's transactions chronologically rather than keeping
+    /// them in the order they appear in the file.
+    pub sort_transactions: bool,
+
+    /// Whether each check of a trailer's counts and control total is an
+    /// error, a warning, or ignored. All of them are warnings by default.
+    pub validation: ValidationConfig,
+
+    /// The version of the format to read files as, for files whose header
+    /// gives the wrong one. It's taken from each file's header by default.
+    pub version: Option<Version>,
+
+    /// Two-digit years from this one up are read as 19xx, and those below it
+    /// as 20xx. The default of 70 reads 69 as 2069 and 70 as 1970, as chrono
+    /// does.
+    pub year_pivot: u8,
+}
+
+impl ParseOptions {
+    /// Options that return an error for anything that doesn't follow the spec.
+    pub fn strict() -> ParseOptions {
+        ParseOptions {
+            allow_missing_trailers: false,
+            allow_short_records: false,
+            allow_unknown_records: false,
+            validation: ValidationConfig::all(Severity::Error),
+            ..ParseOptions::default()
+        }
+    }
+
+    /// Options that parse as much of a file as possible.
+    pub fn lenient() -> ParseOptions {
+        ParseOptions {
+            allow_missing_trailers: true,
+            allow_short_records: true,
+            allow_unknown_records: true,
+            skip_invalid_accounts: true,
+            skip_invalid_groups: true,
+            ..ParseOptions::default()
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            allow_missing_trailers: false,
+            allow_short_records: false,
+            allow_unknown_records: true,
+            continuous_stream: false,
+            currency_exponents: CurrencyExponents::default(),
+            custom_codes: CustomCodes::default(),
+            default_currency: "USD".to_string(),
+            end_of_day: EndOfDayTime::default(),
+            join_continued_text: false,
+            keep_raw_records: false,
+            keep_raw_transactions: false,
+            max_errors: None,
+            midnight_is_end_of_day: false,
+            parallel: false,
+            preserve_formatting: false,
+            record_length: None,
+            skip_invalid_accounts: false,
+            skip_invalid_groups: false,
+            sort_transactions: false,
+            validation: ValidationConfig::default(),
+            version: None,
+            year_pivot: 70,
+        }
+    }
+}
+
+/// What to make of the times that banks send for the end of the business day.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EndOfDayTime {
+    /// Read them as `Time::EndOfDay`.
+    #[default]
+    EndOfDay,
+    /// Read them as 23:59:59, for systems that need a time of day.
+    LastSecond,
+    /// Keep the code the bank sent as `Time::Raw`, to tell them apart.
+    Raw,
+}
+
+/// Options that control how a file is serialized to JSON.
+#[deprecated(note = "the text of a transaction is always written as one string")]
+#[derive(Clone, Debug, Default)]
+pub struct JsonOptions {
+    /// Write the text of each transaction as a single string. It always is
+    /// now, so this has no effect.
+    pub join_text: bool,
+}
+
+/// Options that control how a file is written.
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    /// Leave the currency off of accounts that use the same currency as their
+    /// group, as most banks do.
+    pub omit_redundant_currency: bool,
+
+    /// Pad every record with spaces to the file's physical record length, for
+    /// systems that expect fixed-length records. This has no effect when the
+    /// file doesn't have one.
+    pub pad_records: bool,
+}
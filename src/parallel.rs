@@ -0,0 +1,61 @@
+/// Apply `f` to each item along with its index, in parallel when `parallel` is
+/// set and the `rayon` feature is enabled. The results are in the same order as
+/// the items, and the error returned is the one for the earliest item that
+/// failed, just as when they're mapped one at a time.
+pub(crate) fn try_map<T, U, E, F>(items: &[T], parallel: bool, f: F) -> Result<Vec<U>, E>
+where
+    T: Sync,
+    U: Send,
+    E: Send,
+    F: Fn(usize, &T) -> Result<U, E> + Send + Sync,
+{
+    #[cfg(feature = "rayon")]
+    if parallel {
+        use rayon::prelude::*;
+
+        let results: Vec<Result<U, E>> = items
+            .par_iter()
+            .enumerate()
+            .map(|(i, item)| f(i, item))
+            .collect();
+        return results.into_iter().collect();
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    let _ = parallel;
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| f(i, item))
+        .collect()
+}
+
+/// Apply `f` to each item along with its index, like `try_map`, but keeping
+/// the result for every item rather than stopping at the first error.
+pub(crate) fn map<T, U, F>(items: &[T], parallel: bool, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(usize, &T) -> U + Send + Sync,
+{
+    #[cfg(feature = "rayon")]
+    if parallel {
+        use rayon::prelude::*;
+
+        return items
+            .par_iter()
+            .enumerate()
+            .map(|(i, item)| f(i, item))
+            .collect();
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    let _ = parallel;
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| f(i, item))
+        .collect()
+}
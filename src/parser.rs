@@ -0,0 +1,87 @@
+use crate::borrowed::Bai2FileRef;
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
+use crate::scanner::node::Tree;
+use crate::scanner::Scanner;
+use crate::Bai2File;
+
+/// The length of a record in most files, used to size the buffer for lines.
+const TYPICAL_LINE_LENGTH: usize = 80;
+
+/// Parses one file after another, reusing the buffers that hold the scanned
+/// records between them, so that services parsing many files don't allocate
+/// new ones for each file.
+///
+/// ```
+/// use bai2::{Bai2Parser, ParseOptions};
+///
+/// let mut parser = Bai2Parser::with_capacity(10_000, ParseOptions::default());
+/// # let files: Vec<String> = Vec::new();
+/// for content in &files {
+///     let file = parser.parse(content);
+///     // ...
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Bai2Parser {
+    options: ParseOptions,
+    scanner: Scanner,
+}
+
+impl Bai2Parser {
+    pub fn new(options: ParseOptions) -> Bai2Parser {
+        Bai2Parser::with_capacity(0, options)
+    }
+
+    /// A parser with room for files of up to `lines` lines before it needs to
+    /// grow its buffers. Buffers that grow for a larger file stay that size.
+    pub fn with_capacity(lines: usize, options: ParseOptions) -> Bai2Parser {
+        let tree = Tree::with_capacity(lines, lines * TYPICAL_LINE_LENGTH);
+        let scanner = Scanner::with_tree(&options, tree);
+
+        Bai2Parser { options, scanner }
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Parse a file. The parser is reset first, so it doesn't matter whether
+    /// the last file parsed successfully.
+    pub fn parse(&mut self, content: &str) -> Result<Bai2File, Bai2Error> {
+        self.scan(content)?;
+        let mut file = Bai2File::from_scan(
+            self.scanner.tree(),
+            self.scanner.skipped_records(),
+            &self.options,
+        )?;
+
+        if let Some(original) = &mut file.original {
+            original.set_line_endings(content);
+        }
+        Ok(file)
+    }
+
+    /// Parse a file into a view that borrows its text from the parser, so
+    /// that fields aren't copied. The view has to be dropped before the next
+    /// file is parsed.
+    pub fn parse_borrowed(&mut self, content: &str) -> Result<Bai2FileRef<'_>, Bai2Error> {
+        self.scan(content)?;
+        Bai2FileRef::from_tree(self.scanner.tree(), &self.options)
+    }
+
+    /// Forget the last file that was parsed, keeping the capacity of the
+    /// buffers. Parsing does this itself, but a service can call it to avoid
+    /// holding on to the contents of a file while it's idle.
+    pub fn reset(&mut self) {
+        self.scanner.reset();
+    }
+
+    fn scan(&mut self, content: &str) -> Result<(), Bai2Error> {
+        self.reset();
+        for line in content.lines() {
+            self.scanner.push_line(line)?;
+        }
+        self.scanner.close()
+    }
+}
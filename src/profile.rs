@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::error::Bai2Error;
+use crate::file::util::parse_string;
+use crate::options::ParseOptions;
+use crate::rules::CategoryRules;
+use crate::Bai2File;
+
+/// How to parse the files from one bank: the options that cover its quirks,
+/// and optionally the rules that categorize its transactions.
+#[derive(Debug, Default)]
+pub struct Profile {
+    category_rules: Option<CategoryRules>,
+    options: ParseOptions,
+}
+
+impl Profile {
+    pub fn new(options: ParseOptions) -> Profile {
+        Profile {
+            category_rules: None,
+            options,
+        }
+    }
+
+    /// Apply the rules to every transaction after the file is parsed.
+    pub fn category_rules(mut self, rules: CategoryRules) -> Profile {
+        self.category_rules = Some(rules);
+        self
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    pub fn parse(&self, content: String) -> Result<Bai2File, Bai2Error> {
+        let mut file = Bai2File::new_with_options(content, &self.options)?;
+        if let Some(rules) = &self.category_rules {
+            rules.apply(&mut file);
+        }
+
+        Ok(file)
+    }
+}
+
+/// Profiles for each bank a service receives files from, keyed by the sender
+/// identification in the file header. Files from senders without a profile
+/// use the default profile.
+///
+/// ```
+/// use bai2::{ParseOptions, Profile, Profiles, Severity, ValidationCheck, ValidationConfig};
+///
+/// let profiles = Profiles::default()
+///     .add("CANBANK", Profile::new(ParseOptions {
+///         default_currency: "CAD".to_string(),
+///         ..ParseOptions::default()
+///     }))
+///     .add("SLOPPYBANK", Profile::new(ParseOptions {
+///         validation: ValidationConfig::new()
+///             .set(ValidationCheck::FileRecordCount, Severity::Ignore),
+///         ..ParseOptions::lenient()
+///     }));
+/// ```
+#[derive(Debug, Default)]
+pub struct Profiles {
+    default: Profile,
+    profiles: HashMap<String, Profile>,
+}
+
+impl Profiles {
+    /// Profiles that use the given profile for senders that don't have one.
+    pub fn new(default: Profile) -> Profiles {
+        Profiles {
+            default,
+            profiles: HashMap::new(),
+        }
+    }
+
+    pub fn add(mut self, sender: &str, profile: Profile) -> Profiles {
+        self.profiles.insert(sender.to_string(), profile);
+        self
+    }
+
+    /// The profile for the sender, or the default profile when there isn't
+    /// one.
+    pub fn profile(&self, sender: &str) -> &Profile {
+        self.profiles.get(sender).unwrap_or(&self.default)
+    }
+
+    /// Parse the file with the profile of its sender. The sender is read from
+    /// the file header before parsing, and files that don't start with one
+    /// are parsed with the default profile.
+    pub fn parse(&self, content: String) -> Result<Bai2File, Bai2Error> {
+        let profile = match sender(&content) {
+            Some(sender) => self.profile(&sender),
+            None => &self.default,
+        };
+
+        profile.parse(content)
+    }
+}
+
+/// The sender identification from the file header on the first line, or from
+/// a continuation of it when the header was split before the sender.
+fn sender(content: &str) -> Option<String> {
+    let mut lines = content.lines().skip_while(|line| line.trim().is_empty());
+    let header = lines.next()?;
+    if header.split(',').next()?.trim() != "01" {
+        return None;
+    }
+
+    let continuations = lines.take_while(|line| line.starts_with("88,"));
+    let mut fields = Vec::new();
+    for (i, line) in std::iter::once(header).chain(continuations).enumerate() {
+        let mut line_fields: Vec<&str> = line.split(',').skip(if i == 0 { 0 } else { 1 }).collect();
+        // A line that's continued can end with the delimiter for its last field
+        if line.trim_end().ends_with(',') {
+            line_fields.pop();
+        }
+        fields.extend(line_fields);
+    }
+
+    fields.get(1).copied().map(parse_string)
+}
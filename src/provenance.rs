@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::hash::raw_hash;
+
+/// Where a parsed file came from, so that downstream records can be traced back
+/// to a specific transmission.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Provenance {
+    pub crate_version: String,
+    pub filename: Option<String>,
+    pub parsed_at: DateTime<Utc>,
+    pub sha256: String,
+}
+
+impl Provenance {
+    pub fn new(filename: Option<&str>, raw: &[u8]) -> Provenance {
+        Provenance {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            filename: filename.map(str::to_string),
+            parsed_at: Utc::now(),
+            sha256: raw_hash(raw),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, Lines};
+
+use crate::aggregate::Aggregator;
+use crate::error::Bai2Error;
+use crate::file::account::Account;
+use crate::file::group::Group;
+use crate::file::version;
+use crate::options::ParseOptions;
+use crate::scanner::records::RecordSplitter;
+use crate::scanner::Scanner;
+
+/// Reads a file one line at a time, yielding each account as soon as its
+/// trailer has been read so that memory use stays bounded by the size of the
+/// largest account rather than the size of the file.
+///
+/// The accounts are owned, so each transaction's text is put together as it's
+/// read. To put it together only when it's asked for, parse the file into a
+/// `Bai2FileRef` instead.
+///
+/// Iteration stops after the first error.
+#[derive(Debug)]
+pub struct Bai2Reader<R> {
+    accounts_read: usize,
+    done: bool,
+    lines: Lines<R>,
+    options: ParseOptions,
+    records: VecDeque<String>,
+    scanner: Scanner,
+    splitter: RecordSplitter,
+}
+
+impl<R: BufRead> Bai2Reader<R> {
+    pub fn new(reader: R) -> Bai2Reader<R> {
+        Bai2Reader::with_options(reader, ParseOptions::default())
+    }
+
+    pub fn with_options(reader: R, options: ParseOptions) -> Bai2Reader<R> {
+        let scanner = Scanner::new(&options);
+        let splitter = RecordSplitter::new(&options);
+
+        Bai2Reader {
+            accounts_read: 0,
+            done: false,
+            lines: reader.lines(),
+            options,
+            records: VecDeque::new(),
+            scanner,
+            splitter,
+        }
+    }
+
+    /// Read the rest of the file, passing each account to the aggregator as
+    /// soon as it's been read rather than keeping it.
+    pub fn aggregate(self, aggregator: &mut impl Aggregator) -> Result<(), Bai2Error> {
+        for account in self {
+            aggregator.account(&account?);
+        }
+
+        Ok(())
+    }
+
+    fn next_account(&mut self) -> Result<Option<Account>, Bai2Error> {
+        while let Some(record) = self.next_record()? {
+            self.scanner.push_record(&record)?;
+
+            let Some(tree) = self.scanner.take_account() else {
+                continue;
+            };
+            if self.options.version.is_none() {
+                let options = version::with_version(&self.options, &self.scanner.tree().root());
+                self.options.version = options.version;
+            }
+            let group = self
+                .scanner
+                .current_group()
+                .expect("accounts are only taken from a group");
+            let (group_currency, as_of_date) = Group::account_context(&group, &self.options)?;
+
+            self.accounts_read += 1;
+            let account = Account::from_node(
+                &tree.root(),
+                self.accounts_read,
+                group_currency.as_deref(),
+                as_of_date,
+                &self.options,
+            )?;
+            return Ok(Some(account));
+        }
+
+        // Check that the file and every group in it were closed
+        let tree = self.scanner.finish()?;
+        let root = tree.root();
+        if root.sibling().is_none() && !self.options.allow_missing_trailers {
+            return Err(Bai2Error::FileTrailerMissing {
+                line: root.line_number(),
+            });
+        }
+
+        Ok(None)
+    }
+
+    /// Read the next record, which is the next line unless the file is a
+    /// continuous stream of records or has fixed-length records, where one
+    /// line can hold many accounts.
+    fn next_record(&mut self) -> Result<Option<String>, Bai2Error> {
+        while self.records.is_empty() {
+            let mut push = |record: &str| {
+                self.records.push_back(record.to_string());
+                Ok(())
+            };
+
+            let Some(line) = self.lines.next() else {
+                self.splitter.flush(&mut push)?;
+                break;
+            };
+            let line = line.map_err(|e| Bai2Error::ReadFailed {
+                line: self.scanner.line_number() + 1,
+                message: e.to_string(),
+            })?;
+            self.splitter.push(&line, push)?;
+        }
+
+        Ok(self.records.pop_front())
+    }
+}
+
+impl<R: BufRead> Iterator for Bai2Reader<R> {
+    type Item = Result<Account, Bai2Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_account() {
+            Ok(Some(account)) => Some(Ok(account)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use serde::Serialize;
+
+use crate::file::account::{Account, AmountType};
+use crate::file::direction::Direction;
+use crate::Bai2File;
+
+/// Summary codes that total more than a single family of detail codes, or that
+/// can't be checked against the details in a single file.
+const NON_FAMILY_CODES: [u16; 13] = [
+    100, 101, 105, 106, 107, 109, 400, 401, 403, 405, 406, 410, 412,
+];
+
+/// A summary amount on an account that doesn't match the detail transactions
+/// reported under it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Discrepancy {
+    pub customer_account_number: String,
+    pub detail_count: usize,
+    pub detail_total: i64,
+    pub item_count: Option<u16>,
+    pub summary_amount: Option<i64>,
+    pub summary_code: String,
+}
+
+/// Compare each credit and debit summary on the account to the sum of the
+/// transactions in its type code family.
+///
+/// A detail code belongs to the family of the closest summary code at or below
+/// it in the same range, so 451 and 452 are checked against 450. Total credits
+/// (100) and total debits (400) are checked against every credit or debit
+/// detail, plus any credits (105) or debits (406) not detailed.
+pub(crate) fn account(account: &Account) -> Vec<Discrepancy> {
+    let details: Vec<(u16, u64)> = account
+        .transactions()
+        .iter()
+        .map(|t| (t.transaction_type().code(), t.amount().unwrap_or(0)))
+        .collect();
+
+    let mut discrepancies = Vec::new();
+    for summary in account.amounts() {
+        if summary.amount_type().direction() == Direction::Unknown {
+            continue;
+        }
+        let code = summary.amount_type().code();
+
+        let (family, not_detailed): (Vec<u64>, Option<u16>) = match code {
+            100 => (in_range(&details, 100..=399), Some(105)),
+            400 => (in_range(&details, 400..=699), Some(406)),
+            code if NON_FAMILY_CODES.contains(&code) => continue,
+            code => (
+                details
+                    .iter()
+                    .filter(|(d, _)| family_head(*d) == Some(code))
+                    .map(|(_, amount)| *amount)
+                    .collect(),
+                None,
+            ),
+        };
+
+        let detail_count = family.len();
+        // A total too large to hold can't be compared, so it's skipped
+        let not_detailed_total = not_detailed
+            .and_then(|c| summary_amount(account, c))
+            .unwrap_or(0);
+        let Some(detail_total) = family
+            .iter()
+            .try_fold(not_detailed_total, |total, &amount| {
+                total.checked_add(i64::try_from(amount).ok()?)
+            })
+        else {
+            continue;
+        };
+
+        let amount_matches = summary.amount().unwrap_or(0) == detail_total;
+        let count_matches = match summary.item_count() {
+            Some(count) if not_detailed.is_none() => count as usize == detail_count,
+            _ => true,
+        };
+
+        if !amount_matches || !count_matches {
+            discrepancies.push(Discrepancy {
+                customer_account_number: account.customer_account_number().to_string(),
+                detail_count,
+                detail_total,
+                item_count: summary.item_count(),
+                summary_amount: summary.amount(),
+                summary_code: format!("{:03}", code),
+            });
+        }
+    }
+
+    discrepancies
+}
+
+pub(crate) fn file(file: &Bai2File) -> Vec<Discrepancy> {
+    file.groups
+        .iter()
+        .flat_map(|g| g.accounts())
+        .flat_map(account)
+        .collect()
+}
+
+fn in_range(details: &[(u16, u64)], range: std::ops::RangeInclusive<u16>) -> Vec<u64> {
+    details
+        .iter()
+        .filter(|(code, _)| range.contains(code))
+        .map(|(_, amount)| *amount)
+        .collect()
+}
+
+/// Find the summary code that a detail code is totaled under, if any.
+fn family_head(detail: u16) -> Option<u16> {
+    let floor = match detail {
+        101..=399 => 100,
+        401..=699 => 400,
+        701..=799 => 700,
+        _ => return None,
+    };
+
+    (floor..=detail)
+        .rev()
+        .filter(|c| !NON_FAMILY_CODES.contains(c))
+        .find(|&c| AmountType::from_code(c).direction() != Direction::Unknown)
+}
+
+fn summary_amount(account: &Account, code: u16) -> Option<i64> {
+    account.summary(code).and_then(|a| a.amount())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Bai2File;
+
+    #[test]
+    fn detail_totals_too_large_to_hold_are_skipped() {
+        let content = "01,A,B,200331,2300,1,,,2/
+02,,B,1,200331,2300,,/
+03,1,USD,100,1,2,/
+16,175,9223372036854775807,,,/
+16,175,1,,,/
+49,1,5/
+98,1,1,7/
+99,1,1,9/";
+
+        let file = Bai2File::new(content.to_string()).unwrap();
+
+        assert!(file.reconcile().is_empty());
+    }
+}
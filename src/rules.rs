@@ -0,0 +1,102 @@
+use regex::Regex;
+use std::ops::RangeInclusive;
+
+use crate::file::transaction::Transaction;
+use crate::Bai2File;
+
+/// A rule that assigns a custom category to the transactions it matches.
+///
+/// A transaction matches when every condition set on the rule matches. A rule
+/// without any conditions matches every transaction.
+#[derive(Debug)]
+pub struct CategoryRule {
+    amount: Option<RangeInclusive<u64>>,
+    category: String,
+    codes: Vec<String>,
+    text: Option<Regex>,
+}
+
+impl CategoryRule {
+    pub fn new(category: &str) -> CategoryRule {
+        CategoryRule {
+            amount: None,
+            category: category.to_string(),
+            codes: Vec::new(),
+            text: None,
+        }
+    }
+
+    /// Only match transactions with one of the given type codes.
+    pub fn codes(mut self, codes: &[&str]) -> CategoryRule {
+        self.codes = codes.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Only match transactions whose text matches the pattern.
+    pub fn text(mut self, pattern: &str) -> Result<CategoryRule, regex::Error> {
+        self.text = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Only match transactions with an amount in the given range.
+    pub fn amount(mut self, range: RangeInclusive<u64>) -> CategoryRule {
+        self.amount = Some(range);
+        self
+    }
+
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        if !self.codes.is_empty() {
+            let code = transaction.transaction_type().code();
+            if !self.codes.iter().any(|c| c.parse() == Ok(code)) {
+                return false;
+            }
+        }
+
+        if let Some(range) = &self.amount {
+            match transaction.amount() {
+                Some(amount) if range.contains(&amount) => (),
+                _ => return false,
+            }
+        }
+
+        if let Some(pattern) = &self.text {
+            if !pattern.is_match(transaction.text()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// An ordered set of category rules, where the first matching rule wins.
+#[derive(Debug, Default)]
+pub struct CategoryRules {
+    rules: Vec<CategoryRule>,
+}
+
+impl CategoryRules {
+    pub fn new() -> CategoryRules {
+        CategoryRules::default()
+    }
+
+    pub fn push(&mut self, rule: CategoryRule) {
+        self.rules.push(rule);
+    }
+
+    pub fn categorize(&self, transaction: &Transaction) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|r| r.matches(transaction))
+            .map(|r| r.category.as_str())
+    }
+
+    /// Attach the custom category of the first matching rule to each
+    /// transaction in the file.
+    pub fn apply(&self, file: &mut Bai2File) {
+        for transaction in file.transactions_mut() {
+            let category = self.categorize(transaction).map(str::to_string);
+            transaction.set_custom_category(category);
+        }
+    }
+}
@@ -1,155 +1,286 @@
-use self::node::{Node, NodeType};
-use std::str::Lines;
+use self::node::{Node, NodeType, Tree};
+use self::records::RecordSplitter;
+use crate::error::Bai2Error;
+use crate::options::ParseOptions;
 
 use log::debug;
 
 pub mod node;
+pub(crate) mod records;
 
 #[derive(Debug)]
-pub struct Scanner<'a> {
-    lines: Lines<'a>,
-    stack: Vec<Node>,
+pub struct Scanner {
+    allow_missing_trailers: bool,
+    allow_unknown_records: bool,
+    /// The record that a continuation on the next line would continue.
+    last_record: Option<usize>,
+    line_number: usize,
+    max_errors: Option<usize>,
+    skipped_records: usize,
+    splitter: RecordSplitter,
+    stack: Vec<usize>,
+    tree: Tree,
 }
 
-impl<'a> Scanner<'a> {
-    pub fn new(content: &'a str) -> Scanner<'a> {
-        let lines = content.lines();
+impl Scanner {
+    pub fn new(options: &ParseOptions) -> Scanner {
+        Scanner::with_tree(options, Tree::default())
+    }
 
+    /// A scanner that scans into the given tree, so that its capacity can be
+    /// reused.
+    pub fn with_tree(options: &ParseOptions, tree: Tree) -> Scanner {
         Scanner {
-            lines,
+            allow_missing_trailers: options.allow_missing_trailers,
+            allow_unknown_records: options.allow_unknown_records,
+            last_record: None,
+            line_number: 0,
+            max_errors: options.max_errors,
+            skipped_records: 0,
+            splitter: RecordSplitter::new(options),
             stack: Vec::new(),
+            tree,
         }
     }
 
-    pub fn scan(&mut self) -> Result<Node, &'static str> {
+    /// Forget everything that was scanned so that another file can be scanned,
+    /// keeping the capacity of the tree.
+    pub fn reset(&mut self) {
+        self.last_record = None;
+        self.line_number = 0;
+        self.skipped_records = 0;
+        self.splitter.clear();
+        self.stack.clear();
+        self.tree.clear();
+    }
+
+    pub fn scan(&mut self, content: &str) -> Result<Tree, Bai2Error> {
         debug!("Scanning file");
 
-        let mut file_header_line;
+        for line in content.lines() {
+            self.push_line(line)?;
+        }
+
+        self.finish()
+    }
 
-        // loop until a non-empty line is found
-        loop {
-            file_header_line = match self.lines.next() {
-                Some(line) => line,
-                None => {
-                    debug!("no lines found in file");
-                    return Err("no lines found in file");
-                }
-            };
+    /// Scan the next line of the file, which holds a single record unless the
+    /// file is a continuous stream of records or has fixed-length records.
+    pub fn push_line(&mut self, line: &str) -> Result<(), Bai2Error> {
+        let mut splitter = std::mem::take(&mut self.splitter);
+        let result = splitter.push(line, |record| self.push_record(record));
+        self.splitter = splitter;
 
-            if !file_header_line.is_empty() {
-                break;
-            }
+        result
+    }
+
+    /// Scan the next record of the file.
+    pub fn push_record(&mut self, line: &str) -> Result<(), Bai2Error> {
+        self.line_number += 1;
+
+        if !self.stack.is_empty() {
+            return self.handle_line(line);
+        }
+
+        // Skip any empty lines before the file header
+        if line.is_empty() {
+            return Ok(());
         }
 
         // The first line should always be the file header
-        if !file_header_line.get(0..2).eq(&Some("01")) {
+        if !line.get(0..2).eq(&Some("01")) {
             debug!("file header not found");
-            return Err("file header not found");
+            return Err(Bai2Error::FileHeaderMissing {
+                line: self.line_number,
+            });
         }
 
         debug!("file header found");
-        self.push_node(NodeType::FileHeader, file_header_line.to_string());
+        self.push_node(NodeType::FileHeader, line);
+        Ok(())
+    }
 
-        while let Some(line) = self.lines.next() {
-            match self.handle_line(line) {
-                Ok(_) => (),
-                Err(message) => {
-                    return Err(message);
-                }
-            }
-        }
+    /// Finish scanning, returning the tree of everything that was scanned with
+    /// the file header at its root.
+    pub fn finish(&mut self) -> Result<Tree, Bai2Error> {
+        self.close()?;
+        Ok(std::mem::take(&mut self.tree))
+    }
 
+    /// Finish scanning, leaving the tree of everything that was scanned in the
+    /// scanner. See `tree`.
+    pub fn close(&mut self) -> Result<(), Bai2Error> {
         debug!("Done scanning file");
 
-        return Ok(self.stack.pop().unwrap());
+        let mut splitter = std::mem::take(&mut self.splitter);
+        let result = splitter.flush(&mut |record| self.push_record(record));
+        self.splitter = splitter;
+        result?;
+
+        if self.stack.is_empty() {
+            debug!("no lines found in file");
+            return Err(Bai2Error::EmptyFile);
+        }
+
+        if self.is_current_type(NodeType::TransactionDetail) {
+            self.pop_node();
+        }
+
+        if self.allow_missing_trailers {
+            self.close_until(NodeType::FileHeader);
+        }
+
+        // Anything still open other than the file header was never closed by
+        // its trailer
+        let unclosed = self.tree.node(self.stack.pop().unwrap());
+        match unclosed.r#type() {
+            NodeType::FileHeader => Ok(()),
+            NodeType::AccountIdentifier => Err(Bai2Error::AccountTrailerMissing {
+                line: unclosed.line_number(),
+            }),
+            _ => Err(Bai2Error::GroupTrailerMissing {
+                line: unclosed.line_number(),
+            }),
+        }
     }
 
-    // Private
+    /// Everything scanned so far.
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
 
-    fn assert_current_type(&self, node_type: NodeType) -> Result<(), &'static str> {
-        match self.current_type() {
-            Some(current_type) => {
-                if current_type != node_type {
-                    return Err("unexpected node type");
-                }
-            }
-            None => return Err("no current node"),
+    /// The number of lines scanned so far.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// The number of records skipped so far because their record code wasn't
+    /// recognized.
+    pub fn skipped_records(&self) -> usize {
+        self.skipped_records
+    }
+
+    /// Remove the most recent account from the current group once its trailer
+    /// has been scanned, so that it can be handled without keeping the whole
+    /// file in memory.
+    /// The account is returned as a tree of its own, rooted at its account
+    /// identifier.
+    pub fn take_account(&mut self) -> Option<Tree> {
+        if !self.is_current_type(NodeType::GroupHeader) {
+            return None;
         }
-        Ok(())
+
+        let group = *self.stack.last()?;
+        let account = self.tree.pop_child(group)?;
+        self.last_record = None;
+        Some(self.tree.split_off(account))
+    }
+
+    /// The header of the group currently being scanned.
+    pub fn current_group(&self) -> Option<Node<'_>> {
+        self.stack
+            .iter()
+            .map(|&i| self.tree.node(i))
+            .find(|node| node.r#type() == NodeType::GroupHeader)
+    }
+
+    // Private
+
+    fn is_current_type(&self, node_type: NodeType) -> bool {
+        self.current_type() == Some(node_type)
     }
 
     fn current_type(&self) -> Option<NodeType> {
-        match self.stack.last() {
-            None => None,
-            Some(node) => Some(node.r#type),
-        }
+        self.stack.last().map(|&i| self.tree.node(i).r#type())
     }
 
-    fn handle_line(&mut self, line: &str) -> Result<(), &'static str> {
+    fn handle_line(&mut self, line: &str) -> Result<(), Bai2Error> {
         match line.get(0..2) {
             Some("02") => {
-                if let Err(_) = self.assert_current_type(NodeType::FileHeader) {
-                    return Err("file trailer found without file header");
+                self.close_until(NodeType::FileHeader);
+                if !self.is_current_type(NodeType::FileHeader) {
+                    return Err(Bai2Error::GroupHeaderWithoutFileHeader {
+                        line: self.line_number,
+                    });
                 }
 
                 debug!("group header found");
-                self.push_node(NodeType::GroupHeader, line.to_string());
+                self.push_node(NodeType::GroupHeader, line);
                 Ok(())
             }
             Some("03") => {
-                if let Err(_) = self.assert_current_type(NodeType::GroupHeader) {
-                    return Err("account identifier found without group header");
+                self.close_until(NodeType::GroupHeader);
+                if !self.is_current_type(NodeType::GroupHeader) {
+                    return Err(Bai2Error::AccountWithoutGroupHeader {
+                        line: self.line_number,
+                    });
                 }
 
                 debug!("account identifier found");
-                self.push_node(NodeType::AccountIdentifier, line.to_string());
+                self.push_node(NodeType::AccountIdentifier, line);
                 Ok(())
             }
             Some("16") => {
+                self.close_until(NodeType::AccountIdentifier);
                 match self.current_type() {
                     Some(NodeType::AccountIdentifier) => (),
                     Some(NodeType::TransactionDetail) => self.pop_node(),
-                    _ => return Err("transaction detail found without account identifier"),
+                    _ => {
+                        return Err(Bai2Error::TransactionWithoutAccount {
+                            line: self.line_number,
+                        })
+                    }
                 }
 
                 debug!("transaction found");
-                self.push_node(NodeType::TransactionDetail, line.to_string());
+                self.push_node(NodeType::TransactionDetail, line);
                 Ok(())
             }
             Some("49") => {
+                self.close_until(NodeType::AccountIdentifier);
                 match self.current_type() {
                     Some(NodeType::AccountIdentifier) => (),
                     Some(NodeType::TransactionDetail) => self.pop_node(),
-                    _ => return Err("account control found without account identifier"),
+                    _ => {
+                        return Err(Bai2Error::AccountTrailerWithoutAccount {
+                            line: self.line_number,
+                        })
+                    }
                 }
 
                 debug!("account control found");
-                self.put_sibling(NodeType::AccountTrailer, line.to_string());
+                self.put_sibling(NodeType::AccountTrailer, line);
                 self.pop_node();
                 Ok(())
             }
             Some("88") => {
                 debug!("continuation found");
-                self.push_continuation(line.to_string());
+                self.push_continuation(line);
                 Ok(())
             }
             Some("98") => {
-                if let Err(_) = self.assert_current_type(NodeType::GroupHeader) {
-                    return Err("group trailer found without group header");
+                self.close_until(NodeType::GroupHeader);
+                if !self.is_current_type(NodeType::GroupHeader) {
+                    return Err(Bai2Error::GroupTrailerWithoutGroupHeader {
+                        line: self.line_number,
+                    });
                 }
 
                 debug!("group trailer found");
-                self.put_sibling(NodeType::GroupTrailer, line.to_string());
+                self.put_sibling(NodeType::GroupTrailer, line);
                 self.pop_node();
                 Ok(())
             }
             Some("99") => {
-                if let Err(_) = self.assert_current_type(NodeType::FileHeader) {
-                    return Err("file trailer found without file header");
+                self.close_until(NodeType::FileHeader);
+                if !self.is_current_type(NodeType::FileHeader) {
+                    return Err(Bai2Error::FileTrailerWithoutFileHeader {
+                        line: self.line_number,
+                    });
                 }
 
                 debug!("file trailer found");
-                self.put_sibling(NodeType::FileTrailer, line.to_string());
+                self.put_sibling(NodeType::FileTrailer, line);
                 Ok(())
             }
             None => {
@@ -157,53 +288,85 @@ impl<'a> Scanner<'a> {
                 Ok(())
             }
             Some(record_type) => {
-                debug!("skipping unrecognized record type: {}", record_type);
+                if !self.allow_unknown_records {
+                    return Err(Bai2Error::UnknownRecordType {
+                        line: self.line_number,
+                        code: record_type.to_string(),
+                    });
+                }
+
+                self.skipped_records += 1;
+                if let Some(max) = self.max_errors.filter(|&max| self.skipped_records > max) {
+                    return Err(Bai2Error::TooManyErrors {
+                        line: self.line_number,
+                        max,
+                    });
+                }
+
+                debug!("keeping unrecognized record type: {}", record_type);
+                // Transaction details can't hold other records, so a record
+                // after one belongs to its account
+                let parent = *self
+                    .stack
+                    .iter()
+                    .rev()
+                    .find(|&&i| self.tree.node(i).r#type() != NodeType::TransactionDetail)
+                    .expect("the file header is always open");
+                let node = self
+                    .tree
+                    .push(NodeType::Unrecognized, line, self.line_number);
+                self.tree.push_other(parent, node);
+                self.last_record = Some(node);
                 Ok(())
             }
         }
     }
 
+    /// When missing trailers are allowed, close anything still open inside the
+    /// closest node of the given type, since the current record shows that it
+    /// must have ended.
+    fn close_until(&mut self, node_type: NodeType) {
+        let is_open = self
+            .stack
+            .iter()
+            .any(|&i| self.tree.node(i).r#type() == node_type);
+        if !self.allow_missing_trailers || !is_open {
+            return;
+        }
+
+        while !self.is_current_type(node_type) {
+            debug!("closing record without a trailer");
+            self.pop_node();
+        }
+    }
+
     fn pop_node(&mut self) {
         let child = self.stack.pop().unwrap();
-        let parent = self.stack.last_mut().unwrap();
-        parent.push_child(child);
-    }
-
-    fn push_continuation(&mut self, line: String) {
-        let current_node = self.stack.last_mut().unwrap();
-        let continuation = Node {
-            children: Vec::new(),
-            continuations: Vec::new(),
-            line,
-            sibling: Box::new(None),
-            r#type: NodeType::Continuation,
-        };
-        current_node.continuations.push(continuation);
-    }
-
-    fn push_node(&mut self, node_type: NodeType, line: String) {
-        let node = Node {
-            children: Vec::new(),
-            continuations: Vec::new(),
-            line,
-            sibling: Box::new(None),
-            r#type: node_type,
-        };
-
-        self.stack.push(node);
+        let parent = *self.stack.last().unwrap();
+        self.tree.push_child(parent, child);
     }
 
-    fn put_sibling(&mut self, node_type: NodeType, line: String) {
-        let current_node = self.stack.last_mut().unwrap();
+    /// Add a continuation to the record on the line before it, which may be a
+    /// trailer rather than the header at the top of the stack. When that
+    /// record has been taken or skipped, the header is used instead.
+    fn push_continuation(&mut self, line: &str) {
+        let current_node = self.last_record.unwrap_or(*self.stack.last().unwrap());
+        let continuation = self
+            .tree
+            .push(NodeType::Continuation, line, self.line_number);
+        self.tree.push_continuation(current_node, continuation);
+    }
 
-        let sibling = Node {
-            children: Vec::new(),
-            continuations: Vec::new(),
-            line,
-            sibling: Box::new(None),
-            r#type: node_type,
-        };
+    fn push_node(&mut self, node_type: NodeType, line: &str) {
+        let node = self.tree.push(node_type, line, self.line_number);
+        self.stack.push(node);
+        self.last_record = Some(node);
+    }
 
-        current_node.sibling = Box::new(Some(sibling));
+    fn put_sibling(&mut self, node_type: NodeType, line: &str) {
+        let current_node = *self.stack.last().unwrap();
+        let sibling = self.tree.push(node_type, line, self.line_number);
+        self.tree.set_sibling(current_node, sibling);
+        self.last_record = Some(sibling);
     }
 }
@@ -1,3 +1,6 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum NodeType {
     AccountIdentifier,
@@ -8,47 +11,301 @@ pub enum NodeType {
     GroupHeader,
     GroupTrailer,
     TransactionDetail,
+    /// A record with a code that isn't part of the format, e.g. a bank's own
+    /// extension, kept when unknown records are allowed.
+    Unrecognized,
+}
+
+/// The records of a scanned file, kept in a single arena and linked together
+/// by their index in it rather than each owning its children. The first record
+/// is the root. The lines of the records are kept together in one buffer, so
+/// that clearing the tree keeps its allocations for the next file.
+#[derive(Debug, Default)]
+pub struct Tree {
+    records: Vec<Record>,
+    text: String,
 }
 
 #[derive(Debug)]
-pub struct Node {
-    pub children: Vec<Node>,
-    pub continuations: Vec<Node>,
-    pub line: String,
-    pub sibling: Box<Option<Node>>,
-    pub r#type: NodeType,
+struct Record {
+    children: Vec<usize>,
+    continuations: Vec<usize>,
+    line: Range<usize>,
+    line_number: usize,
+    /// The unrecognized records found within this one, which are kept apart
+    /// from its children since they aren't part of the hierarchy.
+    others: Vec<usize>,
+    sibling: Option<usize>,
+    r#type: NodeType,
 }
 
-impl Node {
-    pub fn fields(&self) -> Vec<&str> {
-        let mut fields: Vec<&str> = self.line.split(",").collect();
+impl Tree {
+    /// An empty tree with room for `records` records and `text` bytes of
+    /// their lines.
+    pub fn with_capacity(records: usize, text: usize) -> Tree {
+        Tree {
+            records: Vec::with_capacity(records),
+            text: String::with_capacity(text),
+        }
+    }
 
-        for continuation in &self.continuations {
-            let continuation_fields = continuation.line.split(",").skip(1);
-            for field in continuation_fields {
-                fields.push(field);
-            }
+    /// Remove every record, keeping the capacity of the tree.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.text.clear();
+    }
+
+    pub fn root(&self) -> Node<'_> {
+        Node {
+            index: 0,
+            tree: self,
+        }
+    }
+
+    pub fn node(&self, index: usize) -> Node<'_> {
+        Node { index, tree: self }
+    }
+
+    /// Add a record to the arena without linking it to any other record,
+    /// returning its index.
+    pub fn push(&mut self, node_type: NodeType, line: &str, line_number: usize) -> usize {
+        let start = self.text.len();
+        self.text.push_str(line);
+        self.records.push(Record {
+            children: Vec::new(),
+            continuations: Vec::new(),
+            line: start..self.text.len(),
+            line_number,
+            others: Vec::new(),
+            sibling: None,
+            r#type: node_type,
+        });
+        self.records.len() - 1
+    }
+
+    pub fn push_child(&mut self, parent: usize, child: usize) {
+        self.records[parent].children.push(child);
+    }
+
+    pub fn push_continuation(&mut self, parent: usize, continuation: usize) {
+        self.records[parent].continuations.push(continuation);
+    }
+
+    pub fn set_sibling(&mut self, node: usize, sibling: usize) {
+        self.records[node].sibling = Some(sibling);
+    }
+
+    pub fn push_other(&mut self, parent: usize, other: usize) {
+        self.records[parent].others.push(other);
+    }
+
+    pub fn pop_child(&mut self, parent: usize) -> Option<usize> {
+        self.records[parent].children.pop()
+    }
+
+    /// Move the records from `index` onwards into a tree of their own, rooted
+    /// at the record that was at `index`. Every record after `index` must
+    /// belong beneath it.
+    pub fn split_off(&mut self, index: usize) -> Tree {
+        let rebase = |i: usize| i - index;
+        let start = self.records[index].line.start;
+        let text = self.text.split_off(start);
+        let records = self
+            .records
+            .split_off(index)
+            .into_iter()
+            .map(|record| Record {
+                children: record.children.into_iter().map(rebase).collect(),
+                continuations: record.continuations.into_iter().map(rebase).collect(),
+                others: record.others.into_iter().map(rebase).collect(),
+                line: record.line.start - start..record.line.end - start,
+                sibling: record.sibling.map(rebase),
+                ..record
+            })
+            .collect();
+
+        Tree { records, text }
+    }
+}
+
+/// A record in a `Tree`, along with its continuations, children, and trailer.
+#[derive(Clone, Copy, Debug)]
+pub struct Node<'a> {
+    index: usize,
+    tree: &'a Tree,
+}
+
+impl<'a> Node<'a> {
+    fn record(&self) -> &'a Record {
+        &self.tree.records[self.index]
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = Node<'a>> + '_ {
+        self.record().children.iter().map(|&i| self.tree.node(i))
+    }
+
+    pub fn continuations(&self) -> impl Iterator<Item = Node<'a>> + '_ {
+        self.record()
+            .continuations
+            .iter()
+            .map(|&i| self.tree.node(i))
+    }
+
+    /// The unrecognized records found within this one but not within any of
+    /// its children, in the order they were found.
+    pub fn others(&self) -> impl Iterator<Item = Node<'a>> + '_ {
+        self.record().others.iter().map(|&i| self.tree.node(i))
+    }
+
+    /// The trailer that closes this record, if it was found.
+    pub fn sibling(&self) -> Option<Node<'a>> {
+        self.record().sibling.map(|i| self.tree.node(i))
+    }
+
+    pub fn line(&self) -> &'a str {
+        &self.tree.text[self.record().line.clone()]
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.record().line_number
+    }
+
+    /// The record and its continuations as they appeared in the file, one
+    /// line each.
+    pub fn raw(&self) -> String {
+        std::iter::once(self.line())
+            .chain(self.continuations().map(|c| c.line()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The record followed by its trailer, each with their continuations, as
+    /// they appeared in the file. The records between them aren't included.
+    pub fn raw_with_trailer(&self) -> String {
+        match self.sibling() {
+            Some(trailer) => format!("{}\n{}", self.raw(), trailer.raw()),
+            None => self.raw(),
         }
+    }
 
-        fields
+    pub fn r#type(&self) -> NodeType {
+        self.record().r#type
     }
 
-    pub fn has_continuations(&self) -> bool {
-        !self.continuations.is_empty()
+    pub fn fields(&self) -> Vec<&'a str> {
+        self.line_fields()
+            .into_iter()
+            .flat_map(|(_, fields)| fields)
+            .collect()
     }
 
-    pub fn push_child(&mut self, node: Node) {
-        self.children.push(node);
+    /// Everything in the record from the field at `index` in `fields()` on, as
+    /// one piece of text, for a transaction's text, which runs to the end of
+    /// the record and can hold commas and slashes of its own. Only a `/`
+    /// ending a line is left out, as the end of the record. A continuation
+    /// starts a new field, so it's joined on after a comma unless the line
+    /// before ended with one, or straight on when `join_continued` is set for
+    /// text that was wrapped mid-word. It's only copied when the text is
+    /// continued.
+    pub fn text_from(&self, index: usize, join_continued: bool) -> Cow<'a, str> {
+        let lines = std::iter::once(*self).chain(self.continuations());
+        let mut remaining = index;
+        let mut text: Option<Cow<'a, str>> = None;
+
+        for (i, (node, (_, fields))) in lines.zip(self.line_fields()).enumerate() {
+            // A continuation's fields start after its record code
+            let fields_start = if i == 0 { 0 } else { 3 };
+            let line = node.line().trim_end();
+            let line = line.strip_suffix('/').unwrap_or(line);
+            match &mut text {
+                Some(text) => {
+                    let continued = line.get(fields_start..).unwrap_or("");
+                    if continued.is_empty() {
+                        continue;
+                    }
+                    let text = text.to_mut();
+                    if !join_continued && !text.is_empty() && !text.ends_with(',') {
+                        text.push(',');
+                    }
+                    text.push_str(continued);
+                }
+                None if remaining < fields.len() => {
+                    let start = fields_start
+                        + fields[..remaining]
+                            .iter()
+                            .map(|field| field.len() + 1)
+                            .sum::<usize>();
+                    text = Some(Cow::Borrowed(line.get(start..).unwrap_or("")));
+                }
+                None => remaining -= fields.len(),
+            }
+        }
+
+        text.unwrap_or(Cow::Borrowed(""))
+    }
+
+    /// The fields of the record, which must have at least `count` of them.
+    /// When `pad` is set, missing fields are filled in as empty instead. Errors
+    /// hold the position of the first missing field.
+    pub fn required_fields(&self, count: usize, pad: bool) -> Result<Vec<&'a str>, (usize, usize)> {
+        let mut fields = self.fields();
+        if fields.len() < count {
+            if !pad {
+                return Err(self.field_position(fields.len()));
+            }
+            fields.resize(count, "");
+        }
+
+        Ok(fields)
     }
 
-    pub fn push_continuation(&mut self, node: Node) {
-        self.continuations.push(node);
+    /// The line number and field number of the field at `index` in `fields()`,
+    /// counting the record code as field 1.
+    pub fn field_position(&self, index: usize) -> (usize, usize) {
+        (self.field_line(index), index + 1)
     }
 
-    pub fn sibling_fields(&self) -> Vec<&str> {
-        match &*self.sibling {
-            Some(sibling) => sibling.fields(),
-            None => Vec::new(),
+    /// The line number of the field at `index` in `fields()`. Fields past the
+    /// end of the record are reported on the record's last line.
+    pub fn field_line(&self, index: usize) -> usize {
+        let mut remaining = index;
+        let mut last_line_number = self.line_number();
+
+        for (line_number, fields) in self.line_fields() {
+            if remaining < fields.len() {
+                return line_number;
+            }
+            remaining -= fields.len();
+            last_line_number = line_number;
         }
+
+        last_line_number
+    }
+
+    fn line_fields(&self) -> Vec<(usize, Vec<&'a str>)> {
+        let lines = std::iter::once(*self)
+            .chain(self.continuations())
+            .enumerate();
+        let last_line = self.record().continuations.len();
+
+        lines
+            .map(|(i, node)| {
+                let line = node.line();
+                let mut line_fields: Vec<&str> = line.split(',').collect();
+
+                // The continuation's record code isn't part of the continued record
+                if i > 0 {
+                    line_fields.remove(0);
+                }
+
+                // A line that is continued can end with the delimiter for its last
+                // field, which shouldn't be treated as an extra empty field.
+                if i < last_line && line.trim_end().ends_with(',') {
+                    line_fields.pop();
+                }
+
+                (node.line_number(), line_fields)
+            })
+            .collect()
     }
 }
@@ -0,0 +1,243 @@
+use std::mem;
+
+use crate::options::ParseOptions;
+
+/// The record codes that can start a record.
+const RECORD_CODES: [&str; 8] = ["01", "02", "03", "16", "49", "88", "98", "99"];
+
+/// Turns the lines of a file into the records in them, for files that don't
+/// put each record on a line of its own. Records are passed on unchanged for
+/// files that do.
+///
+/// Without a record length in the options, the physical record length in the
+/// file header is used when the header is padded out to that length, since a
+/// file with variable-length records has nothing after the header's `/`.
+#[derive(Debug, Default)]
+pub(crate) struct RecordSplitter {
+    configured_length: Option<usize>,
+    continuous_stream: bool,
+    detect_record_length: bool,
+    pending: String,
+    record_length: Option<usize>,
+}
+
+impl RecordSplitter {
+    pub(crate) fn new(options: &ParseOptions) -> RecordSplitter {
+        let record_length = options.record_length.filter(|&length| length > 0);
+        RecordSplitter {
+            configured_length: record_length,
+            continuous_stream: options.continuous_stream,
+            detect_record_length: record_length.is_none(),
+            pending: String::new(),
+            record_length,
+        }
+    }
+
+    /// Pass each record that the line completes to `f`. With fixed-length
+    /// records, the last record of the line is held back until the next line
+    /// shows whether it's been wrapped onto it.
+    pub(crate) fn push<E>(
+        &mut self,
+        line: &str,
+        mut f: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if self.detect_record_length && !line.trim().is_empty() {
+            self.detect_record_length = false;
+            self.record_length = padded_header_length(line);
+        }
+
+        let Some(length) = self.record_length else {
+            return self.split(line, &mut f);
+        };
+
+        let mut rest = line;
+        while !rest.is_empty() {
+            let end = rest
+                .char_indices()
+                .nth(length)
+                .map_or(rest.len(), |(i, _)| i);
+            let (physical_record, next) = rest.split_at(end);
+            rest = next;
+
+            if self.starts_record(physical_record) {
+                self.flush(&mut f)?;
+            }
+            self.pending.push_str(physical_record);
+        }
+
+        Ok(())
+    }
+
+    /// Pass the record being held back, if there is one, to `f`.
+    pub(crate) fn flush<E>(&mut self, f: &mut impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let record = mem::take(&mut self.pending);
+        let result = self.split(record.trim_end(), f);
+        self.pending = record;
+        self.pending.clear();
+
+        result
+    }
+
+    /// Forget the last file, so that the next line is the start of a new one.
+    pub(crate) fn clear(&mut self) {
+        self.detect_record_length = self.configured_length.is_none();
+        self.pending.clear();
+        self.record_length = self.configured_length;
+    }
+
+    /// Whether a physical record starts a new record rather than continuing
+    /// the one before it, which it does when that one was ended with a `/` or
+    /// when it starts with a record code.
+    fn starts_record(&self, physical_record: &str) -> bool {
+        self.pending.trim_end().ends_with('/')
+            || physical_record.get(2..3) == Some(",")
+                && RECORD_CODES.contains(&&physical_record[..2])
+    }
+
+    fn split<E>(&self, line: &str, f: &mut impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+        if !self.continuous_stream {
+            return f(line);
+        }
+
+        for record in split_records(line) {
+            f(record)?;
+        }
+        Ok(())
+    }
+}
+
+/// The physical record length given by the file header at the start of the
+/// line, if the header is padded with spaces to that length.
+fn padded_header_length(line: &str) -> Option<usize> {
+    if !line.starts_with("01,") {
+        return None;
+    }
+
+    let length: usize = line
+        .split([',', '/'])
+        .nth(6)
+        .and_then(|field| field.trim().parse().ok())
+        .filter(|&length| length > 0)?;
+    let physical_record: String = line.chars().take(length).collect();
+    let (_, padding) = physical_record.split_once('/')?;
+
+    (physical_record.chars().count() == length && padding.trim().is_empty()).then_some(length)
+}
+
+/// Split a continuous stream of records into the records in it. Each record
+/// ends with a `/` that's followed by the record code of the next record, or
+/// by the end of the stream, so a `/` within a field doesn't end it.
+pub(crate) fn split_records(stream: &str) -> impl Iterator<Item = &str> {
+    let mut rest = stream.trim_start();
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = rest
+            .match_indices('/')
+            .map(|(i, _)| i + 1)
+            .find(|&end| {
+                let next = rest[end..].trim_start().as_bytes();
+                next.is_empty()
+                    || (next.len() >= 3
+                        && next[..2].iter().all(u8::is_ascii_digit)
+                        && next[2] == b',')
+            })
+            .unwrap_or(rest.len());
+        let (record, next) = rest.split_at(end);
+        rest = next.trim_start();
+
+        Some(record)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bai2File;
+
+    fn records(options: &ParseOptions, lines: &[&str]) -> Vec<String> {
+        let mut splitter = RecordSplitter::new(options);
+        let mut records = Vec::new();
+        let mut f = |record: &str| -> Result<(), ()> {
+            records.push(record.to_string());
+            Ok(())
+        };
+        for line in lines {
+            splitter.push(line, &mut f).unwrap();
+        }
+        splitter.flush(&mut f).unwrap();
+        records
+    }
+
+    #[test]
+    fn continuous_streams_split_only_where_a_record_code_follows() {
+        let options = ParseOptions {
+            continuous_stream: true,
+            ..ParseOptions::default()
+        };
+        let stream = "01,A,B,200331,2300,1,,,2/ 02,,B,1,200331,2300,,/16,175,100,0,,,PAID 03/31/2020/ 88,21/";
+
+        assert_eq!(
+            records(&options, &[stream]),
+            [
+                "01,A,B,200331,2300,1,,,2/",
+                "02,,B,1,200331,2300,,/",
+                "16,175,100,0,,,PAID 03/31/2020/",
+                "88,21/",
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_length_records_are_unpadded_and_unwrapped() {
+        let options = ParseOptions {
+            record_length: Some(20),
+            ..ParseOptions::default()
+        };
+        let lines = [
+            "02,,B,1,200331,2300,",
+            ",/                  ",
+            "16,175,100,0,,,text ",
+            "wrapped onto the nex",
+            "t one/              ",
+        ];
+
+        assert_eq!(
+            records(&options, &lines),
+            [
+                "02,,B,1,200331,2300,,/",
+                "16,175,100,0,,,text wrapped onto the next one/",
+            ]
+        );
+    }
+
+    #[test]
+    fn record_length_is_taken_from_a_padded_header() {
+        let content = [
+            "01,A,B,200331,2300,1,80,,2/",
+            "02,,B,1,200331,2300,,/",
+            "03,1,USD,010,500,,/",
+            "16,175,100,0,,,text that runs on past the end of the eighty character physical record/",
+            "49,600,3/",
+            "98,600,1,5/",
+            "99,600,1,7/",
+        ]
+        .map(|record| format!("{:1$}", record, record.len().div_ceil(80) * 80))
+        .concat();
+
+        let file = Bai2File::new(content).unwrap();
+        let transaction = &file.groups[0].accounts()[0].transactions()[0];
+
+        assert_eq!(file.physical_record_length, Some(80));
+        assert_eq!(
+            transaction.text(),
+            "text that runs on past the end of the eighty character physical record"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::availability::{AvailabilityDate, Calendar};
+use crate::file::account::Account;
+use crate::file::direction::Direction;
+use crate::Bai2File;
+
+/// The funds becoming available on each date, per currency, according to the
+/// distributed availability reported on account summaries.
+///
+/// Availability is counted in calendar days from the as-of date of the group,
+/// or in business days when a `Calendar` is given.
+/// Total credits (100) already include the other credit summaries, so when an
+/// account reports availability on its total credits the others are ignored.
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct AvailabilitySchedule {
+    entries: Vec<ScheduledFunds>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ScheduledFunds {
+    pub amount: i64,
+    pub currency_code: String,
+    pub date: NaiveDate,
+}
+
+impl AvailabilitySchedule {
+    pub fn new(file: &Bai2File) -> AvailabilitySchedule {
+        AvailabilitySchedule::with_calendar(file, &AvailabilityDate::new())
+    }
+
+    /// Count availability in the business days of the calendar, so that funds
+    /// available in one day from a Friday are scheduled for Monday.
+    pub fn with_calendar(file: &Bai2File, calendar: &dyn Calendar) -> AvailabilitySchedule {
+        let mut totals: BTreeMap<(String, NaiveDate), i64> = BTreeMap::new();
+
+        for group in &file.groups {
+            let Some(as_of_date) = group.as_of_date() else {
+                continue;
+            };
+
+            for account in group.accounts() {
+                for (days, amount) in account_availability(account) {
+                    let Some(date) = calendar.add_business_days(as_of_date, days) else {
+                        continue;
+                    };
+                    let key = (account.currency_code().to_string(), date);
+                    *totals.entry(key).or_insert(0) += amount;
+                }
+            }
+        }
+
+        let entries = totals
+            .into_iter()
+            .map(|((currency_code, date), amount)| ScheduledFunds {
+                amount,
+                currency_code,
+                date,
+            })
+            .collect();
+
+        AvailabilitySchedule { entries }
+    }
+
+    /// The scheduled funds, ordered by currency and then by date.
+    pub fn entries(&self) -> &[ScheduledFunds] {
+        &self.entries
+    }
+
+    /// The total becoming available in a currency on a date.
+    pub fn amount_on(&self, currency_code: &str, date: NaiveDate) -> i64 {
+        self.entries
+            .iter()
+            .find(|e| e.currency_code == currency_code && e.date == date)
+            .map_or(0, |e| e.amount)
+    }
+}
+
+fn account_availability(account: &Account) -> Vec<(u16, i64)> {
+    let credits = account
+        .amounts()
+        .iter()
+        .filter(|a| a.amount_type().direction() == Direction::Credit)
+        .filter(|a| !a.availability().is_empty());
+
+    let total_credits = credits.clone().find(|a| a.amount_type().code() == 100);
+
+    match total_credits {
+        Some(total) => total.availability().iter().map(|(d, a)| (*d, *a)).collect(),
+        None => credits
+            .flat_map(|a| a.availability().iter().map(|(d, a)| (*d, *a)))
+            .collect(),
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::NaiveDate;
+
+use crate::file::group::{AsOfDateModifier, Group};
+use crate::file::time::Time;
+
+/// A group in its place among the reports of the day it's for, for showing
+/// intraday reports in order, e.g. "as of 10:30" before "as of 14:00".
+///
+/// Reports are for the same day when they have the same originator, ultimate
+/// receiver, and as-of date, as in `DayView`. They're ordered by as-of time,
+/// with the end of the day last, and a report without an as-of time after
+/// those that have one. Interim reports come before final ones at the same
+/// time, and reports that are otherwise equal stay in the order given.
+#[derive(Clone, Copy, Debug)]
+pub struct Snapshot<'a> {
+    pub group: &'a Group,
+    /// The number of the report among the reports of its day, from 1 for the
+    /// earliest.
+    pub sequence: usize,
+}
+
+impl<'a> Snapshot<'a> {
+    /// Put the groups in snapshot order, e.g. the groups of several files
+    /// received through the day.
+    pub fn order(groups: impl IntoIterator<Item = &'a Group>) -> Vec<Snapshot<'a>> {
+        let mut groups: Vec<&Group> = groups.into_iter().collect();
+        groups.sort_by_key(|group| {
+            (
+                group.originator(),
+                group.ultimate_receiver(),
+                group.as_of_date(),
+                group.as_of_time().is_none(),
+                group.as_of_time(),
+                group.as_of_date_modifier().is_some_and(is_final),
+            )
+        });
+
+        let mut snapshots: Vec<Snapshot> = Vec::with_capacity(groups.len());
+        for group in groups {
+            let sequence = match snapshots.last() {
+                Some(last) if last.group.same_group(group) => last.sequence + 1,
+                _ => 1,
+            };
+            snapshots.push(Snapshot { group, sequence });
+        }
+
+        snapshots
+    }
+
+    pub fn as_of_date(&self) -> Option<NaiveDate> {
+        self.group.as_of_date()
+    }
+
+    /// A label for the report, e.g. "as of 10:30" or "as of end of day".
+    pub fn label(&self) -> String {
+        match self.group.as_of_time() {
+            Some(Time::At(time)) => format!("as of {}", time.format("%H:%M")),
+            Some(Time::EndOfDay | Time::Raw(_)) => "as of end of day".to_string(),
+            None => "as of unknown time".to_string(),
+        }
+    }
+
+    /// Whether this is the final report of the day rather than an interim one,
+    /// as given by the group's as-of date modifier.
+    pub fn is_final(&self) -> bool {
+        self.group.as_of_date_modifier().is_some_and(is_final)
+    }
+}
+
+fn is_final(modifier: &AsOfDateModifier) -> bool {
+    matches!(
+        modifier,
+        AsOfDateModifier::FinalPreviousDayData | AsOfDateModifier::FinalSameDayData
+    )
+}
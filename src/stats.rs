@@ -0,0 +1,148 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::file::util::parse_int;
+use crate::scanner::node::Node;
+
+/// How the records of a file were laid out across lines, for diagnosing banks
+/// that split records into unusual numbers of continuations.
+///
+/// A record is a logical record, made up of the line with its record code and
+/// any continuation (88) lines that follow it. Lengths are the total number of
+/// characters across those lines.
+///
+/// The record counts in trailers count physical records, i.e. lines, so
+/// `lines` is the count to reconcile against them rather than `records`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FileStats {
+    /// The record counts of each account, in the order they appear in the
+    /// file, which is also the order of their sequence numbers.
+    pub accounts: Vec<SectionStats>,
+    /// The number of continuation lines in the file.
+    pub continuations: usize,
+    /// The record counts of each group, in the order they appear in the file.
+    pub groups: Vec<SectionStats>,
+    /// The number of lines in the file, counting continuations.
+    pub lines: usize,
+    /// The record that's longest across all of its lines.
+    pub longest_record: Option<RecordStats>,
+    /// The record that has the most continuations.
+    pub most_continued_record: Option<RecordStats>,
+    /// The records of each record code, e.g. `"16"` for transaction details.
+    pub record_types: BTreeMap<String, RecordTypeStats>,
+    /// The number of logical records in the file.
+    pub records: usize,
+    /// The number of records the file trailer reports.
+    pub reported_records: Option<usize>,
+}
+
+/// The records of a group or an account, from its header to its trailer.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SectionStats {
+    /// The line number of the header.
+    pub line: usize,
+    /// The number of lines, counting continuations.
+    pub lines: usize,
+    /// The number of logical records.
+    pub records: usize,
+    /// The number of records the trailer reports.
+    pub reported_records: Option<usize>,
+}
+
+/// The layout of a single record.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RecordStats {
+    pub continuations: usize,
+    pub length: usize,
+    pub line: usize,
+    pub record_type: String,
+}
+
+/// The layout of all of the records with the same record code.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct RecordTypeStats {
+    pub continuations: usize,
+    pub length: usize,
+    pub max_continuations: usize,
+    pub max_length: usize,
+    pub records: usize,
+}
+
+/// Collect the stats of the file with the given header and everything under it.
+pub(crate) fn collect(file: &Node) -> FileStats {
+    let mut stats = FileStats::default();
+    visit(file, &mut stats);
+
+    stats.reported_records = reported_records(file, 3);
+    for group in file.children() {
+        stats.groups.push(section(&group, 3));
+        stats
+            .accounts
+            .extend(group.children().map(|account| section(&account, 2)));
+    }
+
+    stats
+}
+
+fn section(header: &Node, count_field: usize) -> SectionStats {
+    let mut stats = FileStats::default();
+    visit(header, &mut stats);
+
+    SectionStats {
+        line: header.line_number(),
+        lines: stats.lines,
+        records: stats.records,
+        reported_records: reported_records(header, count_field),
+    }
+}
+
+/// The record count in the field of the header's trailer.
+fn reported_records(header: &Node, count_field: usize) -> Option<usize> {
+    parse_int(header.sibling()?.fields().get(count_field)?)
+}
+
+fn visit(node: &Node, stats: &mut FileStats) {
+    let record = RecordStats {
+        continuations: node.continuations().count(),
+        length: node.line().len() + node.continuations().map(|c| c.line().len()).sum::<usize>(),
+        line: node.line_number(),
+        record_type: node.line().get(0..2).unwrap_or_default().to_string(),
+    };
+
+    stats.records += 1;
+    stats.lines += 1 + record.continuations;
+    stats.continuations += record.continuations;
+
+    let type_stats = stats
+        .record_types
+        .entry(record.record_type.clone())
+        .or_default();
+    type_stats.records += 1;
+    type_stats.continuations += record.continuations;
+    type_stats.length += record.length;
+    type_stats.max_continuations = type_stats.max_continuations.max(record.continuations);
+    type_stats.max_length = type_stats.max_length.max(record.length);
+
+    if stats
+        .longest_record
+        .as_ref()
+        .is_none_or(|r| record.length > r.length)
+    {
+        stats.longest_record = Some(record.clone());
+    }
+    if record.continuations > 0
+        && stats
+            .most_continued_record
+            .as_ref()
+            .is_none_or(|r| record.continuations > r.continuations)
+    {
+        stats.most_continued_record = Some(record);
+    }
+
+    for child in node.children() {
+        visit(&child, stats);
+    }
+    if let Some(trailer) = node.sibling() {
+        visit(&trailer, stats);
+    }
+}
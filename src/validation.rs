@@ -0,0 +1,428 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::error::Bai2Error;
+use crate::file::account::Account;
+use crate::file::group::{Group, SkippedGroup};
+use crate::file::util::parse_int;
+use crate::options::ParseOptions;
+use crate::scanner::node::Node;
+
+/// A check on a file that can fail without stopping the file from being
+/// parsed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationCheck {
+    /// The control total in an account trailer (49)
+    AccountControlTotal,
+    /// The number of records in an account trailer (49)
+    AccountRecordCount,
+    /// The control total in a group trailer (98)
+    GroupControlTotal,
+    /// The number of accounts in a group trailer (98)
+    GroupAccountCount,
+    /// The number of records in a group trailer (98)
+    GroupRecordCount,
+    /// The control total in the file trailer (99)
+    FileControlTotal,
+    /// The number of groups in the file trailer (99)
+    FileGroupCount,
+    /// The number of records in the file trailer (99)
+    FileRecordCount,
+}
+
+impl ValidationCheck {
+    pub const ALL: [ValidationCheck; 8] = [
+        ValidationCheck::AccountControlTotal,
+        ValidationCheck::AccountRecordCount,
+        ValidationCheck::GroupControlTotal,
+        ValidationCheck::GroupAccountCount,
+        ValidationCheck::GroupRecordCount,
+        ValidationCheck::FileControlTotal,
+        ValidationCheck::FileGroupCount,
+        ValidationCheck::FileRecordCount,
+    ];
+
+    /// The name of the check, as it's serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValidationCheck::AccountControlTotal => "account_control_total",
+            ValidationCheck::AccountRecordCount => "account_record_count",
+            ValidationCheck::GroupControlTotal => "group_control_total",
+            ValidationCheck::GroupAccountCount => "group_account_count",
+            ValidationCheck::GroupRecordCount => "group_record_count",
+            ValidationCheck::FileControlTotal => "file_control_total",
+            ValidationCheck::FileGroupCount => "file_group_count",
+            ValidationCheck::FileRecordCount => "file_record_count",
+        }
+    }
+
+    /// A short description of the value checked, for messages.
+    pub fn description(&self) -> &'static str {
+        match self {
+            ValidationCheck::AccountControlTotal => "account control total",
+            ValidationCheck::AccountRecordCount => "account record count",
+            ValidationCheck::GroupControlTotal => "group control total",
+            ValidationCheck::GroupAccountCount => "group account count",
+            ValidationCheck::GroupRecordCount => "group record count",
+            ValidationCheck::FileControlTotal => "file control total",
+            ValidationCheck::FileGroupCount => "file group count",
+            ValidationCheck::FileRecordCount => "file record count",
+        }
+    }
+
+    /// The record code of the trailer the check is made against.
+    pub fn record_type(&self) -> &'static str {
+        match self {
+            ValidationCheck::AccountControlTotal | ValidationCheck::AccountRecordCount => "49",
+            ValidationCheck::GroupControlTotal
+            | ValidationCheck::GroupAccountCount
+            | ValidationCheck::GroupRecordCount => "98",
+            ValidationCheck::FileControlTotal
+            | ValidationCheck::FileGroupCount
+            | ValidationCheck::FileRecordCount => "99",
+        }
+    }
+
+    /// The field of the trailer the check is made against, counting the record
+    /// code as field 1.
+    pub fn field(&self) -> usize {
+        match self {
+            ValidationCheck::AccountControlTotal
+            | ValidationCheck::GroupControlTotal
+            | ValidationCheck::FileControlTotal => 2,
+            ValidationCheck::AccountRecordCount
+            | ValidationCheck::GroupAccountCount
+            | ValidationCheck::FileGroupCount => 3,
+            ValidationCheck::GroupRecordCount | ValidationCheck::FileRecordCount => 4,
+        }
+    }
+}
+
+impl FromStr for ValidationCheck {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<ValidationCheck, String> {
+        ValidationCheck::ALL
+            .into_iter()
+            .find(|c| c.name() == name)
+            .ok_or_else(|| format!("unknown validation check `{}`", name))
+    }
+}
+
+/// A value reported in the file that doesn't match what was actually found.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ValidationFailure {
+    pub check: ValidationCheck,
+    pub expected: i64,
+    pub found: i64,
+    pub line: usize,
+}
+
+/// What to do when a validation check fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Severity {
+    /// Fail the parse with `Bai2Error::ValidationFailed`
+    Error,
+    /// List the failure in `validation_failures`
+    #[default]
+    Warning,
+    /// Don't report the failure at all
+    Ignore,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Severity, String> {
+        match name {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::Warning),
+            "ignore" => Ok(Severity::Ignore),
+            _ => Err(format!(
+                "unknown severity `{}`, expected error, warning, or ignore",
+                name
+            )),
+        }
+    }
+}
+
+/// The severity of each validation check. Checks that haven't been set are
+/// warnings.
+///
+/// ```
+/// use bai2::{Severity, ValidationCheck, ValidationConfig};
+///
+/// // tolerate a bank that miscounts records, but not one whose totals are off
+/// let config = ValidationConfig::new()
+///     .set(ValidationCheck::AccountRecordCount, Severity::Ignore)
+///     .set(ValidationCheck::FileControlTotal, Severity::Error);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ValidationConfig {
+    severities: BTreeMap<ValidationCheck, Severity>,
+}
+
+impl ValidationConfig {
+    pub fn new() -> ValidationConfig {
+        ValidationConfig::default()
+    }
+
+    /// Give every check the same severity.
+    pub fn all(severity: Severity) -> ValidationConfig {
+        ValidationCheck::ALL
+            .into_iter()
+            .fold(ValidationConfig::new(), |config, check| {
+                config.set(check, severity)
+            })
+    }
+
+    pub fn set(mut self, check: ValidationCheck, severity: Severity) -> ValidationConfig {
+        self.severities.insert(check, severity);
+        self
+    }
+
+    pub fn severity(&self, check: ValidationCheck) -> Severity {
+        self.severities.get(&check).copied().unwrap_or_default()
+    }
+}
+
+/// Collects the failures of a file, applying the severity of each check as it
+/// goes and giving up once there are more errors than allowed.
+struct Failures<'a> {
+    config: &'a ValidationConfig,
+    errors: usize,
+    failures: Vec<ValidationFailure>,
+    max_errors: Option<usize>,
+}
+
+impl Failures<'_> {
+    fn push(&mut self, failure: ValidationFailure) -> Result<(), Bai2Error> {
+        match self.config.severity(failure.check) {
+            Severity::Error => Err(Bai2Error::ValidationFailed { failure }),
+            Severity::Ignore => Ok(()),
+            Severity::Warning => {
+                self.errors += 1;
+                if let Some(max) = self.max_errors.filter(|&max| self.errors > max) {
+                    return Err(Bai2Error::TooManyErrors {
+                        line: failure.line,
+                        max,
+                    });
+                }
+
+                self.failures.push(failure);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Check the counts and control totals in every trailer of the file against
+/// the records that were scanned and the amounts that were parsed from them.
+/// Record counts include the header and trailer themselves and every
+/// continuation (88) in between, as the spec requires. Control totals are the
+/// sum of every amount in the account identifiers and transaction details.
+///
+/// Amounts in different currencies can't be added up, so the control total of
+/// a group or file with accounts in more than one currency isn't checked. The
+/// totals of each of their accounts still are.
+///
+/// Failures are dropped or returned as an error according to the severity of
+/// their check, and `errors` is the number of errors already tolerated while
+/// scanning, which counts towards the maximum.
+///
+/// The groups in `skipped_groups` couldn't be parsed, so they aren't in
+/// `groups` and their trailers aren't checked. Neither is the file's control
+/// total, since it includes them. The same goes for the accounts skipped in
+/// each group, and the control totals of their group and the file.
+pub(crate) fn check_trailers(
+    file: &Node,
+    groups: &[Group],
+    skipped_groups: &[SkippedGroup],
+    options: &ParseOptions,
+    errors: usize,
+) -> Result<Vec<ValidationFailure>, Bai2Error> {
+    let mut failures = Failures {
+        config: &options.validation,
+        errors,
+        failures: Vec::new(),
+        max_errors: options.max_errors,
+    };
+    let mut file_total = Some(0);
+    let mut file_currencies = BTreeSet::new();
+    let mut skipped_accounts = false;
+
+    let group_nodes = file.children().enumerate().filter(|(i, _)| {
+        !skipped_groups
+            .iter()
+            .any(|skipped| skipped.sequence_number == i + 1)
+    });
+    for ((_, group_node), group) in group_nodes.zip(groups) {
+        let mut group_total = Some(0);
+        let mut group_currencies = BTreeSet::new();
+
+        let account_nodes = group_node.children().filter(|n| {
+            !group
+                .skipped_accounts()
+                .iter()
+                .any(|skipped| skipped.line == n.line_number())
+        });
+        for (account_node, account) in account_nodes.zip(group.accounts()) {
+            let account_total = account_total(account);
+            group_total = checked_add(group_total, account_total);
+            group_currencies.insert(account.currency());
+
+            if let Some(account_total) = account_total {
+                check_trailer(
+                    &account_node,
+                    ValidationCheck::AccountControlTotal,
+                    account_total,
+                    &mut failures,
+                )?;
+            }
+            check_trailer(
+                &account_node,
+                ValidationCheck::AccountRecordCount,
+                record_count(&account_node),
+                &mut failures,
+            )?;
+        }
+
+        file_total = checked_add(file_total, group_total);
+        let accounts = group_node.children().count() as i64;
+        skipped_accounts |= !group.skipped_accounts().is_empty();
+        if let Some(group_total) = group_total {
+            if group_currencies.len() <= 1 && group.skipped_accounts().is_empty() {
+                check_trailer(
+                    &group_node,
+                    ValidationCheck::GroupControlTotal,
+                    group_total,
+                    &mut failures,
+                )?;
+            }
+        }
+        check_trailer(
+            &group_node,
+            ValidationCheck::GroupAccountCount,
+            accounts,
+            &mut failures,
+        )?;
+        check_trailer(
+            &group_node,
+            ValidationCheck::GroupRecordCount,
+            record_count(&group_node),
+            &mut failures,
+        )?;
+        file_currencies.extend(group_currencies);
+    }
+
+    let groups = file.children().count() as i64;
+    if let Some(file_total) = file_total {
+        if file_currencies.len() <= 1 && skipped_groups.is_empty() && !skipped_accounts {
+            check_trailer(
+                file,
+                ValidationCheck::FileControlTotal,
+                file_total,
+                &mut failures,
+            )?;
+        }
+    }
+    check_trailer(file, ValidationCheck::FileGroupCount, groups, &mut failures)?;
+    check_trailer(
+        file,
+        ValidationCheck::FileRecordCount,
+        record_count(file),
+        &mut failures,
+    )?;
+
+    Ok(failures.failures)
+}
+
+/// Compare a value in the node's trailer to the value found. Trailers that are
+/// missing or that don't have a numeric value are skipped, since parsing
+/// reports those.
+fn check_trailer(
+    node: &Node,
+    check: ValidationCheck,
+    found: i64,
+    failures: &mut Failures,
+) -> Result<(), Bai2Error> {
+    let Some(trailer) = node.sibling() else {
+        return Ok(());
+    };
+    let index = check.field() - 1;
+    let fields = trailer.fields();
+    let Some(expected) = fields.get(index).and_then(|f| parse_int::<i64>(f)) else {
+        return Ok(());
+    };
+
+    if expected == found {
+        return Ok(());
+    }
+
+    failures.push(ValidationFailure {
+        check,
+        expected,
+        found,
+        line: trailer.field_line(index),
+    })
+}
+
+/// The sum of the account's summary and transaction amounts, or `None` when
+/// it's too large to hold, in which case its control total isn't checked.
+fn account_total(account: &Account) -> Option<i64> {
+    let amounts = account
+        .amounts()
+        .iter()
+        .filter_map(|a| a.amount())
+        .map(Some);
+    let transactions = account
+        .transactions()
+        .iter()
+        .filter_map(|t| t.amount())
+        .map(|amount| i64::try_from(amount).ok());
+
+    amounts
+        .chain(transactions)
+        .try_fold(0i64, |total, amount| total.checked_add(amount?))
+}
+
+fn checked_add(total: Option<i64>, amount: Option<i64>) -> Option<i64> {
+    total?.checked_add(amount?)
+}
+
+fn record_count(node: &Node) -> i64 {
+    let own = 1 + node.continuations().count() as i64;
+    let children: i64 = node.children().map(|c| record_count(&c)).sum();
+    let trailer = match node.sibling() {
+        Some(trailer) => record_count(&trailer),
+        None => 0,
+    };
+
+    own + children + trailer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Bai2File;
+
+    #[test]
+    fn control_totals_too_large_to_hold_are_skipped() {
+        let content = "01,A,B,200331,2300,1,,,2/
+02,,B,1,200331,2300,,/
+03,1,USD,010,1,,/
+16,175,9000000000000000000,,,/
+16,175,9000000000000000000,,,/
+49,1,5/
+98,1,1,7/
+99,1,1,9/";
+
+        let file = Bai2File::new(content.to_string()).unwrap();
+
+        assert!(file
+            .validation_failures
+            .iter()
+            .all(|f| !f.check.name().ends_with("control_total")));
+    }
+}
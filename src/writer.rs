@@ -0,0 +1,492 @@
+use crate::file::account::Account;
+use crate::file::currency::Currency;
+use crate::file::group::Group;
+use crate::file::opaque_record::OpaqueRecord;
+use crate::file::original_format::{fingerprint, OriginalFormat};
+use crate::file::transaction::Transaction;
+use crate::file::util::{format_date, format_int, format_time};
+use crate::file::version::Version;
+use crate::options::WriteOptions;
+use crate::Bai2File;
+
+/// Write a file out in the BAI2 format. The control totals and record counts
+/// in the trailers are computed from what was written rather than copied from
+/// the file that was parsed.
+///
+/// When the file has a physical record length, records that are longer are
+/// continued onto continuation (88) records between fields, or after one of
+/// the commas in a transaction's text, which is kept at the end of the line.
+/// A single field that's longer still is left whole.
+///
+/// Records with unrecognized codes that were kept from the file are written
+/// back exactly as they were, in the same place among the records around
+/// them.
+///
+/// Records parsed with `ParseOptions::preserve_formatting` that haven't
+/// changed are written back as they were too, trailers included, whatever the
+/// options.
+pub(crate) fn write(file: &Bai2File, options: &WriteOptions) -> String {
+    let mut writer = Writer {
+        lines: Vec::new(),
+        options,
+        record_length: file
+            .physical_record_length
+            .map(usize::from)
+            .filter(|&length| length > 0),
+        version: file.version(),
+    };
+    writer.write_file(file);
+
+    let (line_ending, final_line_ending) = file
+        .original
+        .as_ref()
+        .map_or(("\n", true), |o| (o.line_ending(), o.final_line_ending()));
+    let mut output = writer.lines.join(line_ending);
+    if final_line_ending {
+        output.push_str(line_ending);
+    }
+    output
+}
+
+#[derive(Debug)]
+struct Writer<'a> {
+    lines: Vec<String>,
+    options: &'a WriteOptions,
+    record_length: Option<usize>,
+    version: Option<Version>,
+}
+
+impl Writer<'_> {
+    fn record(&mut self, fields: Vec<String>) {
+        self.push_record(&fields, true);
+    }
+
+    /// Write the fields as a record, ended with a slash when `terminated` is
+    /// set, across as many physical records as the record length requires.
+    /// The last field of a record that isn't terminated is its text.
+    fn push_record(&mut self, fields: &[String], terminated: bool) {
+        let Some(length) = self.record_length else {
+            let mut line = fields.join(",");
+            if terminated {
+                line.push('/');
+            }
+            self.lines.push(line);
+            return;
+        };
+
+        // The text can hold commas of its own, so it can be wrapped at those
+        let text_start = fields.len() - usize::from(!terminated);
+        let pieces = fields[..text_start]
+            .iter()
+            .map(String::as_str)
+            .chain(fields[text_start..].iter().flat_map(|text| text.split(',')));
+
+        let mut line = String::new();
+        let mut width = 0;
+        for (i, field) in pieces.enumerate() {
+            let field_width = field.chars().count();
+            let terminator = usize::from(terminated && i == fields.len() - 1);
+            if i > 0 && width > 2 && width + 1 + field_width + terminator > length {
+                // The comma is part of the text, so it stays on the line
+                if i > text_start {
+                    line.push(',');
+                }
+                self.push_line(std::mem::replace(&mut line, "88".to_string()), length);
+                width = 2;
+            }
+
+            if i > 0 {
+                line.push(',');
+                width += 1;
+            }
+            line.push_str(field);
+            width += field_width;
+        }
+
+        if terminated {
+            line.push('/');
+        }
+        self.push_line(line, length);
+    }
+
+    /// Write the lines of the unrecognized records whose position matches,
+    /// as they appeared in the file.
+    fn write_others(&mut self, records: &[OpaqueRecord], matches: impl Fn(usize) -> bool) {
+        for record in records.iter().filter(|r| matches(r.position)) {
+            for line in record.raw.lines() {
+                match self.record_length {
+                    Some(length) => self.push_line(line.to_string(), length),
+                    None => self.lines.push(line.to_string()),
+                }
+            }
+        }
+    }
+
+    fn push_line(&mut self, line: String, length: usize) {
+        if self.options.pad_records {
+            self.lines.push(format!("{:<length$}", line));
+        } else {
+            self.lines.push(line);
+        }
+    }
+
+    /// Write the record's original lines when these are still the fields it
+    /// was parsed with, or the fields otherwise. Returns whether the original
+    /// lines were written.
+    fn write_record(
+        &mut self,
+        original: Option<&OriginalFormat>,
+        fields: &[String],
+        terminated: bool,
+    ) -> bool {
+        match original.and_then(|o| o.record(fields)) {
+            Some(lines) => {
+                self.lines.extend(lines.lines().map(str::to_string));
+                true
+            }
+            None => {
+                self.push_record(fields, terminated);
+                false
+            }
+        }
+    }
+
+    /// Write the trailer's original lines when nothing it counts has changed,
+    /// or the fields otherwise. Returns whether the original lines were
+    /// written.
+    fn write_trailer(
+        &mut self,
+        original: Option<&OriginalFormat>,
+        unchanged: bool,
+        contents: impl FnOnce() -> u64,
+        fields: Vec<String>,
+    ) -> bool {
+        match original.and_then(|o| o.trailer(unchanged, contents)) {
+            Some(lines) => {
+                self.lines.extend(lines.lines().map(str::to_string));
+                true
+            }
+            None => {
+                self.record(fields);
+                false
+            }
+        }
+    }
+
+    fn write_file(&mut self, file: &Bai2File) {
+        let original = file.original.as_ref();
+        let mut unchanged = self.write_record(original, &file_header(file), true);
+
+        let mut total = 0;
+        for (i, group) in file.groups.iter().enumerate() {
+            self.write_others(&file.other_records, |position| position == i);
+            let (group_total, group_unchanged) = self.write_group(group);
+            total += group_total;
+            unchanged &= group_unchanged;
+        }
+        self.write_others(&file.other_records, |position| {
+            position >= file.groups.len()
+        });
+
+        let records = self.lines.len() + 1;
+        self.write_trailer(
+            original,
+            unchanged,
+            || file_contents(file),
+            vec![
+                "99".to_string(),
+                total.to_string(),
+                file.groups.len().to_string(),
+                records.to_string(),
+            ],
+        );
+    }
+
+    /// Write the group, returning its control total and whether it was
+    /// written exactly as it was parsed. Totals are kept as `i128` so that
+    /// adding up large amounts can't overflow.
+    fn write_group(&mut self, group: &Group) -> (i128, bool) {
+        let start = self.lines.len();
+        let original = group.original.as_ref();
+        let mut unchanged = self.write_record(original, &group_header(group, self.version), true);
+
+        let mut total = 0;
+        for (i, account) in group.accounts.iter().enumerate() {
+            self.write_others(&group.other_records, |position| position == i);
+            let (account_total, account_unchanged) =
+                self.write_account(account, &group.currency_code);
+            total += account_total;
+            unchanged &= account_unchanged;
+        }
+        self.write_others(&group.other_records, |position| {
+            position >= group.accounts.len()
+        });
+
+        let records = self.lines.len() - start + 1;
+        let version = self.version;
+        unchanged &= self.write_trailer(
+            original,
+            unchanged,
+            || group_contents(group, version),
+            vec![
+                "98".to_string(),
+                total.to_string(),
+                group.accounts.len().to_string(),
+                records.to_string(),
+            ],
+        );
+
+        (total, unchanged)
+    }
+
+    /// Write the account, returning its control total and whether it was
+    /// written exactly as it was parsed.
+    fn write_account(&mut self, account: &Account, group_currency: &Currency) -> (i128, bool) {
+        let start = self.lines.len();
+        let original = account.original.as_ref();
+        let currency_code =
+            if self.options.omit_redundant_currency && account.currency_code == *group_currency {
+                String::new()
+            } else {
+                account.currency_code.to_string()
+            };
+        let mut unchanged = self.write_record(
+            original,
+            &account_header(account, currency_code, self.version),
+            true,
+        );
+
+        let mut total = account
+            .amounts
+            .iter()
+            .map(|amount| i128::from(amount.amount.unwrap_or(0)))
+            .sum::<i128>();
+        for (i, transaction) in account.transactions.iter().enumerate() {
+            self.write_others(&account.other_records, |position| position == i);
+            total += i128::from(transaction.amount.unwrap_or(0));
+            unchanged &= self.write_transaction(transaction);
+        }
+        self.write_others(&account.other_records, |position| {
+            position >= account.transactions.len()
+        });
+
+        let records = self.lines.len() - start + 1;
+        let version = self.version;
+        unchanged &= self.write_trailer(
+            original,
+            unchanged,
+            || account_contents(account, version),
+            vec!["49".to_string(), total.to_string(), records.to_string()],
+        );
+
+        (total, unchanged)
+    }
+
+    /// Write the transaction, returning whether it was written exactly as it
+    /// was parsed.
+    fn write_transaction(&mut self, transaction: &Transaction) -> bool {
+        let (fields, terminated) = transaction_detail(transaction, self.version);
+        self.write_record(transaction.original.as_ref(), &fields, terminated)
+    }
+}
+
+pub(crate) fn file_header(file: &Bai2File) -> Vec<String> {
+    let version = file.version();
+    vec![
+        "01".to_string(),
+        file.sender.clone(),
+        file.receiver.clone(),
+        format_date(file.creation_date, version),
+        format_time(file.creation_time, version),
+        file.file_id.clone(),
+        format_int(file.physical_record_length),
+        format_int(file.block_size),
+        format_int(file.version_number),
+    ]
+}
+
+pub(crate) fn group_header(group: &Group, version: Option<Version>) -> Vec<String> {
+    vec![
+        "02".to_string(),
+        group.ultimate_receiver.clone(),
+        group.originator.clone(),
+        group.status.as_code().to_string(),
+        format_date(group.as_of_date, version),
+        format_time(group.as_of_time, version),
+        group.currency_code.to_string(),
+        group
+            .as_of_date_modifier
+            .as_ref()
+            .map(|m| m.as_code().to_string())
+            .unwrap_or_default(),
+    ]
+}
+
+/// A hash of the fields of every record in the file, for telling whether its
+/// trailer still holds. See `OriginalFormat`.
+pub(crate) fn file_contents(file: &Bai2File) -> u64 {
+    let version = file.version();
+    fingerprint((
+        file_header(file),
+        file.groups
+            .iter()
+            .map(|group| group_contents(group, version))
+            .collect::<Vec<_>>(),
+        others(&file.other_records),
+    ))
+}
+
+pub(crate) fn group_contents(group: &Group, version: Option<Version>) -> u64 {
+    fingerprint((
+        group_header(group, version),
+        group
+            .accounts
+            .iter()
+            .map(|account| account_contents(account, version))
+            .collect::<Vec<_>>(),
+        others(&group.other_records),
+    ))
+}
+
+pub(crate) fn account_contents(account: &Account, version: Option<Version>) -> u64 {
+    fingerprint((
+        account_header(account, account.currency_code.to_string(), version),
+        account
+            .transactions
+            .iter()
+            .map(|transaction| transaction_detail(transaction, version))
+            .collect::<Vec<_>>(),
+        others(&account.other_records),
+    ))
+}
+
+fn others(records: &[OpaqueRecord]) -> Vec<(usize, &str)> {
+    records
+        .iter()
+        .map(|r| (r.position, r.raw.as_str()))
+        .collect()
+}
+
+/// The fields of the account identifier, with the currency code as given so
+/// that it can be left off.
+pub(crate) fn account_header(
+    account: &Account,
+    currency_code: String,
+    version: Option<Version>,
+) -> Vec<String> {
+    let mut fields = vec![
+        "03".to_string(),
+        account.customer_account_number.clone(),
+        currency_code,
+    ];
+
+    if account.amounts.is_empty() {
+        fields.extend(vec![String::new(); 4]);
+    }
+
+    for amount in &account.amounts {
+        fields.push(format!("{:03}", amount.amount_type.code()));
+        fields.push(format_int(amount.amount));
+        fields.push(format_int(amount.item_count));
+        fields.extend(amount.funds_type.fields(
+            amount.value_date,
+            amount.value_time,
+            &amount.availability,
+            version,
+        ));
+    }
+
+    fields
+}
+
+/// The fields of the transaction detail, and whether it's terminated with a
+/// slash.
+pub(crate) fn transaction_detail(
+    transaction: &Transaction,
+    version: Option<Version>,
+) -> (Vec<String>, bool) {
+    let mut fields = vec![
+        "16".to_string(),
+        format!("{:03}", transaction.transaction_type.code()),
+        format_int(transaction.amount),
+    ];
+    fields.extend(transaction.funds_type.fields(
+        transaction.value_date,
+        transaction.value_time,
+        &transaction.availability,
+        version,
+    ));
+    fields.push(transaction.bank_reference_number.clone());
+    fields.push(transaction.customer_reference_number.clone());
+
+    // The text runs to the end of the record, so it isn't terminated with a
+    // slash unless it's empty
+    if transaction.text.is_empty() {
+        (fields, true)
+    } else {
+        fields.push(transaction.text.clone());
+        (fields, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Bai2File;
+
+    #[test]
+    fn control_totals_larger_than_an_i64_are_written_in_full() {
+        let content = "01,A,B,200331,2300,1,,,2/
+02,,B,1,200331,2300,,/
+03,1,USD,010,1,,/
+16,175,9000000000000000000,,,/
+16,175,9000000000000000000,,,/
+49,1,5/
+98,1,1,7/
+99,1,1,9/";
+
+        let written = Bai2File::new(content.to_string()).unwrap().to_bai2();
+
+        assert!(
+            written.contains("49,18000000000000000001,4/"),
+            "{}",
+            written
+        );
+        assert!(
+            written.contains("99,18000000000000000001,1,8/"),
+            "{}",
+            written
+        );
+    }
+
+    #[test]
+    fn version_3_files_keep_four_digit_years_and_seconds() {
+        let content = "01,A,B,20200331,235959,1,,,3/
+02,,B,1,20200331,120030,,/
+03,1,USD,010,1,,/
+16,175,100,V,20200401,083015,,/
+49,101,3/
+98,101,1,5/
+99,101,1,7/";
+
+        let file = Bai2File::new(content.to_string()).unwrap();
+        let written = file.to_bai2();
+
+        assert!(
+            written.starts_with("01,A,B,20200331,235959,1,,,3/\n"),
+            "{}",
+            written
+        );
+        assert!(
+            written.contains("02,,B,1,20200331,120030,USD,/"),
+            "{}",
+            written
+        );
+        assert!(
+            written.contains("16,175,100,V,20200401,083015,,/"),
+            "{}",
+            written
+        );
+        let reread = Bai2File::new(written).unwrap();
+        assert_eq!(reread.to_bai2(), file.to_bai2());
+    }
+}